@@ -1,5 +1,5 @@
-use egui::{CursorIcon, Ui};
-use egui_json_tree::{JsonTree, render::DefaultRender};
+use egui::Ui;
+use egui_json_tree::{JsonTree, JsonTreeStyle};
 use serde_json::Value;
 
 use super::Show;
@@ -25,24 +25,16 @@ impl Show for CopyToClipboardExample {
         ui.add_space(10.0);
 
         JsonTree::new(self.title(), &self.value)
-            .on_render(|ui, context| {
-                context
-                    .render_default(ui)
-                    .on_hover_cursor(CursorIcon::ContextMenu)
-                    .context_menu(|ui| {
-                        let pointer = context.pointer().to_json_pointer_string();
-                        if !pointer.is_empty() && ui.button("Copy path").clicked() {
-                            println!("{pointer}");
-                            ui.ctx().copy_text(pointer);
-                        }
-
-                        if ui.button("Copy contents").clicked()
-                            && let Ok(pretty_str) = serde_json::to_string_pretty(context.value())
-                        {
-                            println!("{pretty_str}");
-                            ui.ctx().copy_text(pretty_str);
-                        }
-                    });
+            .style(JsonTreeStyle::new().context_menu(true))
+            .on_context_menu(|ui, context| {
+                // Append custom items after the built-in copy actions.
+                if ui.button("Print to console").clicked() {
+                    println!(
+                        "{}: {}",
+                        context.pointer.to_json_pointer_string(),
+                        context.value
+                    );
+                }
             })
             .show(ui);
     }
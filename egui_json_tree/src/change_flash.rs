@@ -0,0 +1,318 @@
+use std::{
+    collections::{HashMap, HashSet},
+    fmt::{self, Write as _},
+    hash::{DefaultHasher, Hash, Hasher},
+};
+
+use egui::{Color32, Id, Ui, collapsing_header::CollapsingState};
+
+use crate::{
+    node::JsonTreeRoot,
+    pointer::JsonPointerSegment,
+    value::{JsonTreeValue, ToJsonTreeValue},
+};
+
+/// The values of a tree that changed recently, whose backgrounds are flashed in [`JsonTreeVisuals::changed_color`](crate::JsonTreeVisuals::changed_color).
+#[derive(Default)]
+pub(crate) struct ChangeFlashes {
+    /// The time at which the value at each path Id last changed.
+    changed_at: HashMap<Id, f64>,
+    now: f64,
+    duration: f32,
+    color: Color32,
+}
+
+impl ChangeFlashes {
+    /// Compares the values of the tree that are shown with those of the previous frame, and records the time at which any of them changed.
+    /// The entries of collapsed arrays and objects are not visited.
+    ///
+    /// The values of the previous frame are stored against the tree Id within the Id of the `ui`, as for the collapsing state of each path,
+    /// so that trees with the same Id shown in different `Ui`s do not share them.
+    pub(crate) fn detect<'r, 'a: 'r, T: ToJsonTreeValue + ?Sized + 'a>(
+        ui: &Ui,
+        tree_id: Id,
+        roots: impl Iterator<Item = &'r JsonTreeRoot<'a, T>>,
+        make_persistent_id: &dyn Fn(&[JsonPointerSegment]) -> Id,
+        duration: f32,
+        color: Color32,
+    ) -> Self {
+        let is_expanded =
+            |path_id| CollapsingState::load(ui.ctx(), path_id).is_some_and(|state| state.is_open());
+        let mut shown_values = HashMap::new();
+        for root in roots {
+            let mut path_segments: Vec<_> = root
+                .document
+                .map(|(segment, _)| segment)
+                .into_iter()
+                .collect();
+            collect_shown_values(
+                root.value,
+                &mut path_segments,
+                None,
+                make_persistent_id,
+                &is_expanded,
+                &mut shown_values,
+            );
+        }
+
+        let now = ui.input(|i| i.time);
+        let changed_at = ui.ctx().data_mut(|d| {
+            let state = d.get_temp_mut_or_default::<ChangeFlashState>(ui.id().with(tree_id));
+            if let Some(previous) = &state.shown_values {
+                for path_id in changed_path_ids(previous, &shown_values) {
+                    state.changed_at.insert(path_id, now);
+                }
+            }
+            state
+                .changed_at
+                .retain(|_, changed_at| now - *changed_at < duration as f64);
+            state.shown_values = Some(shown_values);
+            state.changed_at.clone()
+        });
+
+        if !changed_at.is_empty() {
+            ui.ctx().request_repaint();
+        }
+
+        Self {
+            changed_at,
+            now,
+            duration,
+            color,
+        }
+    }
+
+    /// Returns the background color of the value at the given path, if it changed recently, fading out over time.
+    pub(crate) fn color(
+        &self,
+        make_persistent_id: &dyn Fn(&[JsonPointerSegment]) -> Id,
+        path_segments: &[JsonPointerSegment],
+    ) -> Option<Color32> {
+        if self.changed_at.is_empty() {
+            return None;
+        }
+        let changed_at = self.changed_at.get(&make_persistent_id(path_segments))?;
+        let remaining = 1.0 - (self.now - changed_at) as f32 / self.duration;
+        Some(self.color.gamma_multiply(remaining.clamp(0.0, 1.0)))
+    }
+}
+
+/// Stored in `egui`'s `IdTypeMap` against the tree Id within the Id of its `Ui`, to compare the values of a tree with those of the previous frame.
+#[derive(Clone, Default)]
+struct ChangeFlashState {
+    /// The values shown on the previous frame by their path Ids, or `None` if the tree has not been shown yet.
+    shown_values: Option<HashMap<Id, ShownValue>>,
+    changed_at: HashMap<Id, f64>,
+}
+
+/// Records every value that is shown, i.e. every value except the entries of collapsed arrays and objects, by its path Id.
+///
+/// The entries of the root are always recorded, as they are shown when a collapsed root is not abbreviated.
+fn collect_shown_values<'a, T: ToJsonTreeValue + ?Sized>(
+    value: &'a T,
+    path_segments: &mut Vec<JsonPointerSegment<'a>>,
+    parent_id: Option<Id>,
+    make_persistent_id: &dyn Fn(&[JsonPointerSegment]) -> Id,
+    is_expanded: &dyn Fn(Id) -> bool,
+    shown_values: &mut HashMap<Id, ShownValue>,
+) {
+    let path_id = make_persistent_id(path_segments);
+    let hash = if value.is_expandable() && !path_segments.is_empty() && !is_expanded(path_id) {
+        None
+    } else {
+        let mut hasher = DefaultHasher::new();
+        match value.to_json_tree_value() {
+            JsonTreeValue::Base(_, display_value, value_type) => {
+                value_type.hash(&mut hasher);
+                let _ = write!(HashWriter(&mut hasher), "{display_value}");
+            }
+            JsonTreeValue::Expandable(entries, expandable_type) => {
+                expandable_type.hash(&mut hasher);
+                for (property, elem) in entries {
+                    property.hash(&mut hasher);
+                    path_segments.push(property);
+                    collect_shown_values(
+                        elem,
+                        path_segments,
+                        Some(path_id),
+                        make_persistent_id,
+                        is_expanded,
+                        shown_values,
+                    );
+                    path_segments.pop();
+                }
+            }
+        }
+        Some(hasher.finish())
+    };
+    shown_values.insert(path_id, ShownValue { parent_id, hash });
+}
+
+/// Returns the path Ids of the values that changed since the previous frame, along with those of the arrays and objects that contain them.
+fn changed_path_ids(
+    previous: &HashMap<Id, ShownValue>,
+    shown_values: &HashMap<Id, ShownValue>,
+) -> HashSet<Id> {
+    let mut changed = HashSet::new();
+    for (path_id, value) in shown_values {
+        let is_changed = match previous.get(path_id) {
+            Some(previous_value) => {
+                matches!((previous_value.hash, value.hash), (Some(previous_hash), Some(hash)) if previous_hash != hash)
+            }
+            // The value was added, unless the array or object that contains it was collapsed on the previous frame.
+            None => value.parent_id.is_none_or(|parent_id| {
+                previous
+                    .get(&parent_id)
+                    .is_some_and(|parent| parent.hash.is_some())
+            }),
+        };
+        let mut ancestor_id = is_changed.then_some(*path_id);
+        while let Some(path_id) = ancestor_id {
+            if !changed.insert(path_id) {
+                break;
+            }
+            ancestor_id = shown_values.get(&path_id).and_then(|value| value.parent_id);
+        }
+    }
+    changed
+}
+
+/// A value shown on a frame, which is compared with the value at the same path Id on the next frame.
+#[derive(Clone)]
+struct ShownValue {
+    /// The path Id of the array or object that contains the value.
+    parent_id: Option<Id>,
+    /// The hash of the type and display value of a base value, or of the type and properties of an expanded array or object.
+    /// `None` for a collapsed array or object, whose entries are not shown.
+    hash: Option<u64>,
+}
+
+/// Hashes formatted text without allocating it.
+struct HashWriter<'a>(&'a mut DefaultHasher);
+
+impl fmt::Write for HashWriter<'_> {
+    fn write_str(&mut self, s: &str) -> fmt::Result {
+        self.0.write(s.as_bytes());
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+    use crate::pointer::JsonPointer;
+
+    fn shown_values(
+        value: &serde_json::Value,
+        expanded_pointers: &[&str],
+    ) -> HashMap<Id, ShownValue> {
+        let expanded_ids: HashSet<Id> = expanded_pointers
+            .iter()
+            .map(|pointer| Id::new(pointer.to_string()))
+            .collect();
+        let mut shown_values = HashMap::new();
+        collect_shown_values(
+            value,
+            &mut vec![],
+            None,
+            &pointer_id,
+            &|path_id| expanded_ids.contains(&path_id),
+            &mut shown_values,
+        );
+        shown_values
+    }
+
+    fn pointer_id(path_segments: &[JsonPointerSegment]) -> Id {
+        Id::new(JsonPointer(path_segments).to_json_pointer_string())
+    }
+
+    fn changed_when_expanded(
+        previous: (&serde_json::Value, &[&str]),
+        current: (&serde_json::Value, &[&str]),
+    ) -> Vec<String> {
+        let changed_ids = changed_path_ids(
+            &shown_values(previous.0, previous.1),
+            &shown_values(current.0, current.1),
+        );
+        let mut changed: Vec<_> = ["", "/a", "/a/0", "/a/1", "/b", "/c"]
+            .into_iter()
+            .filter(|pointer| changed_ids.contains(&Id::new(pointer.to_string())))
+            .map(str::to_string)
+            .collect();
+        changed.sort();
+        changed
+    }
+
+    fn changed(previous: &serde_json::Value, current: &serde_json::Value) -> Vec<String> {
+        changed_when_expanded((previous, &["/a"]), (current, &["/a"]))
+    }
+
+    #[test]
+    fn trees_with_the_same_id_in_different_uis_do_not_share_changes() {
+        let ctx = egui::Context::default();
+        let changed_counts = |values: [&serde_json::Value; 2]| {
+            let mut counts = vec![];
+            let _ = ctx.run_ui(Default::default(), |ui| {
+                for (scope, value) in values.into_iter().enumerate() {
+                    ui.push_id(scope, |ui| {
+                        let roots = [JsonTreeRoot {
+                            value,
+                            document: None,
+                        }];
+                        let flashes = ChangeFlashes::detect(
+                            ui,
+                            Id::new("tree"),
+                            roots.iter(),
+                            &pointer_id,
+                            1.0,
+                            Color32::RED,
+                        );
+                        counts.push(flashes.changed_at.len());
+                    });
+                }
+            });
+            counts
+        };
+
+        assert_eq!(changed_counts([&json!(1), &json!(1)]), [0, 0]);
+        assert_eq!(changed_counts([&json!(2), &json!(1)]), [1, 0]);
+    }
+
+    #[test]
+    fn changes_are_detected_per_pointer_including_ancestors() {
+        let previous = json!({ "a": [1, "x"], "b": null });
+
+        assert!(changed(&previous, &previous).is_empty());
+        assert_eq!(
+            changed(&previous, &json!({ "a": [1, "y"], "b": null })),
+            ["", "/a", "/a/1"]
+        );
+        assert_eq!(
+            changed(&previous, &json!({ "a": [1, "x"], "b": "null" })),
+            ["", "/b"]
+        );
+        assert_eq!(
+            changed(&previous, &json!({ "a": [1], "b": null })),
+            ["", "/a"]
+        );
+        assert_eq!(
+            changed(&previous, &json!({ "a": [1, "x"], "b": null, "c": 1 })),
+            ["", "/c"]
+        );
+    }
+
+    #[test]
+    fn only_shown_values_are_compared() {
+        let previous = json!({ "a": [1, "x"] });
+        let current = json!({ "a": [1, "y"] });
+
+        // Changes within a collapsed array are not detected.
+        assert!(changed_when_expanded((&previous, &[]), (&current, &[])).is_empty());
+        assert_eq!(shown_values(&previous, &[]).len(), 2);
+        // Expanding an array does not flash its entries as if they were added.
+        assert!(changed_when_expanded((&previous, &[]), (&previous, &["/a"])).is_empty());
+        assert!(changed_when_expanded((&previous, &["/a"]), (&previous, &[])).is_empty());
+    }
+}
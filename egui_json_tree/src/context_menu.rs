@@ -0,0 +1,110 @@
+//! Built-in context menu for a [`JsonTree`](crate::JsonTree), with actions for copying JSON pointers, keys and values to the clipboard.
+//!
+//! Enable it via [`JsonTreeStyle::context_menu`](crate::JsonTreeStyle::context_menu),
+//! and append your own menu items via [`JsonTree::on_context_menu`](crate::JsonTree::on_context_menu).
+
+use std::cell::RefCell;
+
+use egui::{CursorIcon, Response, Ui};
+
+use crate::{
+    JsonTreeStyle,
+    pointer::{JsonPointer, JsonPointerSegment},
+    value::{JsonTreeValue, ToJsonTreeValue},
+};
+
+/// A closure for appending user-defined items to the built-in context menu.
+pub type ContextMenuHook<'a, T> = dyn FnMut(&mut Ui, ContextMenuContext<'a, '_, T>) + 'a;
+
+/// A handle to the information of the JSON value that the context menu was opened for.
+pub struct ContextMenuContext<'a, 'b, T: ToJsonTreeValue + ?Sized> {
+    /// The JSON value that the context menu was opened for.
+    pub value: &'a T,
+    /// The full JSON pointer to the JSON value that the context menu was opened for.
+    pub pointer: JsonPointer<'a, 'b>,
+}
+
+pub(crate) struct JsonTreeContextMenu<'a, T: ToJsonTreeValue + ?Sized> {
+    // Render contexts only hold a shared reference to this, since `DefaultRender::render_default` takes `&self`.
+    pub(crate) hook: RefCell<Option<Box<ContextMenuHook<'a, T>>>>,
+}
+
+impl<T: ToJsonTreeValue + ?Sized> Default for JsonTreeContextMenu<'_, T> {
+    fn default() -> Self {
+        Self {
+            hook: RefCell::new(None),
+        }
+    }
+}
+
+impl<'a, T: ToJsonTreeValue + ?Sized> JsonTreeContextMenu<'a, T> {
+    /// Attaches the context menu to the response of a rendered element, if enabled in the style.
+    pub(crate) fn attach(
+        &self,
+        response: Response,
+        style: &JsonTreeStyle,
+        value: &'a T,
+        pointer: JsonPointer<'a, '_>,
+    ) -> Response {
+        if !style.context_menu {
+            return response;
+        }
+        let response = response.on_hover_cursor(CursorIcon::ContextMenu);
+        response.context_menu(|ui| self.show(ui, style, value, pointer));
+        response
+    }
+
+    pub(crate) fn show(
+        &self,
+        ui: &mut Ui,
+        style: &JsonTreeStyle,
+        value: &'a T,
+        pointer: JsonPointer<'a, '_>,
+    ) {
+        // The pointer, path and accessor to the whole document are all trivial, so only offer them for nested values.
+        if !pointer.0.is_empty() {
+            if ui.button("Copy JSON pointer").clicked() {
+                ui.ctx().copy_text(pointer.to_json_pointer_string());
+            }
+            if ui.button("Copy JSONPath").clicked() {
+                ui.ctx().copy_text(pointer.to_json_path_string());
+            }
+            if ui.button("Copy JavaScript accessor").clicked() {
+                ui.ctx().copy_text(pointer.to_javascript_accessor_string());
+            }
+        }
+
+        if let Some(
+            key @ (JsonPointerSegment::Key(_)
+            | JsonPointerSegment::NonStringKey(_)
+            | JsonPointerSegment::DuplicateKey(..)),
+        ) = pointer.last()
+            && ui.button("Copy key").clicked()
+        {
+            ui.ctx().copy_text(key.to_string());
+        }
+
+        let is_expandable = match value.to_json_tree_value() {
+            JsonTreeValue::Base(_, display_value, value_type) => {
+                if ui.button("Copy value").clicked() {
+                    ui.ctx()
+                        .copy_text(style.format_value(display_value, &value_type, pointer.0));
+                }
+                false
+            }
+            JsonTreeValue::Expandable(..) => true,
+        };
+
+        if ui.button("Copy as JSON").clicked() {
+            ui.ctx().copy_text(value.to_json_string(true));
+        }
+        if is_expandable && ui.button("Copy as compact JSON").clicked() {
+            ui.ctx().copy_text(value.to_json_string(false));
+        }
+
+        if let Some(hook) = self.hook.borrow_mut().as_mut() {
+            ui.separator();
+            hook(ui, ContextMenuContext { value, pointer });
+        }
+    }
+}
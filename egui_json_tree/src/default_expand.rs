@@ -1,4 +1,4 @@
-use std::collections::HashSet;
+use std::{collections::HashSet, sync::Arc};
 
 use egui::Id;
 
@@ -27,10 +27,12 @@ pub enum DefaultExpand<'a> {
 
 #[derive(Debug)]
 /// Internal representation for the [`DefaultExpand`] setting.
-pub(crate) enum InnerDefaultExpand {
+pub(crate) enum InnerDefaultExpand<T: ?Sized> {
     All,
     None,
     ToLevel(u8),
     /// Specifies which arrays/objects should be expanded by default, based on its hashed JSON pointer.
-    Paths(HashSet<Id>),
+    Paths(Arc<HashSet<Id>>),
+    /// Whether an array/object is expanded by default, given its value, see `JsonTreeConfig::default_expanded`.
+    Predicate(fn(&T) -> bool),
 }
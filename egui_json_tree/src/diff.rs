@@ -0,0 +1,1208 @@
+//! Structural diffs between two JSON values, as shown by a [`JsonDiffTree`](crate::JsonDiffTree).
+//!
+//! [`JsonDiffValue::new`] merges two values of any [`ToJsonTreeValue`] type into a single tree, in which each node records
+//! its [`JsonDiffStatus`] and the old and new values it was derived from. A [`JsonDiffValue`] implements [`ToJsonTreeValue`] itself,
+//! so it can be visualised by a [`JsonTree`](crate::JsonTree), and inspected by render hooks.
+//!
+//! - Object entries are matched by key. Entries that were removed are placed after the entry that preceded them in the old object.
+//! - Array elements are matched by index, or by equality or a key field as configured by [`JsonDiffOptions`],
+//!   in which case elements that changed position relative to the others are reported as [`JsonDiffValue::moved`].
+//!   A removed element is shown under its old index, as a [`JsonPointerSegment::NonStringKey`] if another element now has that index.
+//! - Non-recursive values are compared by type and display value, e.g. `1` and `1.0` are different numbers.
+//! - A modified non-recursive value is shown as `old → new`, as a [`BaseValueType::Other`] value of kind [`MODIFIED_VALUE_KIND`].
+//! - A value that became a different type of value, e.g. an object that became a string, is shown as its new value
+//!   followed by its removed old value. The old value is shown under a [`JsonPointerSegment::NonStringKey`] of its old index,
+//!   or of a [`RemovedKey`], so that the paths of the two values do not clash. Its JSON pointer is its path within the old value.
+//!
+//! ```rust
+//! # use egui_json_tree::diff::{JsonDiffStatus, JsonDiffValue};
+//! # use egui_json_tree::value::{JsonTreeValue, ToJsonTreeValue};
+//! # use serde_json::json;
+//! let old = json!({ "a": 1, "b": [true], "c": null });
+//! let new = json!({ "a": 2, "b": [true], "d": "x" });
+//! let diff = JsonDiffValue::new(&old, &new);
+//! assert_eq!(diff.status, JsonDiffStatus::ContainsChanges);
+//!
+//! let JsonTreeValue::Expandable(entries, _) = diff.to_json_tree_value() else { unreachable!() };
+//! let statuses: Vec<_> = entries.iter().map(|(property, value)| (property.to_string(), value.status)).collect();
+//! assert_eq!(statuses, [
+//!     ("a".to_string(), JsonDiffStatus::Modified),
+//!     ("b".to_string(), JsonDiffStatus::Unchanged),
+//!     ("c".to_string(), JsonDiffStatus::Removed),
+//!     ("d".to_string(), JsonDiffStatus::Added),
+//! ]);
+//! ```
+
+use std::{
+    collections::{HashMap, HashSet, VecDeque},
+    fmt::{self, Display},
+    hash::{DefaultHasher, Hash, Hasher},
+};
+
+use crate::{
+    pointer::JsonPointerSegment,
+    serialize::write_escaped_str,
+    value::{BaseValueType, ExpandableType, JsonTreeValue, JsonTreeVisit, ToJsonTreeValue},
+};
+
+/// The [`BaseValueType::Other`] kind of a modified non-recursive value, which is shown as `old → new`.
+///
+/// Its color and quoting can be configured via [`JsonTreeVisuals::other_values`](crate::JsonTreeVisuals::other_values).
+pub const MODIFIED_VALUE_KIND: &str = "modified";
+
+/// The key of the removed old value of an object entry that became a different type of value in a unified diff,
+/// as a [`JsonPointerSegment::NonStringKey`], see the [module docs](self). It is displayed as the key itself.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct RemovedKey(pub String);
+
+impl Display for RemovedKey {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.0)
+    }
+}
+
+/// How a node of a [`JsonDiffValue`] differs between the old and the new value.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum JsonDiffStatus {
+    /// The node is the same in the old and the new value.
+    Unchanged,
+    /// The node only exists in the new value.
+    Added,
+    /// The node only exists in the old value.
+    Removed,
+    /// The node exists in both values, but is a different non-recursive value, or a different type of value.
+    Modified,
+    /// The node is an array or object in both values, and some of its entries were added, removed, modified or moved.
+    ContainsChanges,
+}
+
+/// How the elements of an old and a new array are matched with each other.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub enum ArrayAlignment<'a> {
+    /// Match elements at the same index.
+    #[default]
+    Index,
+    /// Match equal elements via the longest common subsequence of the two arrays.
+    /// Equal elements outside of the common subsequence are matched as moves,
+    /// and the remaining elements between two matched elements are matched in order.
+    Lcs,
+    /// Match object elements with the same value under the given key, e.g. `"id"`.
+    /// Elements with the same key at a different position relative to the other elements are matched as moves,
+    /// and elements without the key are matched in order with other elements without the key between two matched elements.
+    Key(&'a str),
+}
+
+/// Options for computing a [`JsonDiffValue`].
+///
+/// ```rust
+/// # use egui_json_tree::diff::{ArrayAlignment, JsonDiffOptions, JsonDiffStatus, JsonDiffValue};
+/// # use serde_json::json;
+/// let old = json!({ "users": [{ "id": 1, "name": "a" }, { "id": 2, "name": "b" }] });
+/// let new = json!({ "users": [{ "id": 0, "name": "z" }, { "id": 1, "name": "a" }, { "id": 2, "name": "b" }] });
+///
+/// let options = JsonDiffOptions::new().align_arrays_at("/users", ArrayAlignment::Key("id"));
+/// let diff = JsonDiffValue::with_options(&old, &new, &options);
+///
+/// // Only the inserted user is reported, rather than every user being modified.
+/// let patch = egui_json_tree::patch::JsonPatch::from_diff(&diff);
+/// assert_eq!(patch.operations.len(), 1);
+/// assert_eq!(patch.operations[0].pointer().to_json_pointer_string(), "/users/0");
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Eq, Hash)]
+pub struct JsonDiffOptions<'a> {
+    /// The alignment of arrays that do not match any of the [`JsonDiffOptions::array_alignment_rules`].
+    pub array_alignment: ArrayAlignment<'a>,
+    /// The alignment of the arrays at each JSON pointer pattern, where the first matching pattern applies.
+    /// See [`JsonDiffOptions::align_arrays_at`].
+    pub array_alignment_rules: Vec<(&'a str, ArrayAlignment<'a>)>,
+}
+
+impl<'a> JsonDiffOptions<'a> {
+    /// Creates [`JsonDiffOptions`] that match array elements by index.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Override the alignment of arrays that do not match any pattern of [`JsonDiffOptions::align_arrays_at`].
+    /// Defaults to [`ArrayAlignment::Index`].
+    pub fn array_alignment(mut self, array_alignment: ArrayAlignment<'a>) -> Self {
+        self.array_alignment = array_alignment;
+        self
+    }
+
+    /// Align the arrays at the given JSON pointer pattern, e.g. `"/services/*/ports"`,
+    /// where a `*` segment matches any array index or object key.
+    ///
+    /// The pattern is matched against the pointer of the array within the new value.
+    pub fn align_arrays_at(
+        mut self,
+        pattern: &'a str,
+        array_alignment: ArrayAlignment<'a>,
+    ) -> Self {
+        self.array_alignment_rules.push((pattern, array_alignment));
+        self
+    }
+
+    fn alignment_at(&self, path_segments: &[JsonPointerSegment]) -> ArrayAlignment<'a> {
+        self.array_alignment_rules
+            .iter()
+            .find(|(pattern, _)| matches_pointer_pattern(pattern, path_segments))
+            .map_or(self.array_alignment, |(_, array_alignment)| {
+                *array_alignment
+            })
+    }
+}
+
+fn matches_pointer_pattern(pattern: &str, path_segments: &[JsonPointerSegment]) -> bool {
+    if pattern.is_empty() {
+        return path_segments.is_empty();
+    }
+    let Some(pattern) = pattern.strip_prefix('/') else {
+        return false;
+    };
+    let pattern_segments: Vec<_> = pattern.split('/').collect();
+    pattern_segments.len() == path_segments.len()
+        && pattern_segments
+            .iter()
+            .zip(path_segments)
+            .all(|(pattern_segment, segment)| {
+                *pattern_segment == "*"
+                    || segment.to_json_pointer_segment_string()[1..] == **pattern_segment
+            })
+}
+
+/// Which values a [`JsonDiffValue`] shows.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum JsonDiffSide {
+    /// Show the nodes of both values, merged into one tree.
+    Unified,
+    /// Only show the nodes of the old value.
+    Old,
+    /// Only show the nodes of the new value.
+    New,
+}
+
+/// A node of a structural diff between two JSON values, see the [module documentation](self).
+pub struct JsonDiffValue<'a, T: ToJsonTreeValue + ?Sized> {
+    pub status: JsonDiffStatus,
+    /// The node within the old value, if it exists there.
+    pub old: Option<&'a T>,
+    /// The node within the new value, if it exists there.
+    pub new: Option<&'a T>,
+    /// The index of this node within its array in the old value, if it is an array element that exists there.
+    pub old_index: Option<usize>,
+    /// The index of this node within its array in the new value, if it is an array element that exists there.
+    pub new_index: Option<usize>,
+    /// Whether this array element moved relative to the other elements of its array, see [`ArrayAlignment`].
+    pub moved: bool,
+    /// The value that this node shows, i.e. the new value, unless it was removed or only the old side is shown.
+    shown: &'a T,
+    kind: JsonDiffKind<'a, T>,
+    /// For a node of a unified diff that became a different type of value, the removed old value, which is shown before this node.
+    removed_old: Option<Box<JsonDiffValue<'a, T>>>,
+}
+
+enum JsonDiffKind<'a, T: ToJsonTreeValue + ?Sized> {
+    Base(DiffDisplay<'a>, BaseValueType),
+    Expandable(Vec<JsonDiffEntry<'a, T>>, ExpandableType),
+}
+
+/// An array element or object entry of a [`JsonDiffValue`].
+pub(crate) struct JsonDiffEntry<'a, T: ToJsonTreeValue + ?Sized> {
+    pub(crate) property: JsonPointerSegment<'a>,
+    pub(crate) value: JsonDiffValue<'a, T>,
+    /// Whether the property is shown as a [`JsonPointerSegment::NonStringKey`] of the old index, for a removed element
+    /// of a unified diff whose index is also used by an element of the new array, so that their paths do not clash.
+    removed_index_key: bool,
+    /// The key of the removed old value of an object entry of a unified diff that became a different type of value.
+    removed_key: Option<RemovedKey>,
+}
+
+/// The display value of a non-recursive node, which shows both the old and new value if it was modified.
+enum DiffDisplay<'a> {
+    One(&'a dyn Display),
+    Modified {
+        old: (&'a dyn Display, BaseValueType),
+        new: (&'a dyn Display, BaseValueType),
+    },
+}
+
+impl Display for DiffDisplay<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let write_value =
+            |f: &mut fmt::Formatter<'_>, (value, value_type): (&dyn Display, BaseValueType)| {
+                if value_type == BaseValueType::String {
+                    write_escaped_str(f, &value.to_string())
+                } else {
+                    write!(f, "{value}")
+                }
+            };
+        match self {
+            DiffDisplay::One(value) => value.fmt(f),
+            DiffDisplay::Modified { old, new } => {
+                write_value(f, *old)?;
+                f.write_str(" → ")?;
+                write_value(f, *new)
+            }
+        }
+    }
+}
+
+/// The state of computing a diff, passed down to each node.
+struct DiffContext<'a, 'o> {
+    options: &'o JsonDiffOptions<'o>,
+    side: JsonDiffSide,
+    /// The path of the node being compared, within the new value where it exists.
+    path_segments: Vec<JsonPointerSegment<'a>>,
+}
+
+impl<'a, T: ToJsonTreeValue + ?Sized> JsonDiffValue<'a, T> {
+    /// Computes the structural diff from `old` to `new`, merged into one tree, matching array elements by index.
+    pub fn new(old: &'a T, new: &'a T) -> Self {
+        Self::with_options(old, new, &JsonDiffOptions::default())
+    }
+
+    /// Computes the structural diff from `old` to `new`, merged into one tree, with the given options.
+    pub fn with_options(old: &'a T, new: &'a T, options: &JsonDiffOptions) -> Self {
+        Self::with_side(old, new, options, JsonDiffSide::Unified)
+    }
+
+    /// Computes the structural diff from `old` to `new`, only keeping the nodes of the given side.
+    pub(crate) fn with_side(
+        old: &'a T,
+        new: &'a T,
+        options: &JsonDiffOptions,
+        side: JsonDiffSide,
+    ) -> Self {
+        let mut context = DiffContext {
+            options,
+            side,
+            path_segments: vec![],
+        };
+        Self::diff(Some(old), Some(new), &mut context)
+    }
+
+    /// Returns whether this node, or any node within it, differs between the old and the new value, including moved array elements.
+    pub fn is_changed(&self) -> bool {
+        self.status != JsonDiffStatus::Unchanged || self.moved
+    }
+
+    /// Returns the removed old value of this node if it became a different type of value in a unified diff.
+    pub(crate) fn removed_old(&self) -> Option<&Self> {
+        self.removed_old.as_deref()
+    }
+
+    /// Returns the entries of this node if it is an array or object, e.g. to generate patches.
+    pub(crate) fn entries(&self) -> Option<(&[JsonDiffEntry<'a, T>], ExpandableType)> {
+        match &self.kind {
+            JsonDiffKind::Base(..) => None,
+            JsonDiffKind::Expandable(entries, expandable_type) => Some((entries, *expandable_type)),
+        }
+    }
+
+    fn diff(old: Option<&'a T>, new: Option<&'a T>, context: &mut DiffContext<'a, '_>) -> Self {
+        let (old_value, new_value) = match (old, new) {
+            (Some(old_value), Some(new_value)) => (old_value, new_value),
+            (Some(old), None) => return Self::one_sided(old, JsonDiffStatus::Removed, true),
+            (None, Some(new)) => return Self::one_sided(new, JsonDiffStatus::Added, false),
+            (None, None) => unreachable!("a diff node exists in the old or the new value"),
+        };
+        let side = context.side;
+        let shown = if side == JsonDiffSide::Old {
+            old_value
+        } else {
+            new_value
+        };
+
+        let mut removed_old = None;
+        let (status, kind) = match (
+            old_value.to_json_tree_value(),
+            new_value.to_json_tree_value(),
+        ) {
+            (
+                JsonTreeValue::Base(_, old_display, old_type),
+                JsonTreeValue::Base(_, new_display, new_type),
+            ) => {
+                let unchanged =
+                    old_type == new_type && old_display.to_string() == new_display.to_string();
+                let (display, value_type) = match side {
+                    JsonDiffSide::Old => (DiffDisplay::One(old_display), old_type),
+                    JsonDiffSide::New => (DiffDisplay::One(new_display), new_type),
+                    JsonDiffSide::Unified if unchanged => (DiffDisplay::One(new_display), new_type),
+                    JsonDiffSide::Unified => (
+                        DiffDisplay::Modified {
+                            old: (old_display, old_type),
+                            new: (new_display, new_type),
+                        },
+                        BaseValueType::Other(MODIFIED_VALUE_KIND),
+                    ),
+                };
+                let status = if unchanged {
+                    JsonDiffStatus::Unchanged
+                } else {
+                    JsonDiffStatus::Modified
+                };
+                (status, JsonDiffKind::Base(display, value_type))
+            }
+            (
+                JsonTreeValue::Expandable(old_entries, old_type),
+                JsonTreeValue::Expandable(new_entries, new_type),
+            ) if old_type == new_type => {
+                let entries = match new_type {
+                    ExpandableType::Array => diff_arrays(old_entries, new_entries, context),
+                    ExpandableType::Object => diff_objects(old_entries, new_entries, context),
+                };
+                let status = if entries.iter().any(|entry| entry.value.is_changed()) {
+                    JsonDiffStatus::ContainsChanges
+                } else {
+                    JsonDiffStatus::Unchanged
+                };
+                (status, JsonDiffKind::Expandable(entries, new_type))
+            }
+            // A different type of value, e.g. an object that became a string.
+            _ => {
+                if side == JsonDiffSide::Unified {
+                    removed_old = Some(Box::new(Self::one_sided(
+                        old_value,
+                        JsonDiffStatus::Removed,
+                        true,
+                    )));
+                }
+                let one_sided =
+                    Self::one_sided(shown, JsonDiffStatus::Modified, side == JsonDiffSide::Old);
+                (JsonDiffStatus::Modified, one_sided.kind)
+            }
+        };
+
+        Self {
+            status,
+            old,
+            new,
+            old_index: None,
+            new_index: None,
+            moved: false,
+            shown,
+            kind,
+            removed_old,
+        }
+    }
+
+    /// Diffs an entry of an array or object, whose path within the new value ends with the given segment.
+    fn diff_entry(
+        path_segment: JsonPointerSegment<'a>,
+        old: Option<&'a T>,
+        new: Option<&'a T>,
+        context: &mut DiffContext<'a, '_>,
+    ) -> Self {
+        context.path_segments.push(path_segment);
+        let value = Self::diff(old, new, context);
+        context.path_segments.pop();
+        value
+    }
+
+    /// Creates a node for a value that only exists on one side, along with every node within it.
+    fn one_sided(value: &'a T, status: JsonDiffStatus, is_old: bool) -> Self {
+        let entry_status = match status {
+            JsonDiffStatus::Modified if is_old => JsonDiffStatus::Removed,
+            JsonDiffStatus::Modified => JsonDiffStatus::Added,
+            status => status,
+        };
+        let kind = match value.to_json_tree_value() {
+            JsonTreeValue::Base(_, display_value, value_type) => {
+                JsonDiffKind::Base(DiffDisplay::One(display_value), value_type)
+            }
+            JsonTreeValue::Expandable(entries, expandable_type) => JsonDiffKind::Expandable(
+                entries
+                    .into_iter()
+                    .enumerate()
+                    .map(|(idx, (property, elem))| {
+                        let mut value = Self::one_sided(elem, entry_status, is_old);
+                        if expandable_type == ExpandableType::Array {
+                            value.set_indices(is_old.then_some(idx), (!is_old).then_some(idx));
+                        }
+                        JsonDiffEntry::new(property, value)
+                    })
+                    .collect(),
+                expandable_type,
+            ),
+        };
+        Self {
+            status,
+            old: is_old.then_some(value),
+            new: (!is_old).then_some(value),
+            old_index: None,
+            new_index: None,
+            moved: false,
+            shown: value,
+            kind,
+            removed_old: None,
+        }
+    }
+
+    fn set_indices(&mut self, old_index: Option<usize>, new_index: Option<usize>) {
+        self.old_index = old_index;
+        self.new_index = new_index;
+    }
+}
+
+impl<'a, T: ToJsonTreeValue + ?Sized> JsonDiffEntry<'a, T> {
+    fn new(property: JsonPointerSegment<'a>, value: JsonDiffValue<'a, T>) -> Self {
+        let removed_key = match property {
+            JsonPointerSegment::Key(key) if value.removed_old.is_some() => {
+                Some(RemovedKey(key.to_string()))
+            }
+            _ => None,
+        };
+        Self {
+            property,
+            value,
+            removed_index_key: false,
+            removed_key,
+        }
+    }
+}
+
+impl JsonDiffSide {
+    /// Whether an entry that exists in the old and/or new value is shown on this side.
+    fn includes(self, in_old: bool, in_new: bool) -> bool {
+        match self {
+            JsonDiffSide::Unified => true,
+            JsonDiffSide::Old => in_old,
+            JsonDiffSide::New => in_new,
+        }
+    }
+}
+
+fn diff_arrays<'a, T: ToJsonTreeValue + ?Sized>(
+    old_entries: Vec<(JsonPointerSegment<'a>, &'a T)>,
+    new_entries: Vec<(JsonPointerSegment<'a>, &'a T)>,
+    context: &mut DiffContext<'a, '_>,
+) -> Vec<JsonDiffEntry<'a, T>> {
+    let old_elems: Vec<&T> = old_entries.into_iter().map(|(_, elem)| elem).collect();
+    let new_elems: Vec<&T> = new_entries.into_iter().map(|(_, elem)| elem).collect();
+    let alignment = context.options.alignment_at(&context.path_segments);
+    let new_to_old = align_arrays(&old_elems, &new_elems, alignment);
+
+    let mut old_to_new = vec![None; old_elems.len()];
+    for (new_idx, matched) in new_to_old.iter().enumerate() {
+        if let Some((old_idx, moved)) = matched {
+            old_to_new[*old_idx] = Some((new_idx, *moved));
+        }
+    }
+
+    let side = context.side;
+    let mut diff_elem = |old_idx: Option<usize>, new_idx: Option<usize>, moved: bool| {
+        let property = JsonPointerSegment::Index(
+            match side {
+                JsonDiffSide::Old => old_idx.or(new_idx),
+                JsonDiffSide::New | JsonDiffSide::Unified => new_idx.or(old_idx),
+            }
+            .unwrap_or_default(),
+        );
+        // Array alignment patterns are matched against paths within the new value.
+        let path_segment = JsonPointerSegment::Index(new_idx.or(old_idx).unwrap_or_default());
+        let mut value = JsonDiffValue::diff_entry(
+            path_segment,
+            old_idx.map(|idx| old_elems[idx]),
+            new_idx.map(|idx| new_elems[idx]),
+            context,
+        );
+        value.set_indices(old_idx, new_idx);
+        value.moved = moved;
+        JsonDiffEntry {
+            property,
+            removed_index_key: side == JsonDiffSide::Unified
+                && new_idx.is_none()
+                && old_idx.is_some_and(|idx| idx < new_elems.len()),
+            removed_key: None,
+            value,
+        }
+    };
+
+    match side {
+        JsonDiffSide::Old => old_to_new
+            .iter()
+            .enumerate()
+            .map(|(old_idx, matched)| match matched {
+                Some((new_idx, moved)) => diff_elem(Some(old_idx), Some(*new_idx), *moved),
+                None => diff_elem(Some(old_idx), None, false),
+            })
+            .collect(),
+        JsonDiffSide::New => new_to_old
+            .iter()
+            .enumerate()
+            .map(|(new_idx, matched)| match matched {
+                Some((old_idx, moved)) => diff_elem(Some(*old_idx), Some(new_idx), *moved),
+                None => diff_elem(None, Some(new_idx), false),
+            })
+            .collect(),
+        JsonDiffSide::Unified => {
+            // The removed elements, grouped by the nearest preceding element in the old array that was matched.
+            let mut removed_after: HashMap<Option<usize>, Vec<usize>> = HashMap::new();
+            let mut predecessor = None;
+            for (old_idx, matched) in old_to_new.iter().enumerate() {
+                if matched.is_some() {
+                    predecessor = Some(old_idx);
+                } else {
+                    removed_after.entry(predecessor).or_default().push(old_idx);
+                }
+            }
+
+            let removed = |old_idx| (Some(old_idx), None, false);
+            let mut elements: Vec<_> = removed_after
+                .remove(&None)
+                .unwrap_or_default()
+                .into_iter()
+                .map(removed)
+                .collect();
+            for (new_idx, matched) in new_to_old.iter().enumerate() {
+                match matched {
+                    Some((old_idx, moved)) => {
+                        elements.push((Some(*old_idx), Some(new_idx), *moved));
+                        let removed_elements = removed_after.remove(&Some(*old_idx));
+                        elements.extend(
+                            removed_elements
+                                .unwrap_or_default()
+                                .into_iter()
+                                .map(removed),
+                        );
+                    }
+                    None => elements.push((None, Some(new_idx), false)),
+                }
+            }
+            elements
+                .into_iter()
+                .map(|(old_idx, new_idx, moved)| diff_elem(old_idx, new_idx, moved))
+                .collect()
+        }
+    }
+}
+
+/// Matches the elements of the new array with those of the old array,
+/// returning the index of the matched old element for each new element, and whether it moved.
+fn align_arrays<T: ToJsonTreeValue + ?Sized>(
+    old_elems: &[&T],
+    new_elems: &[&T],
+    alignment: ArrayAlignment,
+) -> Vec<Option<(usize, bool)>> {
+    let identities = |elems: &[&T]| -> Vec<Option<u64>> {
+        elems
+            .iter()
+            .map(|elem| match alignment {
+                ArrayAlignment::Index => None,
+                ArrayAlignment::Lcs => Some(hash_value(*elem)),
+                ArrayAlignment::Key(key) => key_identity(*elem, key),
+            })
+            .collect()
+    };
+    let (old_ids, new_ids) = match alignment {
+        ArrayAlignment::Index => {
+            return (0..new_elems.len())
+                .map(|idx| (idx < old_elems.len()).then_some((idx, false)))
+                .collect();
+        }
+        ArrayAlignment::Lcs | ArrayAlignment::Key(_) => {
+            (identities(old_elems), identities(new_elems))
+        }
+    };
+
+    let mut new_to_old = vec![None; new_elems.len()];
+    let mut old_matched = vec![false; old_elems.len()];
+    let anchors = longest_common_subsequence(&old_ids, &new_ids);
+    for &(old_idx, new_idx) in &anchors {
+        new_to_old[new_idx] = Some((old_idx, false));
+        old_matched[old_idx] = true;
+    }
+
+    // Match the remaining elements with the same identity as moves.
+    let mut unmatched_by_id: HashMap<u64, VecDeque<usize>> = HashMap::new();
+    for (old_idx, id) in old_ids.iter().enumerate() {
+        if let (Some(id), false) = (id, old_matched[old_idx]) {
+            unmatched_by_id.entry(*id).or_default().push_back(old_idx);
+        }
+    }
+    for (new_idx, id) in new_ids.iter().enumerate() {
+        if new_to_old[new_idx].is_some() {
+            continue;
+        }
+        let Some(old_idx) = id.and_then(|id| unmatched_by_id.get_mut(&id)?.pop_front()) else {
+            continue;
+        };
+        new_to_old[new_idx] = Some((old_idx, true));
+        old_matched[old_idx] = true;
+    }
+
+    // Match the remaining elements between two anchors in order, e.g. an element that was modified in place.
+    let pairable = |id: Option<u64>| alignment == ArrayAlignment::Lcs || id.is_none();
+    let mut gap_start = (0, 0);
+    for gap_end in anchors
+        .iter()
+        .copied()
+        .chain(std::iter::once((old_elems.len(), new_elems.len())))
+    {
+        let old_gap =
+            (gap_start.0..gap_end.0).filter(|idx| !old_matched[*idx] && pairable(old_ids[*idx]));
+        let new_gap = (gap_start.1..gap_end.1)
+            .filter(|idx| new_to_old[*idx].is_none() && pairable(new_ids[*idx]));
+        let pairs: Vec<_> = old_gap.zip(new_gap).collect();
+        for (old_idx, new_idx) in pairs {
+            new_to_old[new_idx] = Some((old_idx, false));
+            old_matched[old_idx] = true;
+        }
+        gap_start = (gap_end.0 + 1, gap_end.1 + 1);
+    }
+
+    new_to_old
+}
+
+/// Arrays whose differing middles need more insertions and deletions than this to align are aligned by index instead,
+/// since the time and memory of [`longest_common_subsequence`] grow with the number of insertions and deletions.
+const MAX_LCS_EDIT_DISTANCE: usize = 1000;
+
+/// Returns the index pairs of the longest common subsequence of the identities, where elements without an identity never match.
+///
+/// Common prefixes and suffixes are matched directly, and the differing middle is aligned with Myers' O(ND) algorithm.
+/// If the middle needs more than [`MAX_LCS_EDIT_DISTANCE`] insertions and deletions, none of it is matched,
+/// so its elements are matched in order by [`align_arrays`].
+fn longest_common_subsequence(
+    old_ids: &[Option<u64>],
+    new_ids: &[Option<u64>],
+) -> Vec<(usize, usize)> {
+    let eq = |old_idx: usize, new_idx: usize| {
+        old_ids[old_idx].is_some() && old_ids[old_idx] == new_ids[new_idx]
+    };
+    let mut prefix = 0;
+    while prefix < old_ids.len().min(new_ids.len()) && eq(prefix, prefix) {
+        prefix += 1;
+    }
+    let mut suffix = 0;
+    while suffix < (old_ids.len() - prefix).min(new_ids.len() - prefix)
+        && eq(old_ids.len() - 1 - suffix, new_ids.len() - 1 - suffix)
+    {
+        suffix += 1;
+    }
+
+    let old_len = old_ids.len() - prefix - suffix;
+    let new_len = new_ids.len() - prefix - suffix;
+    let mut pairs: Vec<_> = (0..prefix).map(|idx| (idx, idx)).collect();
+    pairs.extend(
+        myers_common_subsequence(old_len, new_len, |i, j| eq(prefix + i, prefix + j))
+            .unwrap_or_default()
+            .into_iter()
+            .map(|(i, j)| (prefix + i, prefix + j)),
+    );
+    pairs.extend((0..suffix).map(|idx| (prefix + old_len + idx, prefix + new_len + idx)));
+    pairs
+}
+
+/// Returns the index pairs of a longest common subsequence of two sequences of the given lengths,
+/// or `None` if they need more than [`MAX_LCS_EDIT_DISTANCE`] insertions and deletions to align.
+fn myers_common_subsequence(
+    old_len: usize,
+    new_len: usize,
+    eq: impl Fn(usize, usize) -> bool,
+) -> Option<Vec<(usize, usize)>> {
+    let max_d = MAX_LCS_EDIT_DISTANCE.min(old_len + new_len) as isize;
+    let (old_len, new_len) = (old_len as isize, new_len as isize);
+    // The furthest old index reached on each diagonal `k = old index - new index`, offset by `max_d + 1`.
+    let mut furthest = vec![0isize; 2 * max_d as usize + 3];
+    let at = |k: isize| (k + max_d + 1) as usize;
+    // The furthest old index reached on diagonals `-d..=d` after each number of edits `d`, to trace the path back.
+    let mut trace: Vec<Vec<isize>> = vec![];
+
+    for d in 0..=max_d {
+        let mut reached_end = false;
+        for k in (-d..=d).step_by(2) {
+            let mut x = if k == -d || (k != d && furthest[at(k - 1)] < furthest[at(k + 1)]) {
+                furthest[at(k + 1)]
+            } else {
+                furthest[at(k - 1)] + 1
+            };
+            let mut y = x - k;
+            while x < old_len && y < new_len && eq(x as usize, y as usize) {
+                x += 1;
+                y += 1;
+            }
+            furthest[at(k)] = x;
+            reached_end |= x >= old_len && y >= new_len;
+        }
+        trace.push(furthest[at(-d)..=at(d)].to_vec());
+        if !reached_end {
+            continue;
+        }
+
+        let mut pairs = vec![];
+        let (mut x, mut y) = (old_len, new_len);
+        for d in (0..=d).rev() {
+            let k = x - y;
+            let (prev_x, prev_y, snake_x) = if d == 0 {
+                (0, 0, 0)
+            } else {
+                let prev = &trace[d as usize - 1];
+                let prev_at = |k: isize| (k + d - 1) as usize;
+                let prev_k = if k == -d || (k != d && prev[prev_at(k - 1)] < prev[prev_at(k + 1)]) {
+                    k + 1
+                } else {
+                    k - 1
+                };
+                let prev_x = prev[prev_at(prev_k)];
+                // An insertion keeps the old index, and a deletion moves past one old element.
+                let snake_x = if prev_k == k + 1 { prev_x } else { prev_x + 1 };
+                (prev_x, prev_x - prev_k, snake_x)
+            };
+            while x > snake_x {
+                x -= 1;
+                y -= 1;
+                pairs.push((x as usize, y as usize));
+            }
+            (x, y) = (prev_x, prev_y);
+        }
+        pairs.reverse();
+        return Some(pairs);
+    }
+    None
+}
+
+/// Hashes the structure and display values of a value, so that equal values have equal hashes.
+fn hash_value<T: ToJsonTreeValue + ?Sized>(value: &T) -> u64 {
+    let mut hasher = DefaultHasher::new();
+    value.visit(&mut |path_segments, visit| {
+        path_segments.len().hash(&mut hasher);
+        path_segments.last().hash(&mut hasher);
+        match visit {
+            JsonTreeVisit::Base(display_value, value_type) => {
+                value_type.hash(&mut hasher);
+                display_value.to_string().hash(&mut hasher);
+            }
+            JsonTreeVisit::Expandable(expandable_type) => expandable_type.hash(&mut hasher),
+        }
+    });
+    hasher.finish()
+}
+
+/// Returns the hash of the value under the given key, if the value is an object with that key.
+fn key_identity<T: ToJsonTreeValue + ?Sized>(value: &T, key: &str) -> Option<u64> {
+    let JsonTreeValue::Expandable(entries, ExpandableType::Object) = value.to_json_tree_value()
+    else {
+        return None;
+    };
+    entries
+        .into_iter()
+        .find(|(property, _)| matches!(property, JsonPointerSegment::Key(k) if *k == key))
+        .map(|(_, elem)| hash_value(elem))
+}
+
+fn diff_objects<'a, T: ToJsonTreeValue + ?Sized>(
+    old_entries: Vec<(JsonPointerSegment<'a>, &'a T)>,
+    new_entries: Vec<(JsonPointerSegment<'a>, &'a T)>,
+    context: &mut DiffContext<'a, '_>,
+) -> Vec<JsonDiffEntry<'a, T>> {
+    let side = context.side;
+    let old_values: HashMap<JsonPointerSegment, &'a T> = old_entries.iter().copied().collect();
+    let new_keys: HashSet<JsonPointerSegment> =
+        new_entries.iter().map(|(property, _)| *property).collect();
+
+    // The removed entries, grouped by the key of the nearest preceding entry in the old object that still exists.
+    let mut removed_after: HashMap<Option<JsonPointerSegment<'a>>, Vec<_>> = HashMap::new();
+    let mut predecessor = None;
+    for (property, elem) in &old_entries {
+        if new_keys.contains(property) {
+            predecessor = Some(*property);
+        } else if side.includes(true, false) {
+            removed_after
+                .entry(predecessor)
+                .or_default()
+                .push((*property, *elem));
+        }
+    }
+    let mut push_removed_after =
+        |entries: &mut Vec<JsonDiffEntry<'a, T>>,
+         predecessor: Option<JsonPointerSegment<'a>>,
+         context: &mut DiffContext<'a, '_>| {
+            for (property, elem) in removed_after.remove(&predecessor).unwrap_or_default() {
+                let value = JsonDiffValue::diff_entry(property, Some(elem), None, context);
+                entries.push(JsonDiffEntry::new(property, value));
+            }
+        };
+
+    let mut entries = vec![];
+    push_removed_after(&mut entries, None, context);
+    for (property, new) in &new_entries {
+        match old_values.get(property) {
+            Some(old) => {
+                let value = JsonDiffValue::diff_entry(*property, Some(*old), Some(*new), context);
+                entries.push(JsonDiffEntry::new(*property, value));
+                push_removed_after(&mut entries, Some(*property), context);
+            }
+            None => {
+                if side.includes(false, true) {
+                    let value = JsonDiffValue::diff_entry(*property, None, Some(*new), context);
+                    entries.push(JsonDiffEntry::new(*property, value));
+                }
+            }
+        }
+    }
+
+    entries
+}
+
+impl<T: ToJsonTreeValue + ?Sized> ToJsonTreeValue for JsonDiffValue<'_, T> {
+    fn to_json_tree_value(&self) -> JsonTreeValue<'_, Self> {
+        match &self.kind {
+            JsonDiffKind::Base(DiffDisplay::One(display_value), value_type) => {
+                JsonTreeValue::Base(self, *display_value, *value_type)
+            }
+            JsonDiffKind::Base(display_value, value_type) => {
+                JsonTreeValue::Base(self, display_value, *value_type)
+            }
+            JsonDiffKind::Expandable(entries, expandable_type) => JsonTreeValue::Expandable(
+                entries
+                    .iter()
+                    .flat_map(|entry| {
+                        let removed_property = || match (&entry.value.old_index, &entry.removed_key)
+                        {
+                            (Some(old_index), _) => JsonPointerSegment::NonStringKey(old_index),
+                            (None, Some(removed_key)) => {
+                                JsonPointerSegment::NonStringKey(removed_key)
+                            }
+                            (None, None) => entry.property,
+                        };
+                        let property = if entry.removed_index_key {
+                            removed_property()
+                        } else {
+                            entry.property
+                        };
+                        let removed_old = entry
+                            .value
+                            .removed_old()
+                            .map(|removed_old| (removed_property(), removed_old));
+                        // The new value comes first, so that its path resolves to it rather than the removed old value.
+                        [(property, &entry.value)].into_iter().chain(removed_old)
+                    })
+                    .collect(),
+                *expandable_type,
+            ),
+        }
+    }
+
+    fn is_expandable(&self) -> bool {
+        matches!(self.kind, JsonDiffKind::Expandable(..))
+    }
+
+    fn to_json_string(&self, pretty: bool) -> String {
+        self.shown.to_json_string(pretty)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::{Value, json};
+
+    use super::*;
+
+    /// Flattens the diff into the JSON pointer, status and display value (if non-recursive) of every node.
+    fn flatten(diff: &JsonDiffValue<Value>) -> Vec<(String, JsonDiffStatus, Option<String>)> {
+        fn walk(
+            diff: &JsonDiffValue<Value>,
+            pointer: String,
+            nodes: &mut Vec<(String, JsonDiffStatus, Option<String>)>,
+        ) {
+            match diff.to_json_tree_value() {
+                JsonTreeValue::Base(_, display_value, _) => {
+                    nodes.push((pointer, diff.status, Some(display_value.to_string())));
+                }
+                JsonTreeValue::Expandable(entries, _) => {
+                    nodes.push((pointer.clone(), diff.status, None));
+                    for (property, elem) in entries {
+                        walk(elem, format!("{pointer}/{property}"), nodes);
+                    }
+                }
+            }
+        }
+        let mut nodes = vec![];
+        walk(diff, String::new(), &mut nodes);
+        nodes
+    }
+
+    fn node(
+        pointer: &str,
+        status: JsonDiffStatus,
+        display_value: Option<&str>,
+    ) -> (String, JsonDiffStatus, Option<String>) {
+        (
+            pointer.to_string(),
+            status,
+            display_value.map(str::to_string),
+        )
+    }
+
+    #[test]
+    fn diff_equal_values_is_unchanged() {
+        let value = json!({ "a": [1, { "b": null }] });
+        let diff = JsonDiffValue::new(&value, &value);
+        assert!(
+            flatten(&diff)
+                .iter()
+                .all(|(_, status, _)| *status == JsonDiffStatus::Unchanged)
+        );
+    }
+
+    #[test]
+    fn diff_merges_object_entries_and_array_elements() {
+        let old = json!({ "a": 1, "b": true, "c": [1, 2, 3], "d": "x" });
+        let new = json!({ "a": 1, "c": [1, 5], "d": "y", "e": null });
+        let diff = JsonDiffValue::new(&old, &new);
+
+        use JsonDiffStatus::*;
+        assert_eq!(
+            flatten(&diff),
+            [
+                node("", ContainsChanges, None),
+                node("/a", Unchanged, Some("1")),
+                node("/b", Removed, Some("true")),
+                node("/c", ContainsChanges, None),
+                node("/c/0", Unchanged, Some("1")),
+                node("/c/1", Modified, Some("2 → 5")),
+                node("/c/2", Removed, Some("3")),
+                node("/d", Modified, Some("\"x\" → \"y\"")),
+                node("/e", Added, Some("null")),
+            ]
+        );
+        assert_eq!(diff.to_json_string(false), new.to_string());
+    }
+
+    #[test]
+    fn diff_escapes_modified_strings() {
+        let old = json!({ "a": "say \"hi\"" });
+        let new = json!({ "a": "line\nbreak" });
+
+        assert_eq!(
+            flatten(&JsonDiffValue::new(&old, &new))[1],
+            node(
+                "/a",
+                JsonDiffStatus::Modified,
+                Some(r#""say \"hi\"" → "line\nbreak""#)
+            )
+        );
+    }
+
+    #[test]
+    fn diff_different_types_of_value_as_whole_subtrees() {
+        let old = json!({ "a": { "b": 1 } });
+        let new = json!({ "a": [2] });
+
+        use JsonDiffStatus::*;
+        assert_eq!(
+            flatten(&JsonDiffValue::new(&old, &new)),
+            [
+                node("", ContainsChanges, None),
+                node("/a", Modified, None),
+                node("/a/0", Added, Some("2")),
+                node("/a", Removed, None),
+                node("/a/b", Removed, Some("1")),
+            ]
+        );
+        let diff = JsonDiffValue::new(&old, &new);
+        let JsonTreeValue::Expandable(entries, _) = diff.to_json_tree_value() else {
+            unreachable!()
+        };
+        assert!(matches!(
+            entries[1].0,
+            JsonPointerSegment::NonStringKey(key) if key.eq_key(&RemovedKey("a".to_string()))
+        ));
+        assert_eq!(
+            crate::pointer::resolve_json_pointer(&diff, "/a").map(|value| value.status),
+            Some(Modified)
+        );
+        assert_eq!(
+            flatten(&JsonDiffValue::with_side(
+                &old,
+                &new,
+                &JsonDiffOptions::new(),
+                JsonDiffSide::Old
+            )),
+            [
+                node("", ContainsChanges, None),
+                node("/a", Modified, None),
+                node("/a/b", Removed, Some("1")),
+            ]
+        );
+    }
+
+    #[test]
+    fn diff_sides_only_keep_their_own_entries() {
+        let old = json!({ "a": 1, "removed": true });
+        let new = json!({ "a": 2, "added": false });
+
+        use JsonDiffStatus::*;
+        assert_eq!(
+            flatten(&JsonDiffValue::with_side(
+                &old,
+                &new,
+                &JsonDiffOptions::new(),
+                JsonDiffSide::Old
+            )),
+            [
+                node("", ContainsChanges, None),
+                node("/a", Modified, Some("1")),
+                node("/removed", Removed, Some("true")),
+            ]
+        );
+        assert_eq!(
+            flatten(&JsonDiffValue::with_side(
+                &old,
+                &new,
+                &JsonDiffOptions::new(),
+                JsonDiffSide::New
+            )),
+            [
+                node("", ContainsChanges, None),
+                node("/a", Modified, Some("2")),
+                node("/added", Added, Some("false")),
+            ]
+        );
+    }
+
+    fn flatten_with(
+        old: &Value,
+        new: &Value,
+        alignment: ArrayAlignment,
+    ) -> Vec<(String, JsonDiffStatus, Option<String>)> {
+        let options = JsonDiffOptions::new().array_alignment(alignment);
+        flatten(&JsonDiffValue::with_options(old, new, &options))
+    }
+
+    #[test]
+    fn diff_arrays_aligned_by_lcs() {
+        let old = json!(["a", "b", "c", "d"]);
+        let new = json!(["x", "a", "c", "e", "b"]);
+
+        use JsonDiffStatus::*;
+        assert_eq!(
+            flatten_with(&old, &new, ArrayAlignment::Lcs),
+            [
+                node("", ContainsChanges, None),
+                node("/0", Added, Some("x")),
+                node("/1", Unchanged, Some("a")),
+                node("/2", Unchanged, Some("c")),
+                node("/3", Modified, Some("\"d\" → \"e\"")),
+                node("/4", Unchanged, Some("b")),
+            ]
+        );
+
+        let diff = JsonDiffValue::with_options(
+            &old,
+            &new,
+            &JsonDiffOptions::new().array_alignment(ArrayAlignment::Lcs),
+        );
+        let (entries, _) = diff.entries().unwrap();
+        let moved: Vec<_> = entries
+            .iter()
+            .map(|entry| {
+                (
+                    entry.value.old_index,
+                    entry.value.new_index,
+                    entry.value.moved,
+                )
+            })
+            .collect();
+        assert_eq!(
+            moved,
+            [
+                (None, Some(0), false),
+                (Some(0), Some(1), false),
+                (Some(2), Some(2), false),
+                (Some(3), Some(3), false),
+                (Some(1), Some(4), true),
+            ]
+        );
+    }
+
+    #[test]
+    fn longest_common_subsequence_of_long_arrays() {
+        let ids = |ids: &[u64]| ids.iter().copied().map(Some).collect::<Vec<_>>();
+        assert_eq!(
+            longest_common_subsequence(&ids(&[1, 2, 3, 4, 5, 6]), &ids(&[1, 7, 3, 2, 5, 8, 6])),
+            [(0, 0), (2, 2), (4, 4), (5, 6)]
+        );
+
+        // Far apart arrays are not aligned beyond their common prefix and suffix, rather than taking quadratic time and memory.
+        let old: Vec<_> = (0..20_000).map(Some).collect();
+        let new: Vec<_> = (0..20_000).map(|id| Some(id + 20_000)).collect();
+        assert_eq!(longest_common_subsequence(&old, &new), []);
+
+        // Arrays with few differences are aligned in full.
+        let mut new = old.clone();
+        new.remove(10_000);
+        new.insert(5_000, None);
+        let pairs = longest_common_subsequence(&old, &new);
+        assert_eq!(pairs.len(), 19_999);
+        assert!(pairs.contains(&(9_999, 10_000)) && pairs.contains(&(10_001, 10_001)));
+    }
+
+    #[test]
+    fn diff_arrays_aligned_by_key_at_pointer_pattern() {
+        let old =
+            json!({ "users": [{ "id": 1, "v": 1 }, { "id": 2, "v": 2 }, { "id": 3, "v": 3 }] });
+        let new =
+            json!({ "users": [{ "id": 0, "v": 0 }, { "id": 2, "v": 2 }, { "id": 1, "v": 5 }] });
+        let options = JsonDiffOptions::new().align_arrays_at("/users", ArrayAlignment::Key("id"));
+
+        use JsonDiffStatus::*;
+        let statuses: Vec<_> = flatten(&JsonDiffValue::with_options(&old, &new, &options))
+            .into_iter()
+            .filter(|(pointer, _, _)| pointer.matches('/').count() <= 2)
+            .map(|(pointer, status, _)| (pointer, status))
+            .collect();
+        assert_eq!(
+            statuses,
+            [
+                ("".to_string(), ContainsChanges),
+                ("/users".to_string(), ContainsChanges),
+                ("/users/0".to_string(), Added),
+                ("/users/1".to_string(), Unchanged),
+                // The removed element is shown after its predecessor in the old array, under its old index.
+                ("/users/2".to_string(), Removed),
+                ("/users/2".to_string(), ContainsChanges),
+            ]
+        );
+
+        // Without the pattern matching, elements are matched by index.
+        let options = JsonDiffOptions::new().align_arrays_at("/*/users", ArrayAlignment::Key("id"));
+        let diff = JsonDiffValue::with_options(&old, &new, &options);
+        let (entries, _) = diff.entries().unwrap();
+        let (users, _) = entries[0].value.entries().unwrap();
+        assert!(
+            users
+                .iter()
+                .all(|entry| entry.value.old_index == entry.value.new_index)
+        );
+    }
+
+    #[test]
+    fn removed_element_with_clashing_index_is_shown_as_non_string_key() {
+        let old = json!([{ "id": 1 }, { "id": 2 }]);
+        let new = json!([{ "id": 3 }, { "id": 4 }]);
+        let diff = JsonDiffValue::with_options(
+            &old,
+            &new,
+            &JsonDiffOptions::new().array_alignment(ArrayAlignment::Key("id")),
+        );
+        let JsonTreeValue::Expandable(entries, _) = diff.to_json_tree_value() else {
+            unreachable!()
+        };
+        let properties: Vec<_> = entries
+            .iter()
+            .map(|(property, value)| (format!("{property:?}"), value.status))
+            .collect();
+        assert_eq!(
+            properties,
+            [
+                ("NonStringKey(0)".to_string(), JsonDiffStatus::Removed),
+                ("NonStringKey(1)".to_string(), JsonDiffStatus::Removed),
+                ("Index(0)".to_string(), JsonDiffStatus::Added),
+                ("Index(1)".to_string(), JsonDiffStatus::Added),
+            ]
+        );
+    }
+}
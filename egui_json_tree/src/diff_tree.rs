@@ -0,0 +1,442 @@
+use crate::{
+    DefaultExpand, JsonTreeResponse, JsonTreeStyle, JsonTreeVisuals,
+    delimiters::ExpandableDelimiter,
+    diff::{JsonDiffOptions, JsonDiffSide, JsonDiffStatus, JsonDiffValue},
+    node::{JsonTreeNode, JsonTreeRoot},
+    render::{DefaultRender, RenderContext},
+    tree::JsonTreeConfig,
+    value::ToJsonTreeValue,
+};
+use egui::{AsId, Color32, Id, Ui};
+
+/// How a [`JsonDiffTree`] lays out the old and new values.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub enum JsonDiffLayout {
+    /// Show a single tree, in which removed entries are shown alongside added entries,
+    /// and modified non-recursive values are shown as `old → new`.
+    #[default]
+    Unified,
+    /// Show the old value and the new value in two columns, which expand and collapse together.
+    SideBySide,
+}
+
+/// The diff of two values laid out for a [`JsonDiffLayout`], as shown by a [`JsonDiffTree`].
+///
+/// [`JsonDiffTree::new`] computes the diff every frame. For large values, compute a [`JsonLayoutDiff`] once whenever the values change,
+/// and show it with [`JsonDiffTree::from_diff`] instead.
+///
+/// ```rust
+/// use egui_json_tree::{JsonDiffLayout, JsonDiffTree, JsonLayoutDiff, diff::JsonDiffOptions};
+///
+/// # egui::__run_test_ui(|ui| {
+/// let old = serde_json::json!({ "name": "egui", "tags": ["gui"] });
+/// let new = serde_json::json!({ "name": "egui_json_tree", "tags": ["gui"], "stars": 100 });
+/// let diff = JsonLayoutDiff::new(&old, &new, &JsonDiffOptions::new(), JsonDiffLayout::Unified);
+///
+/// JsonDiffTree::from_diff("diff", &diff).show(ui);
+/// # });
+/// ```
+pub struct JsonLayoutDiff<'a, T: ToJsonTreeValue + ?Sized> {
+    /// The diff shown in each column.
+    columns: Vec<JsonDiffValue<'a, T>>,
+}
+
+impl<'a, T: ToJsonTreeValue + ?Sized> JsonLayoutDiff<'a, T> {
+    /// Computes the diff from `old` to `new` for the given layout.
+    ///
+    /// If the whole value became a different type of value, e.g. an object that became an array,
+    /// the [`JsonDiffLayout::Unified`] layout shows the old and new values side by side.
+    pub fn new(
+        old: &'a T,
+        new: &'a T,
+        diff_options: &JsonDiffOptions,
+        layout: JsonDiffLayout,
+    ) -> Self {
+        let side_by_side = || {
+            vec![
+                JsonDiffValue::with_side(old, new, diff_options, JsonDiffSide::Old),
+                JsonDiffValue::with_side(old, new, diff_options, JsonDiffSide::New),
+            ]
+        };
+        let columns = match layout {
+            JsonDiffLayout::Unified => {
+                let diff = JsonDiffValue::with_options(old, new, diff_options);
+                if diff.removed_old().is_some() {
+                    side_by_side()
+                } else {
+                    vec![diff]
+                }
+            }
+            JsonDiffLayout::SideBySide => side_by_side(),
+        };
+        Self { columns }
+    }
+}
+
+/// The values of a [`JsonDiffTree`], or the diff of them that was computed already.
+enum JsonDiffSource<'a, T: ToJsonTreeValue + ?Sized> {
+    Values {
+        old: &'a T,
+        new: &'a T,
+        layout: JsonDiffLayout,
+        diff_options: JsonDiffOptions<'a>,
+    },
+    Diff(&'a JsonLayoutDiff<'a, T>),
+}
+
+type DiffRenderHook<'a, T> = dyn FnMut(&mut Ui, RenderContext<'_, '_, JsonDiffValue<'a, T>>) + 'a;
+
+/// An interactive visualiser for the structural differences between two JSON values.
+///
+/// Added, removed and modified values are highlighted with [`JsonTreeVisuals::diff_added_color`], [`JsonTreeVisuals::diff_removed_color`]
+/// and [`JsonTreeVisuals::diff_modified_color`] respectively, and the indices of moved array elements with [`JsonTreeVisuals::diff_moved_color`]. By default, arrays/objects that contain changes are expanded,
+/// and unchanged arrays/objects are collapsed.
+///
+/// Each node of the tree is a [`JsonDiffValue`], which records the [`JsonDiffStatus`] of the node and the old and new values,
+/// see the [`diff`](crate::diff) module for how the values are compared.
+///
+/// ```rust
+/// use egui_json_tree::{JsonDiffLayout, JsonDiffTree};
+///
+/// # egui::__run_test_ui(|ui| {
+/// let old = serde_json::json!({ "name": "egui", "tags": ["gui"] });
+/// let new = serde_json::json!({ "name": "egui_json_tree", "tags": ["gui"], "stars": 100 });
+///
+/// JsonDiffTree::new("diff", &old, &new)
+///     .layout(JsonDiffLayout::SideBySide)
+///     .show(ui);
+/// # });
+/// ```
+#[must_use = "You should call .show()"]
+pub struct JsonDiffTree<'a, T: ToJsonTreeValue + ?Sized> {
+    id: Id,
+    source: JsonDiffSource<'a, T>,
+    style: Option<JsonTreeStyle>,
+    default_expand: Option<DefaultExpand<'a>>,
+    auto_reset_expanded: bool,
+    render_hook: Option<Box<DiffRenderHook<'a, T>>>,
+}
+
+impl<'a, T: ToJsonTreeValue + ?Sized> JsonDiffTree<'a, T> {
+    /// Creates a new [`JsonDiffTree`] of the changes from `old` to `new`, which are compared every frame.
+    /// `id` must be a globally unique identifier.
+    pub fn new(id: impl AsId, old: &'a T, new: &'a T) -> Self {
+        Self::with_source(
+            id,
+            JsonDiffSource::Values {
+                old,
+                new,
+                layout: JsonDiffLayout::default(),
+                diff_options: JsonDiffOptions::default(),
+            },
+        )
+    }
+
+    /// Creates a new [`JsonDiffTree`] of a diff that was computed already, see [`JsonLayoutDiff`].
+    /// `id` must be a globally unique identifier.
+    pub fn from_diff(id: impl AsId, diff: &'a JsonLayoutDiff<'a, T>) -> Self {
+        Self::with_source(id, JsonDiffSource::Diff(diff))
+    }
+
+    fn with_source(id: impl AsId, source: JsonDiffSource<'a, T>) -> Self {
+        Self {
+            id: Id::new(id),
+            source,
+            style: None,
+            default_expand: None,
+            auto_reset_expanded: true,
+            render_hook: None,
+        }
+    }
+
+    /// Override whether the old and new values are shown in a single tree or side by side.
+    /// Defaults to [`JsonDiffLayout::Unified`].
+    ///
+    /// Has no effect on a tree created with [`JsonDiffTree::from_diff`], whose diff was computed for a layout already.
+    pub fn layout(mut self, layout: JsonDiffLayout) -> Self {
+        if let JsonDiffSource::Values { layout: l, .. } = &mut self.source {
+            *l = layout;
+        }
+        self
+    }
+
+    /// Override how the old and new values are compared, e.g. to match array elements by a key field.
+    /// Defaults to matching array elements by index.
+    ///
+    /// Has no effect on a tree created with [`JsonDiffTree::from_diff`], whose diff was computed with its options already.
+    pub fn diff_options(mut self, diff_options: JsonDiffOptions<'a>) -> Self {
+        if let JsonDiffSource::Values {
+            diff_options: options,
+            ..
+        } = &mut self.source
+        {
+            *options = diff_options;
+        }
+        self
+    }
+
+    /// Override colors for JSON syntax highlighting, search match highlighting, and changed values.
+    pub fn style(mut self, style: JsonTreeStyle) -> Self {
+        self.style = Some(style);
+        self
+    }
+
+    /// Override how the [`JsonDiffTree`] expands arrays/objects by default.
+    /// Defaults to expanding only the arrays/objects that contain changes.
+    pub fn default_expand(mut self, default_expand: DefaultExpand<'a>) -> Self {
+        self.default_expand = Some(default_expand);
+        self
+    }
+
+    /// If enabled, automatically reset expanded arrays/objects to respect the [`DefaultExpand`] setting when it changes for this tree Id.
+    /// See [`JsonTree::auto_reset_expanded`](crate::JsonTree::auto_reset_expanded).
+    /// Defaults to enabled.
+    pub fn auto_reset_expanded(mut self, auto_reset_expanded: bool) -> Self {
+        self.auto_reset_expanded = auto_reset_expanded;
+        self
+    }
+
+    /// A convenience method for conditionally registering a custom rendering hook.
+    /// See [`JsonDiffTree::on_render`].
+    pub fn on_render_if(
+        self,
+        condition: bool,
+        render_hook: impl FnMut(&mut Ui, RenderContext<'_, '_, JsonDiffValue<'a, T>>) + 'a,
+    ) -> Self {
+        if condition {
+            self.on_render(render_hook)
+        } else {
+            self
+        }
+    }
+
+    /// Customise rendering of the [`JsonDiffTree`], and/or handle interactions.
+    /// See [`JsonTree::on_render`](crate::JsonTree::on_render).
+    ///
+    /// The value of each render call is a [`JsonDiffValue`], so the hook can inspect its [`JsonDiffStatus`].
+    /// Calling [`render_default`](DefaultRender::render_default) includes the background color of its status.
+    pub fn on_render(
+        mut self,
+        render_hook: impl FnMut(&mut Ui, RenderContext<'_, '_, JsonDiffValue<'a, T>>) + 'a,
+    ) -> Self {
+        self.render_hook = Some(Box::new(render_hook));
+        self
+    }
+
+    /// Show the diff within the `Ui`.
+    ///
+    /// Note that the export methods of the returned [`JsonTreeResponse`], e.g. [`JsonTreeResponse::to_text`],
+    /// only support the value of a [`JsonTree`](crate::JsonTree), not the diff of a [`JsonDiffTree`].
+    pub fn show(self, ui: &mut Ui) -> JsonTreeResponse {
+        let computed_diff;
+        let diff = match self.source {
+            JsonDiffSource::Values {
+                old,
+                new,
+                layout,
+                diff_options,
+            } => {
+                computed_diff = JsonLayoutDiff::new(old, new, &diff_options, layout);
+                &computed_diff
+            }
+            JsonDiffSource::Diff(diff) => diff,
+        };
+        let columns: Vec<_> = diff
+            .columns
+            .iter()
+            .map(|value| {
+                [JsonTreeRoot {
+                    value,
+                    document: None,
+                }]
+            })
+            .collect();
+        let columns: Vec<&[JsonTreeRoot<_>]> = columns.iter().map(|roots| &roots[..]).collect();
+
+        let mut config = JsonTreeConfig {
+            style: self.style,
+            default_expand: self.default_expand,
+            auto_reset_expanded: self.auto_reset_expanded,
+            default_expanded: Some(|value: &JsonDiffValue<'a, T>| {
+                matches!(
+                    value.status,
+                    JsonDiffStatus::ContainsChanges | JsonDiffStatus::Modified
+                )
+            }),
+            ..Default::default()
+        };
+        let mut user_render_hook = self.render_hook;
+        config.renderer.render_hook = Some(Box::new(move |ui, mut context| {
+            set_status_background(ui, &mut context);
+            match user_render_hook.as_mut() {
+                Some(render_hook) => render_hook(ui, context),
+                None => {
+                    context.render_default(ui);
+                }
+            }
+        }));
+
+        JsonTreeNode::show_roots(self.id, config, &columns, ui)
+    }
+}
+
+/// Sets the background of the element being rendered to the color of the [`JsonDiffStatus`] of its value.
+fn set_status_background<T: ToJsonTreeValue + ?Sized>(
+    ui: &Ui,
+    context: &mut RenderContext<'_, '_, JsonDiffValue<'_, T>>,
+) {
+    let status = context.value().status;
+    let (background, style) = match context {
+        // Highlight the index of moved array elements.
+        RenderContext::Property(context) if context.value.moved => {
+            context.background = Some(context.style.resolve_visuals(ui).diff_moved_color);
+            return;
+        }
+        RenderContext::Property(context) => (&mut context.background, context.style),
+        RenderContext::BaseValue(context) => (&mut context.background, context.style),
+        RenderContext::ExpandableDelimiter(context) => {
+            let collapsed = matches!(
+                context.delimiter,
+                ExpandableDelimiter::CollapsedArray | ExpandableDelimiter::CollapsedObject
+            );
+            // Highlight collapsed arrays/objects that hide changes.
+            if collapsed && status == JsonDiffStatus::ContainsChanges {
+                context.background = Some(context.style.resolve_visuals(ui).diff_modified_color);
+                return;
+            }
+            (&mut context.background, context.style)
+        }
+    };
+    if let Some(color) = status_color(style.resolve_visuals(ui), status) {
+        *background = Some(color);
+    }
+}
+
+fn status_color(visuals: &JsonTreeVisuals, status: JsonDiffStatus) -> Option<Color32> {
+    match status {
+        JsonDiffStatus::Added => Some(visuals.diff_added_color),
+        JsonDiffStatus::Removed => Some(visuals.diff_removed_color),
+        JsonDiffStatus::Modified => Some(visuals.diff_modified_color),
+        JsonDiffStatus::Unchanged | JsonDiffStatus::ContainsChanges => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::LazyLock;
+
+    use egui::accesskit::Role;
+    use egui_kittest::{Harness, kittest::Queryable};
+    use serde_json::{Value, json};
+
+    use crate::{JsonDiffLayout, JsonDiffTree, JsonLayoutDiff, diff::JsonDiffOptions};
+
+    static OLD: LazyLock<Value> =
+        LazyLock::new(|| json!({ "same": { "a": 1 }, "changed": { "b": 2 }, "removed": null }));
+    static NEW: LazyLock<Value> =
+        LazyLock::new(|| json!({ "same": { "a": 1 }, "changed": { "b": 3 }, "added": true }));
+
+    #[test]
+    fn render_unified_diff_expanding_changed_subtrees() {
+        let harness = Harness::new_ui(|ui| {
+            JsonDiffTree::new("id", &*OLD, &*NEW).show(ui);
+        });
+
+        assert!(harness.query_by_label("\"removed\"").is_some());
+        assert!(harness.query_by_label("\"added\"").is_some());
+        assert!(harness.query_by_label("2 → 3").is_some());
+        // The unchanged object is collapsed.
+        assert!(harness.query_by_label("\"a\"").is_none());
+    }
+
+    #[test]
+    fn render_side_by_side_diff() {
+        let harness = Harness::new_ui(|ui| {
+            JsonDiffTree::new("id", &*OLD, &*NEW)
+                .layout(JsonDiffLayout::SideBySide)
+                .show(ui);
+        });
+
+        assert_eq!(
+            harness
+                .query_all_by_role_and_label(Role::Button, "/changed")
+                .count(),
+            2
+        );
+        assert_eq!(harness.query_all_by_label("\"removed\"").count(), 1);
+        assert_eq!(harness.query_all_by_label("\"added\"").count(), 1);
+        assert!(harness.query_by_label("2").is_some());
+        assert!(harness.query_by_label("3").is_some());
+    }
+
+    #[test]
+    fn side_by_side_columns_expand_and_collapse_together() {
+        let mut harness = Harness::new_ui(|ui| {
+            JsonDiffTree::new("id", &*OLD, &*NEW)
+                .layout(JsonDiffLayout::SideBySide)
+                .show(ui);
+        });
+        assert_eq!(harness.query_all_by_label("\"a\"").count(), 0);
+
+        harness
+            .query_all_by_role_and_label(Role::Button, "/same")
+            .nth(1)
+            .unwrap()
+            .click();
+        harness.run();
+        assert_eq!(harness.query_all_by_label("\"a\"").count(), 2);
+    }
+
+    #[test]
+    fn render_precomputed_diff() {
+        let diff = JsonLayoutDiff::new(
+            &*OLD,
+            &*NEW,
+            &JsonDiffOptions::new(),
+            JsonDiffLayout::Unified,
+        );
+        let harness = Harness::new_ui(|ui| {
+            JsonDiffTree::from_diff("id", &diff).show(ui);
+        });
+
+        assert!(harness.query_by_label("2 → 3").is_some());
+    }
+
+    #[test]
+    fn render_removed_old_value_of_unified_type_change() {
+        let old = json!({ "value": { "old": 1 } });
+        let new = json!({ "value": "new" });
+        let harness = Harness::new_ui(|ui| {
+            JsonDiffTree::new("id", &old, &new).show(ui);
+        });
+
+        assert!(harness.query_by_label("\"value\"").is_some());
+        // The key of the removed old value is a `RemovedKey`, rather than a duplicate key.
+        assert!(harness.query_by_label("value").is_some());
+        // The removed old object is collapsed like other removed values.
+        assert!(
+            harness
+                .query_by_role_and_label(Role::Button, "/value")
+                .is_some()
+        );
+        assert!(harness.query_by_label("\"new\"").is_some());
+    }
+
+    #[test]
+    fn render_unified_root_type_change_side_by_side() {
+        let old = json!({ "old": 1 });
+        let new = json!(["new"]);
+        let harness = Harness::new_ui(|ui| {
+            JsonDiffTree::new("id", &old, &new).show(ui);
+        });
+
+        assert_eq!(
+            harness
+                .query_all_by_role_and_label(Role::Button, "")
+                .count(),
+            2
+        );
+        assert!(harness.query_by_label("\"old\"").is_some());
+        assert!(harness.query_by_label("\"new\"").is_some());
+    }
+}
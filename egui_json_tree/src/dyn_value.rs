@@ -0,0 +1,416 @@
+//! Visualisation of trees of different Rust types, e.g. your own domain types, without converting them to a JSON value type first.
+//!
+//! [`ToJsonTreeValue`] requires every array element and object entry to be of the same type as its parent,
+//! which suits JSON value types, but not structs whose fields have different types.
+//! [`ToDynJsonTreeValue`] lifts this restriction by returning each child as a `&dyn ToDynJsonTreeValue`,
+//! and `dyn ToDynJsonTreeValue` implements [`ToJsonTreeValue`], so any implementing type can be shown in a [`JsonTree`](crate::JsonTree).
+//!
+//! [`ToDynJsonTreeValue`] is implemented for primitives, strings, `Option`, `Box` and the standard collections,
+//! as well as `serde_json::Value` when the `serde_json` feature is enabled.
+//!
+//! With the `derive` feature enabled, `#[derive(ToDynJsonTreeValue)]` implements it for structs and enums, mapping them in the same way as `serde_json`:
+//! - Structs with named fields are an object.
+//! - Newtype structs (e.g. `struct Meters(u32);`) are their inner value.
+//! - Tuple structs (e.g. `struct Point(i32, i32);`) are an array.
+//! - Unit structs (e.g. `struct Unit;`) are `null`.
+//! - Enum variants are externally tagged:
+//!   - A unit variant (e.g. `E::A`) is a string of the variant name, e.g. `"A"`.
+//!   - Any other variant (e.g. `E::B(1)`, `E::C(1, 2)` or `E::D { x: 1 }`) is an object with a single entry,
+//!     keyed by the variant name, of its inner value, e.g. `{ "B": 1 }`, `{ "C": [1, 2] }` or `{ "D": { "x": 1 } }`.
+//!
+//! The derive supports the following attributes:
+//! - `#[json_tree(rename = "name")]` on a named field or enum variant, to show it under a different key.
+//! - `#[json_tree(skip)]` on a field, to leave it out of the tree.
+//! - `#[json_tree(display)]` on a field, to show it as a leaf string via its [`Display`] implementation, using [`DisplayLeaf`].
+//!   This is useful for field types that do not implement [`ToDynJsonTreeValue`], e.g. UUIDs or dates.
+//!
+//! Since the children are trait objects, the derive only supports types without lifetime parameters.
+//! Derived types implement [`ToDynJsonTreeValue`] rather than [`ToJsonTreeValue`], so pass them to a [`JsonTree`](crate::JsonTree)
+//! as a `&dyn ToDynJsonTreeValue`, as shown below.
+//!
+//! ```rust
+//! # #[cfg(feature = "derive")] {
+//! # use egui_json_tree::{dyn_value::ToDynJsonTreeValue, serialize::JsonSerializer, JsonTree};
+//! #[derive(ToDynJsonTreeValue)]
+//! struct User {
+//!     #[json_tree(rename = "userName")]
+//!     name: String,
+//!     #[json_tree(skip)]
+//!     password: String,
+//!     #[json_tree(display)]
+//!     address: std::net::Ipv4Addr,
+//!     roles: Vec<Role>,
+//! }
+//!
+//! #[derive(ToDynJsonTreeValue)]
+//! enum Role {
+//!     Admin,
+//!     Member { team: String },
+//! }
+//!
+//! let user = User {
+//!     name: "Ferris".to_string(),
+//!     password: "hunter2".to_string(),
+//!     address: std::net::Ipv4Addr::LOCALHOST,
+//!     roles: vec![Role::Admin, Role::Member { team: "egui".to_string() }],
+//! };
+//!
+//! let value: &dyn ToDynJsonTreeValue = &user;
+//! assert_eq!(
+//!     JsonSerializer::new().to_string(value),
+//!     r#"{"userName":"Ferris","address":"127.0.0.1","roles":["Admin",{"Member":{"team":"egui"}}]}"#
+//! );
+//!
+//! # egui::__run_test_ui(|ui| {
+//! JsonTree::new("user-tree", value).show(ui);
+//! # });
+//! # }
+//! ```
+
+use std::{
+    collections::{BTreeMap, BTreeSet, HashMap, HashSet, VecDeque},
+    fmt::Display,
+};
+
+use ref_cast::RefCast;
+
+use crate::{
+    pointer::JsonPointerSegment,
+    value::{BaseValueType, ExpandableType, JsonTreeValue, ToJsonTreeValue},
+};
+
+#[cfg(feature = "derive")]
+pub use egui_json_tree_derive::ToDynJsonTreeValue;
+
+/// An object-safe counterpart of [`ToJsonTreeValue`], whose children may be of different types, see the [module docs](self).
+pub trait ToDynJsonTreeValue {
+    /// Converts this value to a [`JsonTreeValue`], whose children are trait objects.
+    fn to_dyn_json_tree_value(&self) -> JsonTreeValue<'_, dyn ToDynJsonTreeValue>;
+    /// Returns whether this value is expandable, i.e. whether it is shown as an object or an array.
+    fn is_expandable(&self) -> bool;
+}
+
+impl ToJsonTreeValue for dyn ToDynJsonTreeValue {
+    fn to_json_tree_value(&self) -> JsonTreeValue<'_, Self> {
+        self.to_dyn_json_tree_value()
+    }
+
+    fn is_expandable(&self) -> bool {
+        ToDynJsonTreeValue::is_expandable(self)
+    }
+}
+
+/// Shows a value as a leaf string via its [`Display`] implementation.
+///
+/// This is used by `#[json_tree(display)]` fields of `#[derive(ToDynJsonTreeValue)]`,
+/// and can be used to show values of types that do not implement [`ToDynJsonTreeValue`].
+///
+/// ```rust
+/// # use egui_json_tree::{dyn_value::{DisplayLeaf, ToDynJsonTreeValue}, serialize::JsonSerializer};
+/// let address = std::net::Ipv4Addr::LOCALHOST;
+/// let value: &dyn ToDynJsonTreeValue = DisplayLeaf::new(&address);
+/// assert_eq!(JsonSerializer::new().to_string(value), r#""127.0.0.1""#);
+/// ```
+#[derive(RefCast)]
+#[repr(transparent)]
+pub struct DisplayLeaf<T: Display>(T);
+
+impl<T: Display> DisplayLeaf<T> {
+    pub fn new(value: &T) -> &Self {
+        Self::ref_cast(value)
+    }
+}
+
+impl<T: Display + 'static> ToDynJsonTreeValue for DisplayLeaf<T> {
+    fn to_dyn_json_tree_value(&self) -> JsonTreeValue<'_, dyn ToDynJsonTreeValue> {
+        JsonTreeValue::Base(self, &self.0, BaseValueType::String)
+    }
+
+    fn is_expandable(&self) -> bool {
+        false
+    }
+}
+
+/// The fields of a tuple or struct enum variant, shown as the value of the single entry keyed by the variant name.
+///
+/// Used by `#[derive(ToDynJsonTreeValue)]`, not intended to be used directly.
+#[doc(hidden)]
+#[derive(RefCast)]
+#[repr(transparent)]
+pub struct VariantFields<T>(T);
+
+impl<T> VariantFields<T> {
+    pub fn new(value: &T) -> &Self {
+        Self::ref_cast(value)
+    }
+}
+
+/// Converts the fields of the current tuple or struct variant of an enum to an array or object.
+///
+/// Implemented by `#[derive(ToDynJsonTreeValue)]`, not intended to be implemented directly.
+#[doc(hidden)]
+pub trait ToDynJsonTreeVariantFields {
+    fn to_dyn_json_tree_variant_fields(&self) -> JsonTreeValue<'_, dyn ToDynJsonTreeValue>;
+}
+
+impl<T: ToDynJsonTreeVariantFields + 'static> ToDynJsonTreeValue for VariantFields<T> {
+    fn to_dyn_json_tree_value(&self) -> JsonTreeValue<'_, dyn ToDynJsonTreeValue> {
+        self.0.to_dyn_json_tree_variant_fields()
+    }
+
+    fn is_expandable(&self) -> bool {
+        true
+    }
+}
+
+/// A type that can be shown as the key of an object entry by the [`ToDynJsonTreeValue`] implementations of maps.
+///
+/// String keys are shown as [`JsonPointerSegment::Key`], and any other keys as [`JsonPointerSegment::NonStringKey`].
+pub trait ToJsonTreeKey {
+    /// Converts this key to a [`JsonPointerSegment`].
+    fn to_json_tree_key(&self) -> JsonPointerSegment<'_>;
+}
+
+impl ToJsonTreeKey for str {
+    fn to_json_tree_key(&self) -> JsonPointerSegment<'_> {
+        JsonPointerSegment::Key(self)
+    }
+}
+
+impl ToJsonTreeKey for String {
+    fn to_json_tree_key(&self) -> JsonPointerSegment<'_> {
+        JsonPointerSegment::Key(self)
+    }
+}
+
+impl<T: ToJsonTreeKey + ?Sized> ToJsonTreeKey for &T {
+    fn to_json_tree_key(&self) -> JsonPointerSegment<'_> {
+        (**self).to_json_tree_key()
+    }
+}
+
+macro_rules! impl_non_string_keys {
+    ($($ty:ty),*) => {
+        $(
+            impl ToJsonTreeKey for $ty {
+                fn to_json_tree_key(&self) -> JsonPointerSegment<'_> {
+                    JsonPointerSegment::NonStringKey(self)
+                }
+            }
+        )*
+    };
+}
+
+impl_non_string_keys!(
+    bool, char, i8, i16, i32, i64, i128, isize, u8, u16, u32, u64, u128, usize
+);
+
+macro_rules! impl_base_values {
+    ($value_type:expr => $($ty:ty),*) => {
+        $(
+            impl ToDynJsonTreeValue for $ty {
+                fn to_dyn_json_tree_value(&self) -> JsonTreeValue<'_, dyn ToDynJsonTreeValue> {
+                    JsonTreeValue::Base(self, self, $value_type)
+                }
+
+                fn is_expandable(&self) -> bool {
+                    false
+                }
+            }
+        )*
+    };
+}
+
+impl_base_values!(BaseValueType::Bool => bool);
+impl_base_values!(BaseValueType::Number => i8, i16, i32, i64, i128, isize, u8, u16, u32, u64, u128, usize);
+impl_base_values!(BaseValueType::String => char, String, &'static str);
+
+macro_rules! impl_float_values {
+    ($($ty:ty),*) => {
+        $(
+            impl ToDynJsonTreeValue for $ty {
+                fn to_dyn_json_tree_value(&self) -> JsonTreeValue<'_, dyn ToDynJsonTreeValue> {
+                    // Non-finite floats have no JSON representation, so are shown as null like `serde_json` does.
+                    if self.is_finite() {
+                        JsonTreeValue::Base(self, self, BaseValueType::Number)
+                    } else {
+                        JsonTreeValue::Base(self, &"null", BaseValueType::Null)
+                    }
+                }
+
+                fn is_expandable(&self) -> bool {
+                    false
+                }
+            }
+        )*
+    };
+}
+
+impl_float_values!(f32, f64);
+
+impl ToDynJsonTreeValue for () {
+    fn to_dyn_json_tree_value(&self) -> JsonTreeValue<'_, dyn ToDynJsonTreeValue> {
+        JsonTreeValue::Base(self, &"null", BaseValueType::Null)
+    }
+
+    fn is_expandable(&self) -> bool {
+        false
+    }
+}
+
+impl<T: ToDynJsonTreeValue + 'static> ToDynJsonTreeValue for Option<T> {
+    fn to_dyn_json_tree_value(&self) -> JsonTreeValue<'_, dyn ToDynJsonTreeValue> {
+        match self {
+            Some(value) => value.to_dyn_json_tree_value(),
+            None => JsonTreeValue::Base(self, &"null", BaseValueType::Null),
+        }
+    }
+
+    fn is_expandable(&self) -> bool {
+        self.as_ref().is_some_and(ToDynJsonTreeValue::is_expandable)
+    }
+}
+
+impl<T: ToDynJsonTreeValue + ?Sized> ToDynJsonTreeValue for Box<T> {
+    fn to_dyn_json_tree_value(&self) -> JsonTreeValue<'_, dyn ToDynJsonTreeValue> {
+        (**self).to_dyn_json_tree_value()
+    }
+
+    fn is_expandable(&self) -> bool {
+        (**self).is_expandable()
+    }
+}
+
+fn array<'a, T: ToDynJsonTreeValue + 'static>(
+    elems: impl Iterator<Item = &'a T>,
+) -> JsonTreeValue<'a, dyn ToDynJsonTreeValue> {
+    JsonTreeValue::Expandable(
+        elems
+            .enumerate()
+            .map(|(idx, elem)| {
+                let elem: &dyn ToDynJsonTreeValue = elem;
+                (JsonPointerSegment::Index(idx), elem)
+            })
+            .collect(),
+        ExpandableType::Array,
+    )
+}
+
+fn object<'a, K: ToJsonTreeKey + 'a, V: ToDynJsonTreeValue + 'static>(
+    entries: impl Iterator<Item = (&'a K, &'a V)>,
+) -> JsonTreeValue<'a, dyn ToDynJsonTreeValue> {
+    JsonTreeValue::Expandable(
+        entries
+            .map(|(key, val)| {
+                let val: &dyn ToDynJsonTreeValue = val;
+                (key.to_json_tree_key(), val)
+            })
+            .collect(),
+        ExpandableType::Object,
+    )
+}
+
+impl<T: ToDynJsonTreeValue + 'static> ToDynJsonTreeValue for [T] {
+    fn to_dyn_json_tree_value(&self) -> JsonTreeValue<'_, dyn ToDynJsonTreeValue> {
+        array(self.iter())
+    }
+
+    fn is_expandable(&self) -> bool {
+        true
+    }
+}
+
+impl<T: ToDynJsonTreeValue + 'static, const N: usize> ToDynJsonTreeValue for [T; N] {
+    fn to_dyn_json_tree_value(&self) -> JsonTreeValue<'_, dyn ToDynJsonTreeValue> {
+        array(self.iter())
+    }
+
+    fn is_expandable(&self) -> bool {
+        true
+    }
+}
+
+impl<T: ToDynJsonTreeValue + 'static> ToDynJsonTreeValue for Vec<T> {
+    fn to_dyn_json_tree_value(&self) -> JsonTreeValue<'_, dyn ToDynJsonTreeValue> {
+        array(self.iter())
+    }
+
+    fn is_expandable(&self) -> bool {
+        true
+    }
+}
+
+impl<T: ToDynJsonTreeValue + 'static> ToDynJsonTreeValue for VecDeque<T> {
+    fn to_dyn_json_tree_value(&self) -> JsonTreeValue<'_, dyn ToDynJsonTreeValue> {
+        array(self.iter())
+    }
+
+    fn is_expandable(&self) -> bool {
+        true
+    }
+}
+
+impl<T: ToDynJsonTreeValue + 'static> ToDynJsonTreeValue for BTreeSet<T> {
+    fn to_dyn_json_tree_value(&self) -> JsonTreeValue<'_, dyn ToDynJsonTreeValue> {
+        array(self.iter())
+    }
+
+    fn is_expandable(&self) -> bool {
+        true
+    }
+}
+
+impl<T: ToDynJsonTreeValue + 'static, S> ToDynJsonTreeValue for HashSet<T, S> {
+    fn to_dyn_json_tree_value(&self) -> JsonTreeValue<'_, dyn ToDynJsonTreeValue> {
+        array(self.iter())
+    }
+
+    fn is_expandable(&self) -> bool {
+        true
+    }
+}
+
+impl<K: ToJsonTreeKey, V: ToDynJsonTreeValue + 'static> ToDynJsonTreeValue for BTreeMap<K, V> {
+    fn to_dyn_json_tree_value(&self) -> JsonTreeValue<'_, dyn ToDynJsonTreeValue> {
+        object(self.iter())
+    }
+
+    fn is_expandable(&self) -> bool {
+        true
+    }
+}
+
+impl<K: ToJsonTreeKey, V: ToDynJsonTreeValue + 'static, S> ToDynJsonTreeValue for HashMap<K, V, S> {
+    fn to_dyn_json_tree_value(&self) -> JsonTreeValue<'_, dyn ToDynJsonTreeValue> {
+        object(self.iter())
+    }
+
+    fn is_expandable(&self) -> bool {
+        true
+    }
+}
+
+#[cfg(feature = "serde_json")]
+impl ToDynJsonTreeValue for serde_json::Value {
+    fn to_dyn_json_tree_value(&self) -> JsonTreeValue<'_, dyn ToDynJsonTreeValue> {
+        match self.to_json_tree_value() {
+            JsonTreeValue::Base(value, display_value, value_type) => {
+                JsonTreeValue::Base(value, display_value, value_type)
+            }
+            JsonTreeValue::Expandable(entries, expandable_type) => JsonTreeValue::Expandable(
+                entries
+                    .into_iter()
+                    .map(|(property, val)| {
+                        let val: &dyn ToDynJsonTreeValue = val;
+                        (property, val)
+                    })
+                    .collect(),
+                expandable_type,
+            ),
+        }
+    }
+
+    fn is_expandable(&self) -> bool {
+        ToJsonTreeValue::is_expandable(self)
+    }
+}
@@ -0,0 +1,544 @@
+//! Edits of a JSON document, as made via a [`JsonTreeEditor`](crate::JsonTreeEditor).
+//!
+//! Each change made in the editor is reported as a [`JsonEditEvent`], which identifies the changed values by JSON pointer strings,
+//! e.g. `/foo/0`. The editor applies the events to the document via [`EditableJsonValue::apply_edit`] before returning them,
+//! so they can be used to keep other state in sync with the document.
+//!
+//! [`EditableJsonValue`] is implemented for `serde_json::Value` when the `serde_json` feature is enabled.
+//! Implement it for your own JSON type to edit it with a [`JsonTreeEditor`](crate::JsonTreeEditor).
+//!
+//! ```rust
+//! # use egui_json_tree::edit::{EditableJsonValue, JsonEditEvent};
+//! # use serde_json::json;
+//! let mut value = json!({ "name": "egui", "tags": ["gui"] });
+//! let events = [
+//!     JsonEditEvent::Replace { pointer: "/name".to_string(), value: json!("egui_json_tree") },
+//!     JsonEditEvent::Add { pointer: "/tags/1".to_string(), value: json!("json") },
+//!     JsonEditEvent::RenameKey {
+//!         object_pointer: "".to_string(),
+//!         key: "tags".to_string(),
+//!         new_key: "topics".to_string(),
+//!     },
+//! ];
+//! for event in &events {
+//!     value.apply_edit(event).unwrap();
+//! }
+//! assert_eq!(value, json!({ "name": "egui_json_tree", "topics": ["gui", "json"] }));
+//! ```
+
+use std::fmt;
+
+#[cfg(feature = "serde_json")]
+use crate::pointer::split_json_pointer;
+use crate::{
+    JsonParseError,
+    value::{BaseValueType, ExpandableType, JsonTreeValue, ToJsonTreeValue},
+};
+
+/// A change to a JSON document, in which values are identified by JSON pointer strings.
+///
+/// The variants correspond to the `add`, `remove`, `replace` and `move` operations of a [JSON Patch](https://www.rfc-editor.org/rfc/rfc6902),
+/// with the addition of [`JsonEditEvent::RenameKey`].
+#[derive(Debug, Clone, PartialEq)]
+pub enum JsonEditEvent<T> {
+    /// The value at `pointer` was replaced, after editing it or changing its type.
+    Replace { pointer: String, value: T },
+    /// The entry with `key` of the object at `object_pointer` was renamed to `new_key`, keeping its value.
+    RenameKey {
+        object_pointer: String,
+        key: String,
+        new_key: String,
+    },
+    /// The value was added at `pointer`, as a new entry of an object, or inserted into an array before the element at the index of `pointer`.
+    Add { pointer: String, value: T },
+    /// The object entry or array element at `pointer` was removed.
+    Remove { pointer: String },
+    /// The object entry or array element at `from` was removed, and added at `pointer`, e.g. after dragging it.
+    ///
+    /// As in a JSON Patch, `pointer` identifies the location of the value after it was removed from `from`,
+    /// so moving the first element of a three-element array to the end moves it to index 2.
+    Move { from: String, pointer: String },
+}
+
+/// An error encountered when applying a [`JsonEditEvent`] to a JSON document.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum JsonEditError {
+    /// The pointer does not identify a value within the document.
+    NotFound(String),
+    /// The object already contains an entry with the key that the pointer would add.
+    KeyExists(String),
+    /// The pointer cannot identify a new value, because its parent is not an array or object, or it is an array index out of bounds.
+    InvalidTarget(String),
+}
+
+impl fmt::Display for JsonEditError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            JsonEditError::NotFound(pointer) => write!(f, "no value at \"{pointer}\""),
+            JsonEditError::KeyExists(pointer) => {
+                write!(f, "a value already exists at \"{pointer}\"")
+            }
+            JsonEditError::InvalidTarget(pointer) => {
+                write!(f, "cannot add a value at \"{pointer}\"")
+            }
+        }
+    }
+}
+
+impl std::error::Error for JsonEditError {}
+
+/// The type of a JSON value, which a value can be changed to in a [`JsonTreeEditor`](crate::JsonTreeEditor).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum JsonEditType {
+    Null,
+    Bool,
+    Number,
+    String,
+    Array,
+    Object,
+}
+
+impl JsonEditType {
+    /// All types, in the order they are offered by the editor.
+    pub const ALL: [JsonEditType; 6] = [
+        JsonEditType::Null,
+        JsonEditType::Bool,
+        JsonEditType::Number,
+        JsonEditType::String,
+        JsonEditType::Array,
+        JsonEditType::Object,
+    ];
+
+    /// Returns the type of a value, or `None` for values that are not part of JSON, e.g. [`BaseValueType::Bytes`].
+    pub fn of<T: ToJsonTreeValue + ?Sized>(value: &T) -> Option<Self> {
+        match value.to_json_tree_value() {
+            JsonTreeValue::Base(_, _, value_type) => match value_type {
+                BaseValueType::Null => Some(JsonEditType::Null),
+                BaseValueType::Bool => Some(JsonEditType::Bool),
+                BaseValueType::Number => Some(JsonEditType::Number),
+                BaseValueType::String => Some(JsonEditType::String),
+                BaseValueType::Bytes | BaseValueType::Other(_) => None,
+            },
+            JsonTreeValue::Expandable(_, ExpandableType::Array) => Some(JsonEditType::Array),
+            JsonTreeValue::Expandable(_, ExpandableType::Object) => Some(JsonEditType::Object),
+        }
+    }
+
+    /// The label of this type in the editor's context menu.
+    pub fn label(&self) -> &'static str {
+        match self {
+            JsonEditType::Null => "Null",
+            JsonEditType::Bool => "Bool",
+            JsonEditType::Number => "Number",
+            JsonEditType::String => "String",
+            JsonEditType::Array => "Array",
+            JsonEditType::Object => "Object",
+        }
+    }
+}
+
+/// A trait for JSON types that can be edited by a [`JsonTreeEditor`](crate::JsonTreeEditor).
+pub trait EditableJsonValue: ToJsonTreeValue + Clone {
+    /// Parses the text that a non-recursive value was edited to.
+    ///
+    /// The `serde_json::Value` implementation parses the text as JSON, falling back to a string of the text as-is,
+    /// so that strings can be entered without quotes.
+    fn parse_input(input: &str) -> Self;
+    /// Parses JSON text, e.g. as pasted into the editor, reporting where the text is invalid.
+    fn parse_json(input: &str) -> Result<Self, JsonParseError>;
+    /// Returns the text with which to start editing this non-recursive value.
+    /// The default implementation serializes the value as compact JSON.
+    fn to_input(&self) -> String {
+        self.to_json_string(false)
+    }
+    /// Returns the default value of the given type, e.g. `0` for [`JsonEditType::Number`].
+    ///
+    /// This is used when changing the type of a value, and new entries are [`JsonEditType::Null`].
+    fn default_of_type(value_type: JsonEditType) -> Self;
+    /// Applies an edit to this document.
+    fn apply_edit(&mut self, event: &JsonEditEvent<Self>) -> Result<(), JsonEditError>;
+}
+
+#[cfg(feature = "serde_json")]
+impl EditableJsonValue for serde_json::Value {
+    fn parse_input(input: &str) -> Self {
+        serde_json::from_str(input).unwrap_or_else(|_| serde_json::Value::String(input.to_string()))
+    }
+
+    fn parse_json(input: &str) -> Result<Self, JsonParseError> {
+        serde_json::from_str(input).map_err(|error| {
+            let (line, column) = (error.line(), error.column());
+            let message = error.to_string();
+            let position = format!(" at line {line} column {column}");
+            let message = message.strip_suffix(&position).unwrap_or(&message);
+            // serde_json counts columns in bytes.
+            let line_start: usize = input
+                .split_inclusive('\n')
+                .take(line.saturating_sub(1))
+                .map(str::len)
+                .sum();
+            let offset = line_start + column.saturating_sub(1);
+            JsonParseError::new(input, offset, message.to_string())
+        })
+    }
+
+    fn to_input(&self) -> String {
+        self.to_string()
+    }
+
+    fn default_of_type(value_type: JsonEditType) -> Self {
+        match value_type {
+            JsonEditType::Null => serde_json::Value::Null,
+            JsonEditType::Bool => serde_json::Value::Bool(false),
+            JsonEditType::Number => serde_json::Value::from(0),
+            JsonEditType::String => serde_json::Value::String(String::new()),
+            JsonEditType::Array => serde_json::Value::Array(vec![]),
+            JsonEditType::Object => serde_json::Value::Object(serde_json::Map::new()),
+        }
+    }
+
+    fn apply_edit(&mut self, event: &JsonEditEvent<Self>) -> Result<(), JsonEditError> {
+        match event {
+            JsonEditEvent::Replace { pointer, value } => {
+                let target = self
+                    .pointer_mut(pointer)
+                    .ok_or_else(|| JsonEditError::NotFound(pointer.clone()))?;
+                *target = value.clone();
+            }
+            JsonEditEvent::RenameKey {
+                object_pointer,
+                key,
+                new_key,
+            } => {
+                let object = self
+                    .pointer_mut(object_pointer)
+                    .and_then(serde_json::Value::as_object_mut)
+                    .ok_or_else(|| JsonEditError::NotFound(object_pointer.clone()))?;
+                if key == new_key {
+                    return Ok(());
+                }
+                if object.contains_key(new_key) {
+                    return Err(JsonEditError::KeyExists(new_key.clone()));
+                }
+                if !object.contains_key(key) {
+                    return Err(JsonEditError::NotFound(key.clone()));
+                }
+                // Rebuild the object rather than removing and inserting the entry, so that the entry keeps its position
+                // if serde_json's `preserve_order` feature is enabled. `Map::shift_insert` is only available with that feature,
+                // which may be enabled by another crate without this crate knowing.
+                *object = std::mem::take(object)
+                    .into_iter()
+                    .map(|(entry_key, value)| {
+                        if entry_key == *key {
+                            (new_key.clone(), value)
+                        } else {
+                            (entry_key, value)
+                        }
+                    })
+                    .collect();
+            }
+            JsonEditEvent::Add { pointer, value } => {
+                add_serde_json_value(self, pointer, value.clone())?;
+            }
+            JsonEditEvent::Remove { pointer } => {
+                remove_serde_json_value(self, pointer)?;
+            }
+            JsonEditEvent::Move { from, pointer } => {
+                if pointer.starts_with(&format!("{from}/")) {
+                    return Err(JsonEditError::InvalidTarget(pointer.clone()));
+                }
+                if from == pointer {
+                    return Ok(());
+                }
+                let value = remove_serde_json_value(self, from)?;
+                if let Err(err) = add_serde_json_value(self, pointer, value.clone()) {
+                    // Put the value back where it was, so that a failed move leaves the document unchanged.
+                    let _ = add_serde_json_value(self, from, value);
+                    return Err(err);
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(feature = "serde_json")]
+fn add_serde_json_value(
+    document: &mut serde_json::Value,
+    pointer: &str,
+    value: serde_json::Value,
+) -> Result<(), JsonEditError> {
+    let (parent_pointer, token) = split_json_pointer(pointer)
+        .ok_or_else(|| JsonEditError::InvalidTarget(pointer.to_string()))?;
+    match document.pointer_mut(parent_pointer) {
+        Some(serde_json::Value::Object(object)) => {
+            if object.contains_key(&token) {
+                return Err(JsonEditError::KeyExists(pointer.to_string()));
+            }
+            object.insert(token, value);
+        }
+        Some(serde_json::Value::Array(array)) => {
+            let idx = match token.as_str() {
+                "-" => array.len(),
+                token => token
+                    .parse()
+                    .ok()
+                    .filter(|idx| *idx <= array.len())
+                    .ok_or_else(|| JsonEditError::InvalidTarget(pointer.to_string()))?,
+            };
+            array.insert(idx, value);
+        }
+        Some(_) => return Err(JsonEditError::InvalidTarget(pointer.to_string())),
+        None => return Err(JsonEditError::NotFound(parent_pointer.to_string())),
+    }
+    Ok(())
+}
+
+#[cfg(feature = "serde_json")]
+fn remove_serde_json_value(
+    document: &mut serde_json::Value,
+    pointer: &str,
+) -> Result<serde_json::Value, JsonEditError> {
+    let not_found = || JsonEditError::NotFound(pointer.to_string());
+    let (parent_pointer, token) = split_json_pointer(pointer).ok_or_else(not_found)?;
+    match document.pointer_mut(parent_pointer) {
+        Some(serde_json::Value::Object(object)) => {
+            // `Map::remove` moves the last entry into the position of the removed entry if serde_json's `preserve_order`
+            // feature is enabled, whereas `Map::retain` keeps the order of the other entries.
+            let mut removed = None;
+            object.retain(|entry_key, value| {
+                if removed.is_none() && *entry_key == token {
+                    removed = Some(std::mem::take(value));
+                    false
+                } else {
+                    true
+                }
+            });
+            removed.ok_or_else(not_found)
+        }
+        Some(serde_json::Value::Array(array)) => {
+            let idx = token
+                .parse()
+                .ok()
+                .filter(|idx| *idx < array.len())
+                .ok_or_else(not_found)?;
+            Ok(array.remove(idx))
+        }
+        _ => Err(not_found()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::{Value, json};
+
+    use super::*;
+
+    fn apply(mut value: Value, event: JsonEditEvent<Value>) -> Result<Value, JsonEditError> {
+        value.apply_edit(&event)?;
+        Ok(value)
+    }
+
+    #[test]
+    fn replace_values_including_the_root() {
+        let value = json!({ "a": [1, 2] });
+        assert_eq!(
+            apply(
+                value.clone(),
+                JsonEditEvent::Replace {
+                    pointer: "/a/1".to_string(),
+                    value: json!({})
+                }
+            ),
+            Ok(json!({ "a": [1, {}] }))
+        );
+        assert_eq!(
+            apply(
+                value.clone(),
+                JsonEditEvent::Replace {
+                    pointer: "".to_string(),
+                    value: json!(null)
+                }
+            ),
+            Ok(json!(null))
+        );
+        assert_eq!(
+            apply(
+                value,
+                JsonEditEvent::Replace {
+                    pointer: "/b".to_string(),
+                    value: json!(null)
+                }
+            ),
+            Err(JsonEditError::NotFound("/b".to_string()))
+        );
+    }
+
+    #[test]
+    fn rename_and_remove_keep_the_position_of_entries() {
+        // The keys are chosen so that the expected order is the same whether or not serde_json's `preserve_order` feature is enabled.
+        let mut value = json!({ "a": 1, "b": 2, "c": 3 });
+        value
+            .apply_edit(&JsonEditEvent::RenameKey {
+                object_pointer: "".to_string(),
+                key: "b".to_string(),
+                new_key: "bb".to_string(),
+            })
+            .unwrap();
+        let keys = |value: &Value| {
+            value
+                .as_object()
+                .unwrap()
+                .keys()
+                .cloned()
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(keys(&value), ["a", "bb", "c"]);
+
+        value
+            .apply_edit(&JsonEditEvent::Remove {
+                pointer: "/a".to_string(),
+            })
+            .unwrap();
+        assert_eq!(keys(&value), ["bb", "c"]);
+    }
+
+    #[test]
+    fn rename_keys() {
+        let value = json!({ "a": { "b/c": 1, "d": 2 } });
+        let rename = |key: &str, new_key: &str| JsonEditEvent::RenameKey {
+            object_pointer: "/a".to_string(),
+            key: key.to_string(),
+            new_key: new_key.to_string(),
+        };
+        assert_eq!(
+            apply(value.clone(), rename("b/c", "e")),
+            Ok(json!({ "a": { "e": 1, "d": 2 } }))
+        );
+        assert_eq!(apply(value.clone(), rename("d", "d")), Ok(value.clone()));
+        assert_eq!(
+            apply(value.clone(), rename("d", "b/c")),
+            Err(JsonEditError::KeyExists("b/c".to_string()))
+        );
+        assert_eq!(
+            apply(value, rename("x", "y")),
+            Err(JsonEditError::NotFound("x".to_string()))
+        );
+    }
+
+    #[test]
+    fn add_to_objects_and_arrays() {
+        let value = json!({ "a": [1, 2], "m~n": {} });
+        let add = |pointer: &str| JsonEditEvent::Add {
+            pointer: pointer.to_string(),
+            value: json!(true),
+        };
+        assert_eq!(
+            apply(value.clone(), add("/m~0n/x~1y")),
+            Ok(json!({ "a": [1, 2], "m~n": { "x/y": true } }))
+        );
+        assert_eq!(
+            apply(value.clone(), add("/a/0")),
+            Ok(json!({ "a": [true, 1, 2], "m~n": {} }))
+        );
+        assert_eq!(
+            apply(value.clone(), add("/a/-")),
+            Ok(json!({ "a": [1, 2, true], "m~n": {} }))
+        );
+        assert_eq!(
+            apply(value.clone(), add("/a/3")),
+            Err(JsonEditError::InvalidTarget("/a/3".to_string()))
+        );
+        assert_eq!(
+            apply(value.clone(), add("/a/0/x")),
+            Err(JsonEditError::InvalidTarget("/a/0/x".to_string()))
+        );
+        assert_eq!(
+            apply(value, add("/a")),
+            Err(JsonEditError::KeyExists("/a".to_string()))
+        );
+    }
+
+    #[test]
+    fn remove_from_objects_and_arrays() {
+        let value = json!({ "a": [1, 2], "b": null });
+        let remove = |pointer: &str| JsonEditEvent::Remove {
+            pointer: pointer.to_string(),
+        };
+        assert_eq!(
+            apply(value.clone(), remove("/a/0")),
+            Ok(json!({ "a": [2], "b": null }))
+        );
+        assert_eq!(
+            apply(value.clone(), remove("/b")),
+            Ok(json!({ "a": [1, 2] }))
+        );
+        assert_eq!(
+            apply(value.clone(), remove("/a/2")),
+            Err(JsonEditError::NotFound("/a/2".to_string()))
+        );
+        assert_eq!(
+            apply(value, remove("")),
+            Err(JsonEditError::NotFound("".to_string()))
+        );
+    }
+
+    #[test]
+    fn move_within_and_between_containers() {
+        let value = json!({ "a": ["x", "y", "z"], "b": { "c": 1 } });
+        let move_to = |from: &str, pointer: &str| JsonEditEvent::Move {
+            from: from.to_string(),
+            pointer: pointer.to_string(),
+        };
+        assert_eq!(
+            apply(value.clone(), move_to("/a/0", "/a/2")),
+            Ok(json!({ "a": ["y", "z", "x"], "b": { "c": 1 } }))
+        );
+        assert_eq!(
+            apply(value.clone(), move_to("/b/c", "/a/1")),
+            Ok(json!({ "a": ["x", 1, "y", "z"], "b": {} }))
+        );
+        assert_eq!(
+            apply(value.clone(), move_to("/a", "/b/a")),
+            Ok(json!({ "b": { "a": ["x", "y", "z"], "c": 1 } }))
+        );
+        assert_eq!(
+            apply(value.clone(), move_to("/b", "/b/c/d")),
+            Err(JsonEditError::InvalidTarget("/b/c/d".to_string()))
+        );
+        // A failed move leaves the document unchanged.
+        assert_eq!(
+            apply(value.clone(), move_to("/a/0", "/b/c")),
+            Err(JsonEditError::KeyExists("/b/c".to_string()))
+        );
+        let mut unchanged = value.clone();
+        let _ = unchanged.apply_edit(&move_to("/a/0", "/b/c"));
+        assert_eq!(unchanged, value);
+    }
+
+    #[test]
+    fn parse_input_falls_back_to_strings() {
+        assert_eq!(Value::parse_input("[1, 2]"), json!([1, 2]));
+        assert_eq!(Value::parse_input("\"quoted\""), json!("quoted"));
+        assert_eq!(Value::parse_input("foo"), json!("foo"));
+        assert_eq!(json!("foo").to_input(), "\"foo\"");
+        assert_eq!(JsonEditType::of(&json!(1.5)), Some(JsonEditType::Number));
+        assert_eq!(JsonEditType::of(&json!({})), Some(JsonEditType::Object));
+    }
+
+    #[test]
+    fn parse_json_reports_error_position() {
+        assert_eq!(Value::parse_json("{\"a\": [1]}"), Ok(json!({ "a": [1] })));
+        let error = Value::parse_json("{\n  \"a\": [1,]\n}").unwrap_err();
+        assert_eq!((error.offset, error.line, error.column), (12, 2, 11));
+        assert_eq!(
+            error.to_string(),
+            format!("{} at line 2 column 11", error.message)
+        );
+        assert!(!error.message.contains("line"));
+        assert!(Value::parse_json("foo").is_err());
+
+        let error = Value::parse_json("[\"é\", x]").unwrap_err();
+        assert_eq!((error.offset, error.line, error.column), (7, 1, 7));
+    }
+}
@@ -0,0 +1,1530 @@
+use egui::{
+    Area, AsId, CursorIcon, Event, Frame, Id, Key, KeyboardShortcut, Margin, Modifiers, Order,
+    Rect, Response, StrokeKind, TextEdit, Ui, ViewportCommand,
+    text::{CCursor, CCursorRange},
+    vec2,
+};
+
+use crate::{
+    DefaultExpand, JsonParseError, JsonTree, JsonTreeResponse, JsonTreeStyle, ToggleButtonsState,
+    context_menu::JsonTreeContextMenu,
+    delimiters::ExpandableDelimiter,
+    edit::{EditableJsonValue, JsonEditError, JsonEditEvent, JsonEditType},
+    history::JsonEditHistory,
+    pointer::{
+        JsonPointer, JsonPointerSegment, escape_json_pointer_key, resolve_json_pointer,
+        split_json_pointer,
+    },
+    render::{
+        DefaultRender, RenderBaseValueContext, RenderContext, RenderExpandableDelimiterContext,
+        RenderPropertyContext,
+    },
+    tree::JsonTreeConfig,
+    value::{ExpandableType, JsonTreeValue},
+};
+
+/// An interactive JSON tree, in which values and object keys can be edited, entries added and deleted,
+/// and the types of values changed, via the context menu of each element.
+///
+/// Array elements and object entries can also be moved by dragging their index/key onto another entry, before which they are inserted,
+/// or onto the closing or collapsed delimiter of an array/object, to the end of which they are moved.
+///
+/// JSON from the clipboard can be pasted into an array or object, or after an array element, via the context menu.
+/// The pasted JSON is parsed via [`EditableJsonValue::parse_json`] and can be amended before it is inserted,
+/// with the position of any parse error shown below it.
+///
+/// Each change is applied to the document via [`EditableJsonValue::apply_edit`], and reported as a [`JsonEditEvent`]
+/// in the returned [`JsonTreeEditorResponse`]. See the [`edit`](crate::edit) module for how to edit your own JSON type.
+///
+/// If enabled via [`JsonTreeStyle::context_menu`], the copy actions of the built-in context menu are shown after the editing actions.
+///
+/// ```rust
+/// use egui_json_tree::{JsonTreeEditor, edit::JsonEditEvent};
+///
+/// # egui::__run_test_ui(|ui| {
+/// let mut value = serde_json::json!({ "name": "egui", "tags": ["gui"] });
+///
+/// let response = JsonTreeEditor::new("editor", &mut value).show(ui);
+/// for event in response.events {
+///     if let JsonEditEvent::Replace { pointer, value } = event {
+///         println!("{pointer} is now {value}");
+///     }
+/// }
+/// # });
+/// ```
+#[must_use = "You should call .show()"]
+pub struct JsonTreeEditor<'a, T: EditableJsonValue> {
+    id: Id,
+    value: &'a mut T,
+    style: Option<JsonTreeStyle>,
+    default_expand: Option<DefaultExpand<'a>>,
+    auto_reset_expanded: bool,
+    history: Option<&'a mut JsonEditHistory<T>>,
+}
+
+/// The response from showing a [`JsonTreeEditor`].
+pub struct JsonTreeEditorResponse<T> {
+    /// The response of the underlying tree.
+    pub response: JsonTreeResponse,
+    /// The edits made during this frame, in order, which have already been applied to the document.
+    pub events: Vec<JsonEditEvent<T>>,
+    /// The errors of the edits made during this frame that could not be applied to the document,
+    /// which are not included in `events`, including those of undoing or redoing edits via the keyboard shortcuts.
+    pub errors: Vec<JsonEditError>,
+}
+
+impl<'a, T: EditableJsonValue> JsonTreeEditor<'a, T> {
+    /// Creates a new [`JsonTreeEditor`] of the document `value`.
+    /// `id` must be a globally unique identifier.
+    pub fn new(id: impl AsId, value: &'a mut T) -> Self {
+        Self {
+            id: Id::new(id),
+            value,
+            style: None,
+            default_expand: None,
+            auto_reset_expanded: true,
+            history: None,
+        }
+    }
+
+    /// Override colors for JSON syntax highlighting, and search match highlighting.
+    ///
+    /// Toggle buttons that are [`ToggleButtonsState::VisibleEnabled`] are disabled while a value or key is being edited.
+    pub fn style(mut self, style: JsonTreeStyle) -> Self {
+        self.style = Some(style);
+        self
+    }
+
+    /// Override how the [`JsonTreeEditor`] expands arrays/objects by default.
+    /// See [`JsonTree::default_expand`].
+    pub fn default_expand(mut self, default_expand: DefaultExpand<'a>) -> Self {
+        self.default_expand = Some(default_expand);
+        self
+    }
+
+    /// If enabled, automatically reset expanded arrays/objects to respect the [`DefaultExpand`] setting when it changes for this tree Id.
+    /// See [`JsonTree::auto_reset_expanded`].
+    /// Defaults to enabled.
+    pub fn auto_reset_expanded(mut self, auto_reset_expanded: bool) -> Self {
+        self.auto_reset_expanded = auto_reset_expanded;
+        self
+    }
+
+    /// Record the edits made in the editor in a [`JsonEditHistory`], so that they can be undone and redone.
+    ///
+    /// While no text is being edited, Ctrl+Z undoes the most recent edits, and Ctrl+Shift+Z or Ctrl+Y redoes them
+    /// (⌘Z, ⌘⇧Z and ⌘Y on macOS). The edits made in the editor during a frame are recorded as a single undo step.
+    pub fn history(mut self, history: &'a mut JsonEditHistory<T>) -> Self {
+        self.history = Some(history);
+        self
+    }
+
+    /// Show the editor within the `Ui`, and apply any edits made to the document.
+    pub fn show(self, ui: &mut Ui) -> JsonTreeEditorResponse<T> {
+        let state_id = self.id.with("edit_state");
+        let mut state = ui.data(|d| d.get_temp::<EditState>(state_id));
+        let mut events = vec![];
+
+        let mut style = self.style.unwrap_or_default();
+        // The editor shows the copy actions within its own context menu instead.
+        let copy_menu = std::mem::replace(&mut style.context_menu, false);
+        if state.is_some() && style.toggle_buttons_state == ToggleButtonsState::VisibleEnabled {
+            style.toggle_buttons_state = ToggleButtonsState::VisibleDisabled;
+        }
+
+        let document: &T = self.value;
+        let mut renderer = EditorRenderer {
+            tree_id: self.id,
+            document,
+            state: &mut state,
+            events: &mut events,
+            copy_menu,
+        };
+        let mut config = JsonTreeConfig {
+            style: Some(style),
+            default_expand: self.default_expand,
+            auto_reset_expanded: self.auto_reset_expanded,
+            ..Default::default()
+        };
+        config.renderer.render_hook = Some(Box::new(move |ui, context| {
+            renderer.render(ui, context);
+        }));
+        let response = JsonTree {
+            id: self.id,
+            value: document,
+            config,
+        }
+        .show(ui);
+
+        let mut errors = vec![];
+        match self.history {
+            Some(history) => {
+                history.begin_group();
+                retain_applied(&mut events, &mut errors, |event| {
+                    history.apply(self.value, event)
+                });
+                history.end_group();
+                if !ui.ctx().egui_wants_keyboard_input() {
+                    match handle_undo_shortcuts(ui, history, self.value) {
+                        Ok(undo_events) => events.extend(undo_events),
+                        Err(error) => errors.push(error),
+                    }
+                }
+            }
+            None => retain_applied(&mut events, &mut errors, |event| {
+                self.value.apply_edit(event)
+            }),
+        }
+
+        ui.data_mut(|d| match state {
+            Some(state) => {
+                d.insert_temp(state_id, state);
+            }
+            None => d.remove::<EditState>(state_id),
+        });
+
+        JsonTreeEditorResponse {
+            response,
+            events,
+            errors,
+        }
+    }
+}
+
+#[derive(Clone)]
+enum EditState {
+    Key(EditKeyState),
+    Value(EditValueState),
+    Paste(PasteState),
+}
+
+#[derive(Clone)]
+struct EditKeyState {
+    object_pointer: String,
+    key: String,
+    input: String,
+    request_focus: bool,
+    /// Whether the entry was just added, in which case cancelling the edit removes it again.
+    is_new: bool,
+}
+
+#[derive(Clone)]
+struct EditValueState {
+    pointer: String,
+    input: String,
+    request_focus: bool,
+}
+
+#[derive(Clone)]
+struct PasteState {
+    /// The pointer of the array/object into which the pasted value is inserted.
+    container: String,
+    /// The array index at which the pasted value is inserted, or `None` to append it to the end of the array.
+    index: Option<usize>,
+    /// The key under which the pasted value is inserted into an object.
+    key: Option<String>,
+    input: String,
+    error: Option<JsonParseError>,
+    /// Whether the clipboard text has been requested, but not yet received.
+    awaiting_clipboard: bool,
+    request_focus: bool,
+}
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum EditOutcome {
+    Save,
+    Cancel,
+}
+
+/// The value being dragged within an editor.
+struct DragPayload {
+    tree_id: Id,
+    pointer: String,
+}
+
+/// How a drop or paste target shows where a value will be inserted.
+#[derive(Clone, Copy)]
+enum InsertionIndicator {
+    /// A line above the target, for inserting the value before it.
+    Above,
+    /// An outline around the target, for inserting the value into it.
+    Around,
+}
+
+impl InsertionIndicator {
+    fn paint(self, ui: &Ui, rect: Rect) {
+        let stroke = ui.visuals().selection.stroke;
+        match self {
+            InsertionIndicator::Above => {
+                ui.painter()
+                    .hline(rect.left()..=ui.max_rect().right(), rect.top(), stroke);
+            }
+            InsertionIndicator::Around => {
+                ui.painter()
+                    .rect_stroke(rect, 2.0, stroke, StrokeKind::Outside);
+            }
+        }
+    }
+}
+
+struct EditorRenderer<'s, T: EditableJsonValue> {
+    tree_id: Id,
+    document: &'s T,
+    state: &'s mut Option<EditState>,
+    events: &'s mut Vec<JsonEditEvent<T>>,
+    copy_menu: bool,
+}
+
+impl<'s, T: EditableJsonValue> EditorRenderer<'s, T> {
+    fn render(&mut self, ui: &mut Ui, context: RenderContext<'s, '_, T>) {
+        match context {
+            RenderContext::Property(context) => self.render_property(ui, context),
+            RenderContext::BaseValue(context) => self.render_base_value(ui, context),
+            RenderContext::ExpandableDelimiter(context) => {
+                self.render_expandable_delimiter(ui, context);
+            }
+        }
+    }
+
+    fn render_property(&mut self, ui: &mut Ui, mut context: RenderPropertyContext<'s, '_, T>) {
+        if let Some(EditState::Key(state)) = self.state.as_mut()
+            && context.property == JsonPointerSegment::Key(&state.key)
+            && context
+                .pointer
+                .parent()
+                .is_some_and(|parent| parent.to_json_pointer_string() == state.object_pointer)
+        {
+            let valid_key = state.input == state.key
+                || resolve_json_pointer(self.document, &state.object_pointer)
+                    .is_some_and(|object| !has_key(object, &state.input));
+            let outcome =
+                show_edit_controls(ui, &mut state.input, &mut state.request_focus, valid_key);
+            match outcome {
+                Some(EditOutcome::Save) if state.input != state.key => {
+                    self.events.push(JsonEditEvent::RenameKey {
+                        object_pointer: state.object_pointer.clone(),
+                        key: state.key.clone(),
+                        new_key: state.input.clone(),
+                    });
+                }
+                Some(EditOutcome::Cancel) if state.is_new => {
+                    self.events.push(JsonEditEvent::Remove {
+                        pointer: context.pointer.to_json_pointer_string(),
+                    });
+                }
+                _ => {}
+            }
+            if outcome.is_some() {
+                *self.state = None;
+            }
+            return;
+        }
+
+        let response = context.render_default(ui);
+        self.drag_and_drop_property(ui, &response, context.pointer);
+        if let (Some(parent), Some(JsonPointerSegment::Index(idx))) =
+            (context.pointer.parent(), context.pointer.last())
+        {
+            // Values pasted at an array index are shown before the element currently at that index.
+            let container = parent.to_json_pointer_string();
+            self.paste_target(
+                ui,
+                &response,
+                &container,
+                Some(*idx),
+                InsertionIndicator::Above,
+            );
+        }
+        self.context_menu(
+            response,
+            context.style,
+            context.value,
+            context.pointer,
+            |ui, editor| {
+                if editor.add_entry_button(ui, context.value, context.pointer)
+                    && let Some(collapsing_state) = context.collapsing_state.as_mut()
+                {
+                    collapsing_state.set_open(true);
+                }
+                if editor.paste_buttons(ui, context.value, context.pointer)
+                    && let Some(collapsing_state) = context.collapsing_state.as_mut()
+                {
+                    collapsing_state.set_open(true);
+                }
+                editor.edit_key_button(ui, context.pointer);
+                editor.change_type_menu(ui, context.value, context.pointer);
+                editor.delete_button(ui, context.pointer);
+            },
+        );
+    }
+
+    fn render_base_value(&mut self, ui: &mut Ui, context: RenderBaseValueContext<'s, '_, T>) {
+        if let Some(EditState::Value(state)) = self.state.as_mut()
+            && state.pointer == context.pointer.to_json_pointer_string()
+        {
+            let outcome = show_edit_controls(ui, &mut state.input, &mut state.request_focus, true);
+            if outcome == Some(EditOutcome::Save) {
+                self.events.push(JsonEditEvent::Replace {
+                    pointer: state.pointer.clone(),
+                    value: T::parse_input(&state.input),
+                });
+            }
+            if outcome.is_some() {
+                *self.state = None;
+            }
+            return;
+        }
+
+        let response = context.render_default(ui);
+        self.context_menu(
+            response,
+            context.style,
+            context.value,
+            context.pointer,
+            |ui, editor| {
+                if ui.button("Edit value").clicked() {
+                    *editor.state = Some(EditState::Value(EditValueState {
+                        pointer: context.pointer.to_json_pointer_string(),
+                        input: context.value.to_input(),
+                        request_focus: true,
+                    }));
+                }
+                editor.paste_buttons(ui, context.value, context.pointer);
+                editor.change_type_menu(ui, context.value, context.pointer);
+                editor.delete_button(ui, context.pointer);
+            },
+        );
+    }
+
+    fn render_expandable_delimiter(
+        &mut self,
+        ui: &mut Ui,
+        context: RenderExpandableDelimiterContext<'s, '_, T>,
+    ) {
+        let response = context.render_default(ui);
+        let insertion_indicator = match context.delimiter {
+            ExpandableDelimiter::ClosingArray | ExpandableDelimiter::ClosingObject => {
+                Some(InsertionIndicator::Above)
+            }
+            ExpandableDelimiter::CollapsedArray
+            | ExpandableDelimiter::CollapsedEmptyArray
+            | ExpandableDelimiter::CollapsedObject
+            | ExpandableDelimiter::CollapsedEmptyObject => Some(InsertionIndicator::Around),
+            // The opening delimiter shares its row with the property, which is already a drop target.
+            ExpandableDelimiter::OpeningArray | ExpandableDelimiter::OpeningObject => None,
+        };
+        if let Some(insertion_indicator) = insertion_indicator {
+            // Values dropped or pasted onto a closing or collapsed delimiter are inserted at the end of its array/object.
+            let container = context.pointer.to_json_pointer_string();
+            self.drop_target(ui, &response, &container, None, insertion_indicator);
+            self.paste_target(ui, &response, &container, None, insertion_indicator);
+        }
+        self.context_menu(
+            response,
+            context.style,
+            context.value,
+            context.pointer,
+            |ui, editor| {
+                if editor.add_entry_button(ui, context.value, context.pointer) {
+                    context.collapsing_state.set_open(true);
+                }
+                if editor.paste_buttons(ui, context.value, context.pointer) {
+                    context.collapsing_state.set_open(true);
+                }
+                editor.change_type_menu(ui, context.value, context.pointer);
+            },
+        );
+    }
+
+    /// Makes the array index or object key of a value draggable, and a drop target for other dragged values,
+    /// which are moved before this value.
+    fn drag_and_drop_property(
+        &mut self,
+        ui: &Ui,
+        response: &Response,
+        pointer: JsonPointer<'_, '_>,
+    ) {
+        let index = match pointer.last() {
+            Some(JsonPointerSegment::Index(idx)) => Some(*idx),
+            Some(JsonPointerSegment::Key(_)) => None,
+            // The root value cannot be moved, and other keys cannot be identified by a JSON pointer.
+            _ => return,
+        };
+        let Some(parent) = pointer.parent() else {
+            return;
+        };
+
+        response.dnd_set_drag_payload(DragPayload {
+            tree_id: self.tree_id,
+            pointer: pointer.to_json_pointer_string(),
+        });
+        if response.dragged() {
+            ui.ctx().set_cursor_icon(CursorIcon::Grabbing);
+        }
+
+        let container = parent.to_json_pointer_string();
+        self.drop_target(ui, response, &container, index, InsertionIndicator::Above);
+    }
+
+    /// Shows a drop indicator if a value of this editor is dragged over the response, and moves the value into the `container`
+    /// at `index` when it is dropped, or to the end of the container if `index` is `None`.
+    ///
+    /// A value cannot be dropped into itself or its descendants, nor into an object that already has an entry with its key.
+    fn drop_target(
+        &mut self,
+        ui: &Ui,
+        response: &Response,
+        container: &str,
+        index: Option<usize>,
+        indicator: InsertionIndicator,
+    ) {
+        let Some(payload) = response.dnd_hover_payload::<DragPayload>() else {
+            return;
+        };
+        if payload.tree_id != self.tree_id {
+            return;
+        }
+        if container == payload.pointer || container.starts_with(&format!("{}/", payload.pointer)) {
+            ui.ctx().set_cursor_icon(CursorIcon::NoDrop);
+            return;
+        }
+        let Some(target) = move_target(self.document, &payload.pointer, container, index) else {
+            return;
+        };
+
+        indicator.paint(ui, response.rect);
+
+        if response.dnd_release_payload::<DragPayload>().is_some() {
+            self.events.push(JsonEditEvent::Move {
+                from: payload.pointer.clone(),
+                pointer: target,
+            });
+        }
+    }
+
+    /// Shows the form for pasting JSON, if JSON is being pasted into the `container` at `index`, or at the end of the container
+    /// if `index` is `None`, along with an indicator of where the pasted value will be inserted.
+    fn paste_target(
+        &mut self,
+        ui: &Ui,
+        response: &Response,
+        container: &str,
+        index: Option<usize>,
+        indicator: InsertionIndicator,
+    ) {
+        let Some(EditState::Paste(state)) = self.state.as_mut() else {
+            return;
+        };
+        if state.container != container {
+            return;
+        }
+        let Some(container_value) = resolve_json_pointer(self.document, container) else {
+            return;
+        };
+        let JsonTreeValue::Expandable(entries, _) = container_value.to_json_tree_value() else {
+            return;
+        };
+        let len = entries.len();
+        let is_target = match index {
+            Some(index) => state.index == Some(index),
+            None => state.index.is_none_or(|index| index >= len),
+        };
+        if !is_target {
+            return;
+        }
+
+        let pointer = match &state.key {
+            Some(key) => format!("{container}{}", escape_json_pointer_key(key)),
+            None => format!(
+                "{container}/{}",
+                state.index.map_or(len, |index| index.min(len))
+            ),
+        };
+        let valid_key = state
+            .key
+            .as_ref()
+            .is_none_or(|key| !has_key(container_value, key));
+
+        indicator.paint(ui, response.rect);
+        let outcome = Area::new(self.tree_id.with("paste"))
+            .order(Order::Foreground)
+            .fixed_pos(response.rect.left_bottom())
+            .show(ui.ctx(), |ui| {
+                Frame::popup(ui.style())
+                    .show(ui, |ui| {
+                        show_paste_form::<T>(ui, state, &pointer, valid_key)
+                    })
+                    .inner
+            })
+            .inner;
+
+        match outcome {
+            Some(EditOutcome::Save) => {
+                if let Ok(value) = T::parse_json(&state.input) {
+                    self.events.push(JsonEditEvent::Add { pointer, value });
+                }
+                *self.state = None;
+            }
+            Some(EditOutcome::Cancel) => *self.state = None,
+            None => {}
+        }
+    }
+
+    fn context_menu(
+        &mut self,
+        response: Response,
+        style: &JsonTreeStyle,
+        value: &'s T,
+        pointer: JsonPointer<'s, '_>,
+        add_contents: impl FnOnce(&mut Ui, &mut Self),
+    ) {
+        response
+            .on_hover_cursor(CursorIcon::ContextMenu)
+            .context_menu(|ui| {
+                add_contents(ui, self);
+                if self.copy_menu {
+                    ui.separator();
+                    JsonTreeContextMenu::default().show(ui, style, value, pointer);
+                }
+            });
+    }
+
+    /// Shows a button to add an entry to an array or object, returning whether it was clicked.
+    ///
+    /// A new object entry is added under a placeholder key, which is then edited.
+    fn add_entry_button(&mut self, ui: &mut Ui, value: &T, pointer: JsonPointer<'_, '_>) -> bool {
+        let JsonTreeValue::Expandable(entries, expandable_type) = value.to_json_tree_value() else {
+            return false;
+        };
+        let pointer = pointer.to_json_pointer_string();
+        match expandable_type {
+            ExpandableType::Array => {
+                if !ui.button("Add to array").clicked() {
+                    return false;
+                }
+                self.events.push(JsonEditEvent::Add {
+                    pointer: format!("{pointer}/{}", entries.len()),
+                    value: T::default_of_type(JsonEditType::Null),
+                });
+            }
+            ExpandableType::Object => {
+                if !ui.button("Add to object").clicked() {
+                    return false;
+                }
+                let key = unique_new_key(value);
+                self.events.push(JsonEditEvent::Add {
+                    pointer: format!("{pointer}{}", escape_json_pointer_key(&key)),
+                    value: T::default_of_type(JsonEditType::Null),
+                });
+                *self.state = Some(EditState::Key(EditKeyState {
+                    object_pointer: pointer,
+                    input: key.clone(),
+                    key,
+                    request_focus: true,
+                    is_new: true,
+                }));
+            }
+        }
+        true
+    }
+
+    /// Shows buttons to paste JSON from the clipboard into an array or object, or after an array element.
+    /// Returns whether JSON is to be pasted into the value, which should then be expanded to show where it is inserted.
+    fn paste_buttons(&mut self, ui: &mut Ui, value: &T, pointer: JsonPointer<'_, '_>) -> bool {
+        if let JsonTreeValue::Expandable(_, expandable_type) = value.to_json_tree_value() {
+            let label = match expandable_type {
+                ExpandableType::Array => "Paste into array",
+                ExpandableType::Object => "Paste into object",
+            };
+            if ui.button(label).clicked() {
+                let key =
+                    (expandable_type == ExpandableType::Object).then(|| unique_new_key(value));
+                self.start_paste(ui, pointer.to_json_pointer_string(), None, key);
+                return true;
+            }
+        }
+        if let (Some(parent), Some(JsonPointerSegment::Index(idx))) =
+            (pointer.parent(), pointer.last())
+            && ui.button("Paste after").clicked()
+        {
+            self.start_paste(ui, parent.to_json_pointer_string(), Some(idx + 1), None);
+        }
+        false
+    }
+
+    /// Requests the clipboard text, which is received as an [`Event::Paste`] by the paste form.
+    fn start_paste(
+        &mut self,
+        ui: &Ui,
+        container: String,
+        index: Option<usize>,
+        key: Option<String>,
+    ) {
+        ui.ctx().send_viewport_cmd(ViewportCommand::RequestPaste);
+        *self.state = Some(EditState::Paste(PasteState {
+            container,
+            index,
+            key,
+            input: String::new(),
+            error: None,
+            awaiting_clipboard: true,
+            request_focus: false,
+        }));
+    }
+
+    fn edit_key_button(&mut self, ui: &mut Ui, pointer: JsonPointer<'_, '_>) {
+        if let (Some(parent), Some(JsonPointerSegment::Key(key))) =
+            (pointer.parent(), pointer.last())
+            && ui.button("Edit key").clicked()
+        {
+            *self.state = Some(EditState::Key(EditKeyState {
+                object_pointer: parent.to_json_pointer_string(),
+                key: key.to_string(),
+                input: key.to_string(),
+                request_focus: true,
+                is_new: false,
+            }));
+        }
+    }
+
+    fn change_type_menu(&mut self, ui: &mut Ui, value: &T, pointer: JsonPointer<'_, '_>) {
+        let current_type = JsonEditType::of(value);
+        ui.menu_button("Change type", |ui| {
+            for value_type in JsonEditType::ALL {
+                let enabled = current_type != Some(value_type);
+                if ui
+                    .add_enabled(enabled, egui::Button::new(value_type.label()))
+                    .clicked()
+                {
+                    self.events.push(JsonEditEvent::Replace {
+                        pointer: pointer.to_json_pointer_string(),
+                        value: T::default_of_type(value_type),
+                    });
+                }
+            }
+        });
+    }
+
+    /// Shows a button to delete an object entry or array element.
+    ///
+    /// Keys that cannot be identified by a JSON pointer, e.g. [`JsonPointerSegment::DuplicateKey`], cannot be deleted.
+    fn delete_button(&mut self, ui: &mut Ui, pointer: JsonPointer<'_, '_>) {
+        if matches!(
+            pointer.last(),
+            Some(JsonPointerSegment::Key(_) | JsonPointerSegment::Index(_))
+        ) && ui.button("Delete").clicked()
+        {
+            self.events.push(JsonEditEvent::Remove {
+                pointer: pointer.to_json_pointer_string(),
+            });
+        }
+    }
+}
+
+/// Applies the events in order, keeping those that were applied and collecting the errors of those that were not.
+fn retain_applied<T>(
+    events: &mut Vec<JsonEditEvent<T>>,
+    errors: &mut Vec<JsonEditError>,
+    mut apply: impl FnMut(&JsonEditEvent<T>) -> Result<(), JsonEditError>,
+) {
+    events.retain(|event| match apply(event) {
+        Ok(()) => true,
+        Err(error) => {
+            errors.push(error);
+            false
+        }
+    });
+}
+
+/// Undoes or redoes the most recent edits of the history if their keyboard shortcut was pressed, returning the edits applied to do so.
+fn handle_undo_shortcuts<T: EditableJsonValue>(
+    ui: &Ui,
+    history: &mut JsonEditHistory<T>,
+    document: &mut T,
+) -> Result<Vec<JsonEditEvent<T>>, JsonEditError> {
+    let redo_shortcuts = [
+        KeyboardShortcut::new(Modifiers::COMMAND | Modifiers::SHIFT, Key::Z),
+        KeyboardShortcut::new(Modifiers::COMMAND, Key::Y),
+    ];
+    let undo_shortcut = KeyboardShortcut::new(Modifiers::COMMAND, Key::Z);
+    // Redo is checked first, since the undo shortcut also matches when Shift is held.
+    if ui.input_mut(|i| redo_shortcuts.iter().any(|s| i.consume_shortcut(s))) {
+        history.redo(document)
+    } else if ui.input_mut(|i| i.consume_shortcut(&undo_shortcut)) {
+        history.undo(document)
+    } else {
+        Ok(vec![])
+    }
+}
+
+/// Returns the JSON pointer string to which the value at `from` is moved when it is dropped into the `container`
+/// at `index`, or to the end of the container if `index` is `None`, or `None` if the move would not change the document
+/// or cannot be made.
+///
+/// As in a JSON Patch `move` operation, an array index of the target accounts for the removal of the value from `from`.
+fn move_target<T: EditableJsonValue>(
+    document: &T,
+    from: &str,
+    container: &str,
+    index: Option<usize>,
+) -> Option<String> {
+    let (source_container, source_token) = split_json_pointer(from)?;
+    match resolve_json_pointer(document, container)?.to_json_tree_value() {
+        JsonTreeValue::Expandable(entries, ExpandableType::Array) => {
+            let mut index = index.unwrap_or(entries.len());
+            if source_container == container {
+                let source_index = source_token.parse::<usize>().ok()?;
+                if source_index < index {
+                    index -= 1;
+                }
+                if source_index == index {
+                    return None;
+                }
+            }
+            Some(format!("{container}/{index}"))
+        }
+        JsonTreeValue::Expandable(entries, ExpandableType::Object) => {
+            // Object entries are positioned by the JSON type, so a value can only be moved into a different object.
+            let key_exists = entries
+                .iter()
+                .any(|(property, _)| *property == JsonPointerSegment::Key(&source_token));
+            (source_container != container && !key_exists)
+                .then(|| format!("{container}{}", escape_json_pointer_key(&source_token)))
+        }
+        JsonTreeValue::Base(..) => None,
+    }
+}
+
+/// Returns a placeholder key for a new entry of the object, which is not yet one of its keys.
+fn unique_new_key<T: EditableJsonValue>(object: &T) -> String {
+    (0..)
+        .map(|counter| match counter {
+            0 => "new_key".to_string(),
+            counter => format!("new_key_{counter}"),
+        })
+        .find(|key| !has_key(object, key))
+        .expect("an object has finitely many keys")
+}
+
+fn has_key<T: EditableJsonValue>(object: &T, key: &str) -> bool {
+    match object.to_json_tree_value() {
+        JsonTreeValue::Expandable(entries, ExpandableType::Object) => entries
+            .iter()
+            .any(|(property, _)| *property == JsonPointerSegment::Key(key)),
+        _ => false,
+    }
+}
+
+/// Shows a form for editing the JSON to paste at `pointer`, and the key under which to paste it into an object,
+/// returning whether the paste was saved or cancelled.
+///
+/// The JSON is parsed whenever it changes, and the position of any error is shown below it.
+fn show_paste_form<T: EditableJsonValue>(
+    ui: &mut Ui,
+    state: &mut PasteState,
+    pointer: &str,
+    valid_key: bool,
+) -> Option<EditOutcome> {
+    let mut input_changed = false;
+    if state.awaiting_clipboard
+        && let Some(text) = ui.input(|i| {
+            i.events.iter().find_map(|event| match event {
+                Event::Paste(text) => Some(text.clone()),
+                _ => None,
+            })
+        })
+    {
+        state.input = text;
+        state.awaiting_clipboard = false;
+        state.request_focus = true;
+        input_changed = true;
+    }
+
+    ui.label(format!("Paste JSON at \"{pointer}\""));
+
+    if let Some(key) = state.key.as_mut() {
+        ui.horizontal(|ui| {
+            ui.label("Key:");
+            TextEdit::singleline(key).code_editor().show(ui);
+        });
+        if !valid_key {
+            ui.colored_label(ui.visuals().error_fg_color, "This key already exists");
+        }
+    }
+
+    let response = TextEdit::multiline(&mut state.input)
+        .code_editor()
+        .hint_text("Paste JSON here")
+        .desired_rows(4)
+        .show(ui)
+        .response;
+    if response.has_focus() {
+        // Text pasted into the focused text edit is already inserted by it.
+        state.awaiting_clipboard = false;
+    }
+    if std::mem::take(&mut state.request_focus) {
+        response.request_focus();
+    }
+    if input_changed || response.changed() {
+        state.error = if state.input.trim().is_empty() {
+            None
+        } else {
+            T::parse_json(&state.input).err()
+        };
+    }
+
+    if let Some(error) = &state.error {
+        ui.colored_label(ui.visuals().error_fg_color, error.to_string());
+    }
+
+    let valid = valid_key && state.error.is_none() && !state.input.trim().is_empty();
+    ui.horizontal(|ui| {
+        let save = ui
+            .add_enabled_ui(valid, |ui| ui.small_button("✅").clicked())
+            .inner;
+        ui.add_space(5.0);
+        let cancel = ui.small_button("❌").clicked();
+        if save {
+            Some(EditOutcome::Save)
+        } else if cancel {
+            Some(EditOutcome::Cancel)
+        } else {
+            None
+        }
+    })
+    .inner
+}
+
+/// Shows a text edit with buttons to save and cancel the edit, returning which of them was clicked.
+/// Pressing Enter whilst the text edit has focus also saves the edit, if it is valid.
+fn show_edit_controls(
+    ui: &mut Ui,
+    input: &mut String,
+    request_focus: &mut bool,
+    valid: bool,
+) -> Option<EditOutcome> {
+    let enter_was_pressed_with_focus = show_text_edit(ui, input, request_focus);
+
+    ui.add_space(5.0);
+
+    let save = ui
+        .add_enabled_ui(valid, |ui| ui.small_button("✅").clicked())
+        .inner
+        || (valid && enter_was_pressed_with_focus);
+
+    ui.add_space(5.0);
+
+    let cancel = ui.small_button("❌").clicked();
+
+    if save {
+        Some(EditOutcome::Save)
+    } else if cancel {
+        Some(EditOutcome::Cancel)
+    } else {
+        None
+    }
+}
+
+/// Returns `bool` indicating whether the Enter key was pressed whilst the text edit had focus.
+fn show_text_edit(ui: &mut Ui, input: &mut String, request_focus: &mut bool) -> bool {
+    // Wrap in horizontal to prevent jitters when typing when children are expanded (due to use of horizontal_wrapped when rendering properties).
+    let text_edit_output = ui
+        .horizontal(|ui| {
+            TextEdit::singleline(input)
+                .code_editor()
+                .margin(Margin::symmetric(2, 0))
+                .clip_text(false)
+                .desired_width(0.0)
+                .min_size(vec2(10.0, 2.0))
+                .return_key(None) // Disable return key so we can capture Enter key press for submission.
+                .show(ui)
+        })
+        .inner;
+
+    if *request_focus {
+        *request_focus = false;
+        let text_edit_id = text_edit_output.response.id;
+        if let Some(mut text_edit_state) = TextEdit::load_state(ui.ctx(), text_edit_id) {
+            text_edit_state
+                .cursor
+                .set_char_range(Some(CCursorRange::two(
+                    CCursor::new(0),
+                    CCursor::new(input.chars().count()),
+                )));
+            text_edit_state.store(ui.ctx(), text_edit_id);
+            ui.ctx().memory_mut(|mem| mem.request_focus(text_edit_id));
+        }
+    }
+
+    text_edit_output.response.has_focus() && ui.input(|i| i.key_pressed(Key::Enter))
+}
+
+#[cfg(test)]
+mod tests {
+    use egui::{
+        Event::{Paste, PointerButton, PointerMoved},
+        Key, Modifiers, Pos2,
+        accesskit::Role,
+    };
+    use egui_kittest::{Harness, kittest::Queryable};
+    use serde_json::{Value, json};
+
+    use super::retain_applied;
+    use crate::{
+        DefaultExpand, JsonTreeEditor,
+        edit::{EditableJsonValue, JsonEditError, JsonEditEvent},
+        history::JsonEditHistory,
+    };
+
+    /// Shows an editor of `value` with all arrays/objects expanded, collecting all edit events.
+    fn harness<'a>(value: &'a mut Value, events: &'a mut Vec<JsonEditEvent<Value>>) -> Harness<'a> {
+        Harness::new_ui(move |ui| {
+            let response = JsonTreeEditor::new("id", value)
+                .default_expand(DefaultExpand::All)
+                .show(ui);
+            events.extend(response.events);
+        })
+    }
+
+    #[test]
+    fn add_to_array() {
+        let mut value = json!({ "abc": 123, "def": [5, 6, 7] });
+        let mut events = vec![];
+        let mut harness = harness(&mut value, &mut events);
+
+        harness.get_by_label("\"def\"").click_secondary();
+        harness.run();
+
+        harness
+            .get_by_role_and_label(Role::Button, "Add to array")
+            .click();
+        harness.run();
+
+        drop(harness);
+
+        assert_eq!(value, json!({ "abc": 123, "def": [5, 6, 7, null] }));
+        assert_eq!(
+            events,
+            [JsonEditEvent::Add {
+                pointer: "/def/3".to_string(),
+                value: Value::Null
+            }]
+        );
+    }
+
+    #[test]
+    fn delete_from_array() {
+        let mut value = json!({ "abc": 123, "def": [5, 6, 7] });
+        let mut events = vec![];
+        let mut harness = harness(&mut value, &mut events);
+
+        harness.get_by_label_contains("6").click_secondary();
+        harness.run();
+
+        harness
+            .get_by_role_and_label(Role::Button, "Delete")
+            .click();
+        harness.run();
+
+        drop(harness);
+
+        assert_eq!(value, json!({ "abc": 123, "def": [5, 7] }));
+        assert_eq!(
+            events,
+            [JsonEditEvent::Remove {
+                pointer: "/def/1".to_string()
+            }]
+        );
+    }
+
+    #[test]
+    fn edit_array_element() {
+        let mut value = json!({ "abc": 123, "def": [5, 6, 7] });
+        let mut events = vec![];
+        let mut harness = harness(&mut value, &mut events);
+
+        harness.get_by_label_contains("6").click_secondary();
+        harness.run();
+
+        harness
+            .get_by_role_and_label(Role::Button, "Edit value")
+            .click();
+        harness.run();
+
+        assert!(harness.get_by_role(Role::TextInput).is_focused());
+
+        // Text input is focussed and we can trigger text input event via any node.
+        harness.root().type_text("foo");
+        harness.run();
+
+        harness.get_by_role_and_label(Role::Button, "✅").click();
+        harness.run();
+        assert!(harness.query_by_role(Role::TextInput).is_none());
+
+        drop(harness);
+
+        assert_eq!(value, json!({ "abc": 123, "def": [5, "foo", 7] }));
+        assert_eq!(
+            events,
+            [JsonEditEvent::Replace {
+                pointer: "/def/1".to_string(),
+                value: json!("foo")
+            }]
+        );
+    }
+
+    #[test]
+    fn change_type_of_value() {
+        let mut value = json!({ "abc": 123, "def": [5, 6, 7] });
+        let mut events = vec![];
+        let mut harness = harness(&mut value, &mut events);
+
+        harness.get_by_label("\"abc\"").click_secondary();
+        harness.run();
+
+        harness.get_by_label_contains("Change type").click();
+        harness.run();
+
+        harness.get_by_role_and_label(Role::Button, "Array").click();
+        harness.run();
+
+        drop(harness);
+
+        assert_eq!(value, json!({ "abc": [], "def": [5, 6, 7] }));
+    }
+
+    #[test]
+    fn add_to_object() {
+        let mut value = json!({ "abc": { "baz": "qux" }, "def": [5, 6, 7] });
+        let mut events = vec![];
+        let mut harness = harness(&mut value, &mut events);
+
+        harness.get_by_label("\"abc\"").click_secondary();
+        harness.run();
+
+        harness
+            .get_by_role_and_label(Role::Button, "Add to object")
+            .click();
+        harness.run();
+
+        assert!(harness.get_by_role(Role::TextInput).is_focused());
+
+        // Text input is focussed and we can trigger text input event via any node.
+        harness.root().type_text("foo");
+        harness.run();
+
+        harness.get_by_role_and_label(Role::Button, "✅").click();
+        harness.run();
+        assert!(harness.query_by_role(Role::TextInput).is_none());
+
+        drop(harness);
+
+        assert_eq!(
+            value,
+            json!({ "abc": { "baz": "qux", "foo": null }, "def": [5, 6, 7] })
+        );
+        assert_eq!(
+            events,
+            [
+                JsonEditEvent::Add {
+                    pointer: "/abc/new_key".to_string(),
+                    value: Value::Null
+                },
+                JsonEditEvent::RenameKey {
+                    object_pointer: "/abc".to_string(),
+                    key: "new_key".to_string(),
+                    new_key: "foo".to_string()
+                }
+            ]
+        );
+    }
+
+    #[test]
+    fn when_add_to_object_and_press_cancel_removes_new_entry() {
+        let original_json = json!({ "abc": { "baz": "qux" }, "def": [5, 6, 7] });
+        let mut value = original_json.clone();
+        let mut events = vec![];
+        let mut harness = harness(&mut value, &mut events);
+
+        harness.get_by_label("\"abc\"").click_secondary();
+        harness.run();
+
+        harness
+            .get_by_role_and_label(Role::Button, "Add to object")
+            .click();
+        harness.run();
+
+        harness.get_by_role_and_label(Role::Button, "❌").click();
+        harness.run();
+        assert!(harness.query_by_role(Role::TextInput).is_none());
+
+        drop(harness);
+
+        assert_eq!(value, original_json);
+    }
+
+    #[test]
+    fn edit_object_key() {
+        let mut value = json!({ "abc": { "baz": "qux" }, "def": [5, 6, 7] });
+        let mut events = vec![];
+        let mut harness = harness(&mut value, &mut events);
+
+        harness.get_by_label("\"abc\"").click_secondary();
+        harness.run();
+
+        harness
+            .get_by_role_and_label(Role::Button, "Edit key")
+            .click();
+        harness.run();
+
+        assert!(harness.get_by_role(Role::TextInput).is_focused());
+
+        // Text input is focussed and we can trigger text input event via any node.
+        harness.root().type_text("foo");
+        harness.run();
+
+        harness.get_by_role_and_label(Role::Button, "✅").click();
+        harness.run();
+        assert!(harness.query_by_role(Role::TextInput).is_none());
+
+        drop(harness);
+
+        assert_eq!(value, json!({ "foo": { "baz": "qux" }, "def": [5, 6, 7] }));
+    }
+
+    #[test]
+    fn when_edit_object_key_can_save_with_enter_key() {
+        let mut value = json!({ "abc": { "baz": "qux" }, "def": [5, 6, 7] });
+        let mut events = vec![];
+        let mut harness = harness(&mut value, &mut events);
+
+        harness.get_by_label("\"abc\"").click_secondary();
+        harness.run();
+
+        harness
+            .get_by_role_and_label(Role::Button, "Edit key")
+            .click();
+        harness.run();
+
+        // Text input is focussed and we can trigger text input event via any node.
+        harness.root().type_text("foo");
+        harness.run();
+
+        harness.key_press(Key::Enter);
+        harness.run();
+        assert!(harness.query_by_role(Role::TextInput).is_none());
+
+        drop(harness);
+
+        assert_eq!(value, json!({ "foo": { "baz": "qux" }, "def": [5, 6, 7] }));
+    }
+
+    #[test]
+    fn when_edit_object_key_and_press_cancel_does_not_change_json() {
+        let original_json = json!({ "abc": { "baz": "qux" }, "def": [5, 6, 7] });
+        let mut value = original_json.clone();
+        let mut events = vec![];
+        let mut harness = harness(&mut value, &mut events);
+
+        harness.get_by_label("\"abc\"").click_secondary();
+        harness.run();
+
+        harness
+            .get_by_role_and_label(Role::Button, "Edit key")
+            .click();
+        harness.run();
+
+        assert!(harness.get_by_role(Role::TextInput).is_focused());
+
+        // Text input is focussed and we can trigger text input event via any node.
+        harness.root().type_text("foo");
+        harness.run();
+
+        harness.get_by_role_and_label(Role::Button, "❌").click();
+        harness.run();
+        assert!(harness.query_by_role(Role::TextInput).is_none());
+
+        drop(harness);
+
+        assert_eq!(value, original_json);
+        assert!(events.is_empty());
+    }
+
+    #[test]
+    fn edits_that_cannot_be_applied_are_reported_as_errors() {
+        let mut value = json!({ "a": 1 });
+        let mut events = vec![
+            JsonEditEvent::Remove {
+                pointer: "/b".to_string(),
+            },
+            JsonEditEvent::Replace {
+                pointer: "/a".to_string(),
+                value: json!(2),
+            },
+        ];
+        let mut errors = vec![];
+        retain_applied(&mut events, &mut errors, |event| value.apply_edit(event));
+
+        assert_eq!(value, json!({ "a": 2 }));
+        assert_eq!(
+            events,
+            [JsonEditEvent::Replace {
+                pointer: "/a".to_string(),
+                value: json!(2)
+            }]
+        );
+        assert_eq!(errors, [JsonEditError::NotFound("/b".to_string())]);
+    }
+
+    #[test]
+    fn when_edit_object_key_to_existing_key_cannot_save() {
+        let original_json = json!({ "abc": { "baz": "qux" }, "def": [5, 6, 7] });
+        let mut value = original_json.clone();
+        let mut events = vec![];
+        let mut harness = harness(&mut value, &mut events);
+
+        harness.get_by_label("\"abc\"").click_secondary();
+        harness.run();
+
+        harness
+            .get_by_role_and_label(Role::Button, "Edit key")
+            .click();
+        harness.run();
+
+        harness.root().type_text("def");
+        harness.run();
+
+        harness.key_press(Key::Enter);
+        harness.run();
+        assert!(harness.query_by_role(Role::TextInput).is_some());
+
+        harness.get_by_role_and_label(Role::Button, "❌").click();
+        harness.run();
+
+        drop(harness);
+
+        assert_eq!(value, original_json);
+        assert!(events.is_empty());
+    }
+
+    #[test]
+    fn delete_from_object() {
+        let mut value = json!({ "abc": { "baz": "qux" }, "def": [5, 6, 7] });
+        let mut events = vec![];
+        let mut harness = harness(&mut value, &mut events);
+
+        harness.get_by_label_contains("qux").click_secondary();
+        harness.run();
+
+        harness
+            .get_by_role_and_label(Role::Button, "Delete")
+            .click();
+        harness.run();
+
+        drop(harness);
+
+        assert_eq!(value, json!({ "abc": {}, "def": [5, 6, 7] }));
+    }
+
+    #[test]
+    fn undo_and_redo_with_keyboard_shortcuts() {
+        let mut value = json!({ "abc": 123, "def": [5, 6, 7] });
+        let mut history = JsonEditHistory::new();
+        let mut harness = Harness::new_ui(|ui| {
+            JsonTreeEditor::new("id", &mut value)
+                .default_expand(DefaultExpand::All)
+                .history(&mut history)
+                .show(ui);
+        });
+
+        harness.get_by_label_contains("6").click_secondary();
+        harness.run();
+
+        harness
+            .get_by_role_and_label(Role::Button, "Delete")
+            .click();
+        harness.run();
+        assert!(harness.query_by_label_contains("6").is_none());
+
+        harness.key_press_modifiers(Modifiers::COMMAND, Key::Z);
+        harness.run();
+        assert!(harness.query_by_label_contains("6").is_some());
+
+        harness.key_press_modifiers(Modifiers::COMMAND | Modifiers::SHIFT, Key::Z);
+        harness.run();
+        harness.key_press_modifiers(Modifiers::COMMAND, Key::Z);
+        harness.run();
+
+        drop(harness);
+
+        assert_eq!(value, json!({ "abc": 123, "def": [5, 6, 7] }));
+        assert!(history.can_redo());
+        assert!(!history.can_undo());
+    }
+
+    #[test]
+    fn drag_array_element_onto_another_element() {
+        let mut value = json!({ "a": ["x", "y", "z"] });
+        let mut events = vec![];
+        let mut harness = harness(&mut value, &mut events);
+
+        let from = harness.get_by_label("0").rect().center();
+        let to = harness.get_by_label("2").rect().center();
+        drag(&mut harness, from, to);
+
+        drop(harness);
+
+        assert_eq!(value, json!({ "a": ["y", "x", "z"] }));
+        assert_eq!(
+            events,
+            [JsonEditEvent::Move {
+                from: "/a/0".to_string(),
+                pointer: "/a/1".to_string()
+            }]
+        );
+    }
+
+    #[test]
+    fn when_drag_object_into_its_own_descendant_does_not_move() {
+        let original_json = json!({ "a": { "b": ["p", "q"] } });
+        let mut value = original_json.clone();
+        let mut events = vec![];
+        let mut harness = harness(&mut value, &mut events);
+
+        let from = harness.get_by_label("\"a\"").rect().center();
+        let to = harness.get_by_label("1").rect().center();
+        drag(&mut harness, from, to);
+
+        drop(harness);
+
+        assert_eq!(value, original_json);
+        assert!(events.is_empty());
+    }
+
+    #[test]
+    fn paste_after_array_element() {
+        let mut value = json!({ "a": ["x", "y"] });
+        let mut events = vec![];
+        let mut harness = harness(&mut value, &mut events);
+
+        harness.get_by_label("0").click_secondary();
+        harness.run();
+
+        harness
+            .get_by_role_and_label(Role::Button, "Paste after")
+            .click();
+        harness.run();
+
+        harness
+            .input_mut()
+            .events
+            .push(Paste("{ \"b\": true }".to_string()));
+        harness.run();
+        assert!(harness.query_by_label("Paste JSON at \"/a/1\"").is_some());
+
+        harness.get_by_role_and_label(Role::Button, "✅").click();
+        harness.run();
+
+        drop(harness);
+
+        assert_eq!(value, json!({ "a": ["x", { "b": true }, "y"] }));
+        assert_eq!(
+            events,
+            [JsonEditEvent::Add {
+                pointer: "/a/1".to_string(),
+                value: json!({ "b": true })
+            }]
+        );
+    }
+
+    #[test]
+    fn paste_into_object() {
+        let mut value = json!({ "a": { "b": 1 } });
+        let mut events = vec![];
+        let mut harness = harness(&mut value, &mut events);
+
+        harness.get_by_label("\"a\"").click_secondary();
+        harness.run();
+
+        harness
+            .get_by_role_and_label(Role::Button, "Paste into object")
+            .click();
+        harness.run();
+
+        harness.input_mut().events.push(Paste("[1, 2]".to_string()));
+        harness.run();
+        assert!(
+            harness
+                .query_by_label("Paste JSON at \"/a/new_key\"")
+                .is_some()
+        );
+
+        harness.get_by_role_and_label(Role::Button, "✅").click();
+        harness.run();
+
+        drop(harness);
+
+        assert_eq!(value, json!({ "a": { "b": 1, "new_key": [1, 2] } }));
+    }
+
+    #[test]
+    fn when_paste_invalid_json_shows_error_and_cannot_save() {
+        let original_json = json!({ "a": [] });
+        let mut value = original_json.clone();
+        let mut events = vec![];
+        let mut harness = harness(&mut value, &mut events);
+
+        harness.get_by_label("\"a\"").click_secondary();
+        harness.run();
+
+        harness
+            .get_by_role_and_label(Role::Button, "Paste into array")
+            .click();
+        harness.run();
+
+        harness
+            .input_mut()
+            .events
+            .push(Paste("{\n  \"b\": [1,]\n}".to_string()));
+        harness.run();
+        assert!(
+            harness
+                .query_by_label_contains("at line 2 column 11")
+                .is_some()
+        );
+
+        harness.get_by_role_and_label(Role::Button, "✅").click();
+        harness.run();
+        assert!(harness.query_by_label_contains("Paste JSON at").is_some());
+
+        harness.get_by_role_and_label(Role::Button, "❌").click();
+        harness.run();
+
+        drop(harness);
+
+        assert_eq!(value, original_json);
+        assert!(events.is_empty());
+    }
+
+    /// Drags with the primary pointer button from `from` and releases it at `to`.
+    fn drag(harness: &mut Harness<'_>, from: Pos2, to: Pos2) {
+        let pointer_button = |pos, pressed| PointerButton {
+            pos,
+            button: egui::PointerButton::Primary,
+            pressed,
+            modifiers: Modifiers::NONE,
+        };
+        harness.input_mut().events.push(PointerMoved(from));
+        harness.input_mut().events.push(pointer_button(from, true));
+        harness.run();
+        for t in [0.25, 0.5, 0.75, 1.0] {
+            harness
+                .input_mut()
+                .events
+                .push(PointerMoved(from.lerp(to, t)));
+            harness.run();
+        }
+        harness.input_mut().events.push(pointer_button(to, false));
+        harness.run();
+    }
+}
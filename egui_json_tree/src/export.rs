@@ -0,0 +1,773 @@
+//! Export of the contents of a [`JsonTree`](crate::JsonTree), respecting which arrays/objects are expanded.
+
+use egui::Color32;
+
+use crate::{
+    JsonTreeStyle, JsonTreeVisuals,
+    delimiters::{ARRAY_DELIMITERS, OBJECT_DELIMITERS, SpacingDelimiter},
+    pointer::JsonPointerSegment,
+    value::{BaseValueType, ExpandableType, JsonTreeComments, JsonTreeValue, ToJsonTreeValue},
+};
+
+const INDENT: &str = "  ";
+
+/// Writes the tree as indented plain text, laid out the same way as it is rendered.
+///
+/// If `ansi` is `true`, each token is wrapped in 24-bit ANSI escape codes for its color in `visuals`.
+pub(crate) fn to_text<T: ToJsonTreeValue + ?Sized>(
+    value: &T,
+    is_expanded: &dyn Fn(&[JsonPointerSegment]) -> bool,
+    style: &JsonTreeStyle,
+    visuals: &JsonTreeVisuals,
+    ansi: bool,
+) -> String {
+    let mut exporter = TextExporter {
+        out: String::new(),
+        is_expanded,
+        style,
+        visuals,
+        ansi,
+    };
+    exporter.write_node(value, None, &mut vec![]);
+    exporter.out
+}
+
+struct TextExporter<'e> {
+    out: String,
+    is_expanded: &'e dyn Fn(&[JsonPointerSegment]) -> bool,
+    style: &'e JsonTreeStyle,
+    visuals: &'e JsonTreeVisuals,
+    ansi: bool,
+}
+
+impl<'e> TextExporter<'e> {
+    fn write_node<'a, T: ToJsonTreeValue + ?Sized>(
+        &mut self,
+        value: &'a T,
+        parent: Option<JsonPointerSegment<'a>>,
+        path_segments: &mut Vec<JsonPointerSegment<'a>>,
+    ) {
+        let comments = value.comments();
+        for comment in &comments.leading {
+            for line in comment.lines() {
+                self.indent(path_segments.len());
+                self.write_colored(line, self.color(self.visuals.comment_color));
+                self.out.push('\n');
+            }
+        }
+
+        self.indent(path_segments.len());
+
+        if let Some(property) = parent {
+            self.write_property(&property);
+            self.write_punctuation(SpacingDelimiter::Colon.as_ref());
+        }
+
+        match value.to_json_tree_value() {
+            JsonTreeValue::Base(_, display_value, value_type) => {
+                self.write_value(
+                    &self
+                        .style
+                        .format_value(display_value, &value_type, path_segments),
+                    &value_type,
+                );
+                self.write_trailing_comment(&comments);
+            }
+            JsonTreeValue::Expandable(entries, expandable_type) => {
+                let delimiters = match expandable_type {
+                    ExpandableType::Array => &ARRAY_DELIMITERS,
+                    ExpandableType::Object => &OBJECT_DELIMITERS,
+                };
+                let is_expanded = (self.is_expanded)(path_segments);
+
+                if path_segments.is_empty() && !is_expanded && !self.style.abbreviate_root {
+                    self.write_collapsed_root(&entries, expandable_type);
+                    self.write_trailing_comment(&comments);
+                } else if is_expanded {
+                    self.write_punctuation(delimiters.opening.as_ref());
+                    self.write_trailing_comment(&comments);
+                    self.out.push('\n');
+
+                    for (property, elem) in entries {
+                        path_segments.push(property);
+                        self.write_node(elem, Some(property), path_segments);
+                        path_segments.pop();
+                    }
+
+                    self.indent(path_segments.len());
+                    self.write_punctuation(delimiters.closing.as_ref());
+                } else {
+                    let delimiter = if entries.is_empty() {
+                        delimiters.collapsed_empty
+                    } else {
+                        delimiters.collapsed
+                    };
+                    self.write_punctuation(delimiter.as_ref());
+                    self.write_trailing_comment(&comments);
+                }
+            }
+        };
+
+        self.out.push('\n');
+    }
+
+    fn write_trailing_comment(&mut self, comments: &JsonTreeComments) {
+        if let Some(comment) = &comments.trailing {
+            self.write_colored(
+                &format!(" {comment}"),
+                self.color(self.visuals.comment_color),
+            );
+        }
+    }
+
+    /// Writes a collapsed root array/object with its direct children inline, e.g. `{ "foo": "bar", "baz": {...} }`.
+    fn write_collapsed_root<T: ToJsonTreeValue + ?Sized>(
+        &mut self,
+        entries: &[(JsonPointerSegment, &T)],
+        expandable_type: ExpandableType,
+    ) {
+        let delimiters = match expandable_type {
+            ExpandableType::Array => &ARRAY_DELIMITERS,
+            ExpandableType::Object => &OBJECT_DELIMITERS,
+        };
+
+        self.write_punctuation(delimiters.opening.as_ref());
+        self.write_punctuation(SpacingDelimiter::Empty.as_ref());
+
+        for (idx, (property, elem)) in entries.iter().enumerate() {
+            // Array indices are not shown when the array is collapsed.
+            if expandable_type == ExpandableType::Object {
+                self.write_property(property);
+                self.write_punctuation(SpacingDelimiter::Colon.as_ref());
+            }
+
+            match elem.to_json_tree_value() {
+                JsonTreeValue::Base(_, display_value, value_type) => {
+                    self.write_value(
+                        &self
+                            .style
+                            .format_value(display_value, &value_type, &[*property]),
+                        &value_type,
+                    );
+                }
+                JsonTreeValue::Expandable(nested_entries, nested_expandable_type) => {
+                    let nested_delimiters = match nested_expandable_type {
+                        ExpandableType::Array => &ARRAY_DELIMITERS,
+                        ExpandableType::Object => &OBJECT_DELIMITERS,
+                    };
+                    let delimiter = if nested_entries.is_empty() {
+                        nested_delimiters.collapsed_empty
+                    } else {
+                        nested_delimiters.collapsed
+                    };
+                    self.write_punctuation(delimiter.as_ref());
+                }
+            };
+
+            let spacing = if idx == entries.len() - 1 {
+                SpacingDelimiter::Empty
+            } else {
+                SpacingDelimiter::Comma
+            };
+            self.write_punctuation(spacing.as_ref());
+        }
+
+        self.write_punctuation(delimiters.closing.as_ref());
+    }
+
+    fn write_property(&mut self, property: &JsonPointerSegment) {
+        match property {
+            JsonPointerSegment::Index(_) => {
+                let color = self.color(self.visuals.array_idx_color);
+                self.write_colored(&property.to_string(), color);
+            }
+            JsonPointerSegment::Key(key) => {
+                let color = self.color(self.visuals.object_key_color);
+                self.write_colored(&format!("\"{key}\""), color);
+            }
+            JsonPointerSegment::NonStringKey(key) => {
+                let color = self.color(self.visuals.object_key_color);
+                self.write_colored(&key.to_string(), color);
+            }
+            JsonPointerSegment::DuplicateKey(key, _) => {
+                let color = self.color(self.visuals.warning_color);
+                self.write_colored(&format!("\"{key}\""), color);
+            }
+        }
+    }
+
+    fn write_value(&mut self, value_str: &str, value_type: &BaseValueType) {
+        let color = self.color(self.visuals.get_color(value_type));
+        if self.visuals.is_quoted(value_type) {
+            self.write_colored(&format!("\"{value_str}\""), color);
+        } else {
+            self.write_colored(value_str, color);
+        }
+    }
+
+    fn write_punctuation(&mut self, text: &str) {
+        let color = self.color(self.visuals.punctuation_color);
+        self.write_colored(text, color);
+    }
+
+    /// The color to write a token in, if writing ANSI escape codes.
+    fn color(&self, color: Color32) -> Option<Color32> {
+        self.ansi.then_some(color)
+    }
+
+    fn write_colored(&mut self, text: &str, color: Option<Color32>) {
+        match color {
+            Some(color) => {
+                let [r, g, b, _] = color.to_array();
+                self.out
+                    .push_str(&format!("\x1b[38;2;{r};{g};{b}m{text}\x1b[0m"));
+            }
+            None => self.out.push_str(text),
+        }
+    }
+
+    fn indent(&mut self, depth: usize) {
+        for _ in 0..depth {
+            self.out.push_str(INDENT);
+        }
+    }
+}
+
+/// Writes the tree as a self-contained HTML snippet, using `<details>`/`<summary>` elements for arrays/objects
+/// so they can still be expanded/collapsed, and inline styles for syntax highlighting.
+pub(crate) fn to_html<T: ToJsonTreeValue + ?Sized>(
+    value: &T,
+    is_expanded: &dyn Fn(&[JsonPointerSegment]) -> bool,
+    style: &JsonTreeStyle,
+    visuals: &JsonTreeVisuals,
+    background_color: Color32,
+) -> String {
+    let mut exporter = HtmlExporter {
+        out: String::new(),
+        is_expanded,
+        style,
+        visuals,
+    };
+    // No whitespace is written between elements, since it would be preserved by `white-space: pre`.
+    exporter.out.push_str(&format!(
+        "<div style=\"font-family: monospace; white-space: pre; padding: 0.5em; background-color: {};\">",
+        css_color(background_color)
+    ));
+    exporter.write_node(value, None, &mut vec![]);
+    exporter.out.push_str("</div>");
+    exporter.out
+}
+
+struct HtmlExporter<'e> {
+    out: String,
+    is_expanded: &'e dyn Fn(&[JsonPointerSegment]) -> bool,
+    style: &'e JsonTreeStyle,
+    visuals: &'e JsonTreeVisuals,
+}
+
+impl HtmlExporter<'_> {
+    fn write_node<'a, T: ToJsonTreeValue + ?Sized>(
+        &mut self,
+        value: &'a T,
+        parent: Option<JsonPointerSegment<'a>>,
+        path_segments: &mut Vec<JsonPointerSegment<'a>>,
+    ) {
+        let comments = value.comments();
+        for comment in &comments.leading {
+            self.out.push_str("<div>");
+            self.write_span(comment, self.visuals.comment_color);
+            self.out.push_str("</div>");
+        }
+
+        match value.to_json_tree_value() {
+            JsonTreeValue::Base(_, display_value, value_type) => {
+                self.out.push_str("<div>");
+                self.write_parent(parent);
+                let value_str = self
+                    .style
+                    .format_value(display_value, &value_type, path_segments);
+                let color = self.visuals.get_color(&value_type);
+                if self.visuals.is_quoted(&value_type) {
+                    self.write_span(&format!("\"{value_str}\""), color);
+                } else {
+                    self.write_span(&value_str, color);
+                }
+                self.write_trailing_comment(&comments);
+                self.out.push_str("</div>");
+            }
+            JsonTreeValue::Expandable(entries, expandable_type) => {
+                let delimiters = match expandable_type {
+                    ExpandableType::Array => &ARRAY_DELIMITERS,
+                    ExpandableType::Object => &OBJECT_DELIMITERS,
+                };
+
+                if entries.is_empty() {
+                    self.out.push_str("<div>");
+                    self.write_parent(parent);
+                    self.write_span(
+                        delimiters.collapsed_empty.as_ref(),
+                        self.visuals.punctuation_color,
+                    );
+                    self.write_trailing_comment(&comments);
+                    self.out.push_str("</div>");
+                    return;
+                }
+
+                if (self.is_expanded)(path_segments) {
+                    self.out.push_str("<details open>");
+                } else {
+                    self.out.push_str("<details>");
+                }
+                self.out.push_str("<summary style=\"cursor: pointer;\">");
+                self.write_parent(parent);
+                self.write_span(delimiters.opening.as_ref(), self.visuals.punctuation_color);
+                self.write_trailing_comment(&comments);
+                self.out
+                    .push_str("</summary><div style=\"margin-left: 2ch;\">");
+
+                for (property, elem) in entries {
+                    path_segments.push(property);
+                    self.write_node(elem, Some(property), path_segments);
+                    path_segments.pop();
+                }
+
+                self.out.push_str("</div><div>");
+                self.write_span(delimiters.closing.as_ref(), self.visuals.punctuation_color);
+                self.out.push_str("</div></details>");
+            }
+        };
+    }
+
+    fn write_parent(&mut self, parent: Option<JsonPointerSegment>) {
+        if let Some(property) = parent {
+            match property {
+                JsonPointerSegment::Index(idx) => {
+                    self.write_span(&idx.to_string(), self.visuals.array_idx_color)
+                }
+                JsonPointerSegment::Key(key) => {
+                    self.write_span(&format!("\"{key}\""), self.visuals.object_key_color)
+                }
+                JsonPointerSegment::NonStringKey(key) => {
+                    self.write_span(&key.to_string(), self.visuals.object_key_color)
+                }
+                JsonPointerSegment::DuplicateKey(key, _) => {
+                    self.write_span(&format!("\"{key}\""), self.visuals.warning_color)
+                }
+            }
+            self.write_span(
+                SpacingDelimiter::Colon.as_ref(),
+                self.visuals.punctuation_color,
+            );
+        }
+    }
+
+    fn write_trailing_comment(&mut self, comments: &JsonTreeComments) {
+        if let Some(comment) = &comments.trailing {
+            self.write_span(&format!(" {comment}"), self.visuals.comment_color);
+        }
+    }
+
+    fn write_span(&mut self, text: &str, color: Color32) {
+        self.out.push_str(&format!(
+            "<span style=\"color: {};\">{}</span>",
+            css_color(color),
+            escape_html(text)
+        ));
+    }
+}
+
+fn css_color(color: Color32) -> String {
+    let [r, g, b, _] = color.to_array();
+    format!("#{r:02x}{g:02x}{b:02x}")
+}
+
+fn escape_html(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            '\'' => escaped.push_str("&#39;"),
+            c => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+    use crate::{NumberFormat, NumberNotation, OtherValueVisuals, pointer::JsonPointer};
+
+    #[test]
+    fn export_text_respects_expanded_state() {
+        let value = json!({
+            "bar": { "grep": 21, "qux": false },
+            "baz": null,
+            "foo": [1, "two", [], {}],
+            "thud": {},
+        });
+        let expanded = ["", "/foo"];
+        let is_expanded = |path: &[JsonPointerSegment]| {
+            expanded.contains(&JsonPointer(path).to_json_pointer_string().as_str())
+        };
+
+        assert_eq!(
+            to_text(
+                &value,
+                &is_expanded,
+                &JsonTreeStyle::default(),
+                &JsonTreeVisuals::default(),
+                false
+            ),
+            r#"{
+  "bar": {...}
+  "baz": null
+  "foo": [
+    0: 1
+    1: "two"
+    2: []
+    3: {}
+  ]
+  "thud": {}
+}
+"#
+        );
+    }
+
+    #[test]
+    fn export_text_collapsed_root() {
+        let value = json!({ "bar": [1], "baz": null, "foo": "a" });
+        let is_expanded = |_: &[JsonPointerSegment]| false;
+
+        assert_eq!(
+            to_text(
+                &value,
+                &is_expanded,
+                &JsonTreeStyle::default(),
+                &JsonTreeVisuals::default(),
+                false
+            ),
+            "{ \"bar\": [...], \"baz\": null, \"foo\": \"a\" }\n"
+        );
+        assert_eq!(
+            to_text(
+                &value,
+                &is_expanded,
+                &JsonTreeStyle::new().abbreviate_root(true),
+                &JsonTreeVisuals::default(),
+                false
+            ),
+            "{...}\n"
+        );
+        assert_eq!(
+            to_text(
+                &json!([1, [2]]),
+                &is_expanded,
+                &JsonTreeStyle::default(),
+                &JsonTreeVisuals::default(),
+                false
+            ),
+            "[ 1, [...] ]\n"
+        );
+        assert_eq!(
+            to_text(
+                &json!("foo"),
+                &is_expanded,
+                &JsonTreeStyle::default(),
+                &JsonTreeVisuals::default(),
+                false
+            ),
+            "\"foo\"\n"
+        );
+    }
+
+    #[cfg(feature = "rmpv")]
+    #[test]
+    fn export_text_respects_bytes_format() {
+        let value = rmpv::Value::Array(vec![
+            rmpv::Value::Binary(vec![0x01, 0x02, 0xff]),
+            rmpv::Value::Ext(4, vec![0x00]),
+        ]);
+        let is_expanded = |_: &[JsonPointerSegment]| true;
+
+        assert_eq!(
+            to_text(
+                &value,
+                &is_expanded,
+                &JsonTreeStyle::default(),
+                &JsonTreeVisuals::default(),
+                false
+            ),
+            "[\n  0: h'0102ff'\n  1: ext(4, h'00')\n]\n"
+        );
+        assert_eq!(
+            to_text(
+                &value,
+                &is_expanded,
+                &JsonTreeStyle::new().bytes_format(crate::BytesFormat::Base64),
+                &JsonTreeVisuals::default(),
+                false
+            ),
+            "[\n  0: b64'AQL/'\n  1: ext(4, b64'AA==')\n]\n"
+        );
+    }
+
+    #[cfg(feature = "toml")]
+    #[test]
+    fn export_toml_as_text() {
+        let value: toml::Value =
+            toml::from_str("[package]\nname = \"demo\"\nreleased = 2024-01-02\nfeatures = [\"a\"]")
+                .unwrap();
+        let is_expanded = |_: &[JsonPointerSegment]| true;
+
+        assert_eq!(
+            to_text(
+                &value,
+                &is_expanded,
+                &JsonTreeStyle::default(),
+                &JsonTreeVisuals::default(),
+                false
+            ),
+            "{\n  \"package\": {\n    \"features\": [\n      0: \"a\"\n    ]\n    \"name\": \"demo\"\n    \"released\": \"2024-01-02\"\n  }\n}\n"
+        );
+    }
+
+    #[test]
+    fn export_text_respects_number_format_by_pointer() {
+        let value = json!({ "price": 1234567.891, "id": 255, "count": 1000 });
+        let style = JsonTreeStyle::new()
+            .number_format(NumberFormat::new().thousands_separator(','))
+            .number_format_at(
+                "/price",
+                NumberFormat::new().notation(NumberNotation::Fixed(2)),
+            )
+            .number_format_at("/id", NumberFormat::new().notation(NumberNotation::Hex));
+        let is_expanded = |_: &[JsonPointerSegment]| true;
+
+        assert_eq!(
+            to_text(
+                &value,
+                &is_expanded,
+                &style,
+                &JsonTreeVisuals::default(),
+                false
+            ),
+            "{\n  \"count\": 1,000\n  \"id\": 0xff\n  \"price\": 1234567.89\n}\n"
+        );
+        assert_eq!(
+            to_text(
+                &value,
+                &|_| false,
+                &style,
+                &JsonTreeVisuals::default(),
+                false
+            ),
+            "{ \"count\": 1,000, \"id\": 0xff, \"price\": 1234567.89 }\n"
+        );
+    }
+
+    #[test]
+    fn export_other_values_with_configured_visuals() {
+        enum Custom {
+            Uuid(&'static str),
+            Date(&'static str),
+            List(Vec<Custom>),
+        }
+
+        impl ToJsonTreeValue for Custom {
+            fn to_json_tree_value(&self) -> JsonTreeValue<'_, Self> {
+                match self {
+                    Custom::Uuid(s) => JsonTreeValue::Base(self, s, BaseValueType::Other("uuid")),
+                    Custom::Date(s) => JsonTreeValue::Base(self, s, BaseValueType::Other("date")),
+                    Custom::List(elems) => JsonTreeValue::Expandable(
+                        elems
+                            .iter()
+                            .enumerate()
+                            .map(|(idx, elem)| (JsonPointerSegment::Index(idx), elem))
+                            .collect(),
+                        ExpandableType::Array,
+                    ),
+                }
+            }
+
+            fn is_expandable(&self) -> bool {
+                matches!(self, Custom::List(_))
+            }
+        }
+
+        let value = Custom::List(vec![Custom::Uuid("0f8f"), Custom::Date("2024-01-01")]);
+        let visuals = JsonTreeVisuals {
+            other_color: Color32::from_rgb(1, 1, 1),
+            ..Default::default()
+        }
+        .other_value(
+            "uuid",
+            OtherValueVisuals {
+                color: Color32::from_rgb(2, 2, 2),
+                quoted: true,
+            },
+        );
+        let is_expanded = |_: &[JsonPointerSegment]| true;
+
+        assert_eq!(
+            to_text(
+                &value,
+                &is_expanded,
+                &JsonTreeStyle::default(),
+                &visuals,
+                false
+            ),
+            "[\n  0: \"0f8f\"\n  1: 2024-01-01\n]\n"
+        );
+
+        let html = to_html(
+            &value,
+            &is_expanded,
+            &JsonTreeStyle::default(),
+            &visuals,
+            Color32::BLACK,
+        );
+        assert!(html.contains("<span style=\"color: #020202;\">&quot;0f8f&quot;</span>"));
+        assert!(html.contains("<span style=\"color: #010101;\">2024-01-01</span>"));
+    }
+
+    #[cfg(feature = "raw")]
+    #[test]
+    fn export_html_uses_warning_color_for_duplicate_keys() {
+        let value = crate::raw_value::RawValue::parse(r#"{"a": 1, "b": 2, "a": 3}"#).unwrap();
+        let visuals = JsonTreeVisuals {
+            object_key_color: Color32::from_rgb(1, 1, 1),
+            warning_color: Color32::from_rgb(2, 2, 2),
+            ..Default::default()
+        };
+        let is_expanded = |_: &[JsonPointerSegment]| true;
+
+        let html = to_html(
+            &value,
+            &is_expanded,
+            &JsonTreeStyle::default(),
+            &visuals,
+            Color32::BLACK,
+        );
+        assert_eq!(
+            html.matches("<span style=\"color: #020202;\">&quot;a&quot;</span>")
+                .count(),
+            2
+        );
+        assert!(html.contains("<span style=\"color: #010101;\">&quot;b&quot;</span>"));
+    }
+
+    #[cfg(feature = "json5")]
+    #[test]
+    fn export_text_with_json5_comments() {
+        let value = crate::json5_value::Json5Value::parse(
+            "{\n  // The name\n  name: 'foo', // trailing\n  /* block */\n  \"count\": 0x1F,\n  list: [null, true],\n}",
+        )
+        .unwrap();
+        let is_expanded = |_: &[JsonPointerSegment]| true;
+
+        assert_eq!(
+            to_text(
+                &value,
+                &is_expanded,
+                &JsonTreeStyle::default(),
+                &JsonTreeVisuals::default(),
+                false
+            ),
+            "{\n  // The name\n  \"name\": \"foo\" // trailing\n  /* block */\n  \"count\": 31\n  \"list\": [\n    0: null\n    1: true\n  ]\n}\n"
+        );
+    }
+
+    #[test]
+    fn export_ansi_text_uses_visuals_colors() {
+        let visuals = JsonTreeVisuals {
+            number_color: Color32::from_rgb(1, 2, 3),
+            ..Default::default()
+        };
+        let is_expanded = |_: &[JsonPointerSegment]| true;
+
+        assert_eq!(
+            to_text(
+                &json!(42),
+                &is_expanded,
+                &JsonTreeStyle::default(),
+                &visuals,
+                true
+            ),
+            "\x1b[38;2;1;2;3m42\x1b[0m\n"
+        );
+    }
+
+    #[test]
+    fn export_html_respects_expanded_state_and_escapes_text() {
+        let value = json!({ "a<b": [1, {}], "c": { "d": "x&y" } });
+        let expanded = ["", "/a<b"];
+        let is_expanded = |path: &[JsonPointerSegment]| {
+            expanded.contains(&JsonPointer(path).to_json_pointer_string().as_str())
+        };
+        let visuals = JsonTreeVisuals {
+            object_key_color: Color32::from_rgb(1, 1, 1),
+            array_idx_color: Color32::from_rgb(2, 2, 2),
+            number_color: Color32::from_rgb(3, 3, 3),
+            string_color: Color32::from_rgb(4, 4, 4),
+            punctuation_color: Color32::from_rgb(5, 5, 5),
+            ..Default::default()
+        };
+
+        let html = to_html(
+            &value,
+            &is_expanded,
+            &JsonTreeStyle::default(),
+            &visuals,
+            Color32::BLACK,
+        );
+
+        let key = |k: &str| format!("<span style=\"color: #010101;\">&quot;{k}&quot;</span>");
+        let punct = |p: &str| format!("<span style=\"color: #050505;\">{p}</span>");
+        let expected = [
+            "<div style=\"font-family: monospace; white-space: pre; padding: 0.5em; background-color: #000000;\">".to_string(),
+            "<details open><summary style=\"cursor: pointer;\">".to_string(),
+            punct("{"),
+            "</summary><div style=\"margin-left: 2ch;\">".to_string(),
+            "<details open><summary style=\"cursor: pointer;\">".to_string(),
+            key("a&lt;b"),
+            punct(": "),
+            punct("["),
+            "</summary><div style=\"margin-left: 2ch;\">".to_string(),
+            "<div><span style=\"color: #020202;\">0</span>".to_string(),
+            punct(": "),
+            "<span style=\"color: #030303;\">1</span></div>".to_string(),
+            "<div><span style=\"color: #020202;\">1</span>".to_string(),
+            punct(": "),
+            punct("{}"),
+            "</div>".to_string(),
+            "</div><div>".to_string(),
+            punct("]"),
+            "</div></details>".to_string(),
+            "<details><summary style=\"cursor: pointer;\">".to_string(),
+            key("c"),
+            punct(": "),
+            punct("{"),
+            "</summary><div style=\"margin-left: 2ch;\">".to_string(),
+            "<div>".to_string(),
+            key("d"),
+            punct(": "),
+            "<span style=\"color: #040404;\">&quot;x&amp;y&quot;</span></div>".to_string(),
+            "</div><div>".to_string(),
+            punct("}"),
+            "</div></details>".to_string(),
+            "</div><div>".to_string(),
+            punct("}"),
+            "</div></details>".to_string(),
+            "</div>".to_string(),
+        ]
+        .concat();
+
+        assert_eq!(html, expected);
+    }
+}
@@ -0,0 +1,680 @@
+//! Undo/redo history of the edits to a JSON document.
+//!
+//! A [`JsonEditHistory`] applies [`JsonEditEvent`]s to a document and records each of them as a reversible [`JsonEditOperation`],
+//! which keeps the values that it replaced or removed, so that it can be undone and redone.
+//! Operations are identified by JSON pointer strings, and each of them can be written as a [JSON Patch (RFC 6902)](https://datatracker.ietf.org/doc/html/rfc6902) operation.
+//!
+//! Pass a history to [`JsonTreeEditor::history`](crate::JsonTreeEditor::history) to record the edits made in the editor,
+//! and to undo and redo them with Ctrl+Z and Ctrl+Shift+Z (⌘Z and ⌘⇧Z on macOS).
+//!
+//! ```rust
+//! # use egui_json_tree::{edit::JsonEditEvent, history::JsonEditHistory, serialize::JsonSerializer};
+//! # use serde_json::json;
+//! let mut value = json!({ "replicas": 2 });
+//! let mut history = JsonEditHistory::new().limit(50);
+//!
+//! let event = JsonEditEvent::Replace { pointer: "/replicas".to_string(), value: json!(3) };
+//! history.apply(&mut value, &event).unwrap();
+//! assert_eq!(value, json!({ "replicas": 3 }));
+//! assert_eq!(
+//!     history.to_patch_string(&JsonSerializer::new()),
+//!     r#"[{"op":"replace","path":"/replicas","value":3}]"#
+//! );
+//!
+//! history.undo(&mut value).unwrap();
+//! assert_eq!(value, json!({ "replicas": 2 }));
+//! history.redo(&mut value).unwrap();
+//! assert_eq!(value, json!({ "replicas": 3 }));
+//! ```
+
+use std::{
+    collections::VecDeque,
+    fmt::{self, Write},
+};
+
+use crate::{
+    edit::{EditableJsonValue, JsonEditError, JsonEditEvent},
+    patch::{PatchOperationFields, write_operation, write_operations},
+    pointer::{escape_json_pointer_key, resolve_json_pointer, split_json_pointer},
+    serialize::JsonSerializer,
+    value::JsonTreeValue,
+};
+
+/// A reversible change to a JSON document, in which values are identified by JSON pointer strings.
+///
+/// Each variant corresponds to the JSON Patch operation of the same name,
+/// and keeps any value that it replaced or removed, so that it can be inverted.
+#[derive(Debug, Clone, PartialEq)]
+pub enum JsonEditOperation<T> {
+    /// Add `value` at `pointer`.
+    Add { pointer: String, value: T },
+    /// Remove `old_value` from `pointer`.
+    Remove { pointer: String, old_value: T },
+    /// Replace `old_value` at `pointer` with `value`.
+    Replace {
+        pointer: String,
+        old_value: T,
+        value: T,
+    },
+    /// Move the value at `from` to `pointer`, e.g. when dragging a value.
+    Move { from: String, pointer: String },
+    /// Rename the key of the object entry at `from` to the last reference token of `pointer`, keeping the entry's position.
+    ///
+    /// It is written as a JSON Patch `move` operation.
+    RenameKey { from: String, pointer: String },
+}
+
+impl<T: EditableJsonValue> JsonEditOperation<T> {
+    /// Returns the name of the operation, as written in the `"op"` member of a JSON Patch operation.
+    pub fn op(&self) -> &'static str {
+        match self {
+            JsonEditOperation::Add { .. } => "add",
+            JsonEditOperation::Remove { .. } => "remove",
+            JsonEditOperation::Replace { .. } => "replace",
+            JsonEditOperation::Move { .. } | JsonEditOperation::RenameKey { .. } => "move",
+        }
+    }
+
+    /// Returns the JSON pointer string to the target of the operation.
+    pub fn pointer(&self) -> &str {
+        match self {
+            JsonEditOperation::Add { pointer, .. }
+            | JsonEditOperation::Remove { pointer, .. }
+            | JsonEditOperation::Replace { pointer, .. }
+            | JsonEditOperation::Move { pointer, .. }
+            | JsonEditOperation::RenameKey { pointer, .. } => pointer,
+        }
+    }
+
+    /// Returns the JSON pointer string to the source of the operation, if it is a move or a rename.
+    pub fn from_pointer(&self) -> Option<&str> {
+        match self {
+            JsonEditOperation::Move { from, .. } | JsonEditOperation::RenameKey { from, .. } => {
+                Some(from)
+            }
+            _ => None,
+        }
+    }
+
+    /// Returns the value of the operation as written in a JSON Patch, if it has one.
+    pub fn value(&self) -> Option<&T> {
+        match self {
+            JsonEditOperation::Add { value, .. } | JsonEditOperation::Replace { value, .. } => {
+                Some(value)
+            }
+            JsonEditOperation::Remove { .. }
+            | JsonEditOperation::Move { .. }
+            | JsonEditOperation::RenameKey { .. } => None,
+        }
+    }
+
+    /// Returns the operation that reverts this operation.
+    pub fn inverse(&self) -> Self {
+        match self.clone() {
+            JsonEditOperation::Add { pointer, value } => JsonEditOperation::Remove {
+                pointer,
+                old_value: value,
+            },
+            JsonEditOperation::Remove { pointer, old_value } => JsonEditOperation::Add {
+                pointer,
+                value: old_value,
+            },
+            JsonEditOperation::Replace {
+                pointer,
+                old_value,
+                value,
+            } => JsonEditOperation::Replace {
+                pointer,
+                old_value: value,
+                value: old_value,
+            },
+            JsonEditOperation::Move { from, pointer } => JsonEditOperation::Move {
+                from: pointer,
+                pointer: from,
+            },
+            JsonEditOperation::RenameKey { from, pointer } => JsonEditOperation::RenameKey {
+                from: pointer,
+                pointer: from,
+            },
+        }
+    }
+
+    /// Writes the operation as a JSON Patch operation object.
+    pub fn to_patch_string(&self, serializer: &JsonSerializer) -> String {
+        let mut out = String::new();
+        // Writing to a `String` cannot fail.
+        let _ = write_operation(serializer, &mut out, &self.patch_fields(), 0);
+        out
+    }
+
+    /// Records an event as an operation, before it is applied to the document.
+    fn from_event(document: &T, event: &JsonEditEvent<T>) -> Result<Self, JsonEditError> {
+        let current_value = |pointer: &String| {
+            resolve_json_pointer(document, pointer)
+                .cloned()
+                .ok_or_else(|| JsonEditError::NotFound(pointer.clone()))
+        };
+        let operation = match event {
+            JsonEditEvent::Replace { pointer, value } => JsonEditOperation::Replace {
+                pointer: pointer.clone(),
+                old_value: current_value(pointer)?,
+                value: value.clone(),
+            },
+            JsonEditEvent::RenameKey {
+                object_pointer,
+                key,
+                new_key,
+            } => JsonEditOperation::RenameKey {
+                from: format!("{object_pointer}{}", escape_json_pointer_key(key)),
+                pointer: format!("{object_pointer}{}", escape_json_pointer_key(new_key)),
+            },
+            JsonEditEvent::Add { pointer, value } => JsonEditOperation::Add {
+                pointer: resolve_array_end(document, pointer),
+                value: value.clone(),
+            },
+            JsonEditEvent::Remove { pointer } => JsonEditOperation::Remove {
+                pointer: pointer.clone(),
+                old_value: current_value(pointer)?,
+            },
+            JsonEditEvent::Move { from, pointer } => JsonEditOperation::Move {
+                from: from.clone(),
+                pointer: pointer.clone(),
+            },
+        };
+        Ok(operation)
+    }
+
+    /// Converts the operation to the event that applies it.
+    fn to_event(&self) -> JsonEditEvent<T> {
+        match self.clone() {
+            JsonEditOperation::Add { pointer, value } => JsonEditEvent::Add { pointer, value },
+            JsonEditOperation::Remove { pointer, .. } => JsonEditEvent::Remove { pointer },
+            JsonEditOperation::Replace { pointer, value, .. } => {
+                JsonEditEvent::Replace { pointer, value }
+            }
+            JsonEditOperation::Move { from, pointer } => JsonEditEvent::Move { from, pointer },
+            JsonEditOperation::RenameKey { from, pointer } => {
+                // Both pointers are recorded from the same object pointer, see `from_event`.
+                let (object_pointer, key) = split_json_pointer(&from).unwrap_or_default();
+                let (_, new_key) = split_json_pointer(&pointer).unwrap_or_default();
+                JsonEditEvent::RenameKey {
+                    object_pointer: object_pointer.to_string(),
+                    key,
+                    new_key,
+                }
+            }
+        }
+    }
+
+    fn patch_fields(&self) -> PatchOperationFields<'_, T> {
+        PatchOperationFields {
+            op: self.op(),
+            from: self.from_pointer().map(str::to_string),
+            path: self.pointer().to_string(),
+            value: self.value(),
+        }
+    }
+}
+
+/// Replaces the `-` token of a pointer to the end of an array with the index it refers to, so that the addition can be undone.
+fn resolve_array_end<T: EditableJsonValue>(document: &T, pointer: &str) -> String {
+    if let Some(parent_pointer) = pointer.strip_suffix("/-")
+        && let Some(JsonTreeValue::Expandable(entries, _)) =
+            resolve_json_pointer(document, parent_pointer).map(|parent| parent.to_json_tree_value())
+    {
+        return format!("{parent_pointer}/{}", entries.len());
+    }
+    pointer.to_string()
+}
+
+/// An undo/redo history of the edits to a JSON document.
+///
+/// Edits must be applied via [`JsonEditHistory::apply`] to be recorded, and the document must not be changed in any other way
+/// while the history is in use, otherwise the recorded operations may no longer apply to it.
+///
+/// Each call to [`JsonEditHistory::apply`] is recorded as a separate undo step, unless it is made between
+/// [`JsonEditHistory::begin_group`] and [`JsonEditHistory::end_group`], in which case all edits of the group are undone together.
+#[derive(Debug, Clone)]
+pub struct JsonEditHistory<T> {
+    undo_steps: VecDeque<Vec<JsonEditOperation<T>>>,
+    redo_steps: Vec<Vec<JsonEditOperation<T>>>,
+    group: Vec<JsonEditOperation<T>>,
+    group_depth: usize,
+    limit: usize,
+}
+
+impl<T: EditableJsonValue> Default for JsonEditHistory<T> {
+    fn default() -> Self {
+        Self {
+            undo_steps: VecDeque::new(),
+            redo_steps: vec![],
+            group: vec![],
+            group_depth: 0,
+            limit: 100,
+        }
+    }
+}
+
+impl<T: EditableJsonValue> JsonEditHistory<T> {
+    /// Creates an empty history, which keeps up to 100 undo steps.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Override the maximum number of undo steps to keep, after which the oldest steps are forgotten.
+    /// Defaults to 100.
+    pub fn limit(mut self, limit: usize) -> Self {
+        self.limit = limit;
+        self.enforce_limit();
+        self
+    }
+
+    /// Applies an edit to the document, and records it so that it can be undone.
+    ///
+    /// Recording an edit clears the steps that can be redone.
+    pub fn apply(
+        &mut self,
+        document: &mut T,
+        event: &JsonEditEvent<T>,
+    ) -> Result<(), JsonEditError> {
+        let operation = JsonEditOperation::from_event(document, event)?;
+        document.apply_edit(event)?;
+        if let JsonEditOperation::Move { from, pointer }
+        | JsonEditOperation::RenameKey { from, pointer } = &operation
+            && from == pointer
+        {
+            return Ok(());
+        }
+        self.redo_steps.clear();
+        self.group.push(operation);
+        if self.group_depth == 0 {
+            self.finish_step();
+        }
+        Ok(())
+    }
+
+    /// Starts a group of edits, which are undone and redone together as a single step.
+    ///
+    /// Groups may be nested, in which case the edits of the outermost group are recorded as a single step.
+    pub fn begin_group(&mut self) {
+        self.group_depth += 1;
+    }
+
+    /// Ends the group of edits started by the matching [`JsonEditHistory::begin_group`].
+    pub fn end_group(&mut self) {
+        self.group_depth = self.group_depth.saturating_sub(1);
+        if self.group_depth == 0 {
+            self.finish_step();
+        }
+    }
+
+    /// Returns whether there is a step that can be undone.
+    pub fn can_undo(&self) -> bool {
+        !self.undo_steps.is_empty()
+    }
+
+    /// Returns whether there is a step that can be redone.
+    pub fn can_redo(&self) -> bool {
+        !self.redo_steps.is_empty()
+    }
+
+    /// Reverts the most recent step, returning the edits that were applied to the document to do so,
+    /// which are empty if there was nothing to undo.
+    ///
+    /// If an edit cannot be applied, e.g. because the document was changed outside of the history,
+    /// the edits of the step that were already applied are reverted, and the step remains to be undone.
+    pub fn undo(&mut self, document: &mut T) -> Result<Vec<JsonEditEvent<T>>, JsonEditError> {
+        let Some(step) = self.undo_steps.pop_back() else {
+            return Ok(vec![]);
+        };
+        match apply_operations(document, step.iter().rev().map(JsonEditOperation::inverse)) {
+            Ok(events) => {
+                self.redo_steps.push(step);
+                Ok(events)
+            }
+            Err(error) => {
+                self.undo_steps.push_back(step);
+                Err(error)
+            }
+        }
+    }
+
+    /// Reapplies the most recently undone step, returning the edits that were applied to the document to do so,
+    /// which are empty if there was nothing to redo.
+    ///
+    /// If an edit cannot be applied, e.g. because the document was changed outside of the history,
+    /// the edits of the step that were already applied are reverted, and the step remains to be redone.
+    pub fn redo(&mut self, document: &mut T) -> Result<Vec<JsonEditEvent<T>>, JsonEditError> {
+        let Some(step) = self.redo_steps.pop() else {
+            return Ok(vec![]);
+        };
+        match apply_operations(document, step.iter().cloned()) {
+            Ok(events) => {
+                self.undo_steps.push_back(step);
+                Ok(events)
+            }
+            Err(error) => {
+                self.redo_steps.push(step);
+                Err(error)
+            }
+        }
+    }
+
+    /// Forgets all recorded steps.
+    pub fn clear(&mut self) {
+        self.undo_steps.clear();
+        self.redo_steps.clear();
+        self.group.clear();
+    }
+
+    /// Returns the operations of the steps that can be undone, in the order they were applied,
+    /// i.e. the operations that transform the document as it was before the oldest of these steps into the current document.
+    pub fn operations(&self) -> impl Iterator<Item = &JsonEditOperation<T>> {
+        self.undo_steps.iter().flatten()
+    }
+
+    /// Writes the [`JsonEditHistory::operations`] as a JSON Patch array.
+    pub fn to_patch_string(&self, serializer: &JsonSerializer) -> String {
+        let mut out = String::new();
+        // Writing to a `String` cannot fail.
+        let _ = self.write_patch(serializer, &mut out);
+        out
+    }
+
+    /// Writes the [`JsonEditHistory::operations`] as a JSON Patch array into the given writer.
+    pub fn write_patch(&self, serializer: &JsonSerializer, writer: &mut impl Write) -> fmt::Result {
+        write_operations(
+            serializer,
+            writer,
+            self.operations().map(JsonEditOperation::patch_fields),
+        )
+    }
+
+    fn finish_step(&mut self) {
+        if !self.group.is_empty() {
+            self.undo_steps.push_back(std::mem::take(&mut self.group));
+            self.enforce_limit();
+        }
+    }
+
+    fn enforce_limit(&mut self) {
+        while self.undo_steps.len() > self.limit {
+            self.undo_steps.pop_front();
+        }
+    }
+}
+
+/// Applies the operations to the document in order, returning the applied edits.
+///
+/// If an operation cannot be applied, the operations applied before it are reverted, leaving the document unchanged.
+fn apply_operations<T: EditableJsonValue>(
+    document: &mut T,
+    operations: impl Iterator<Item = JsonEditOperation<T>>,
+) -> Result<Vec<JsonEditEvent<T>>, JsonEditError> {
+    let mut applied: Vec<JsonEditOperation<T>> = vec![];
+    for operation in operations {
+        if let Err(error) = document.apply_edit(&operation.to_event()) {
+            for applied_operation in applied.iter().rev() {
+                // The inverse of an operation that was just applied also applies.
+                let _ = document.apply_edit(&applied_operation.inverse().to_event());
+            }
+            return Err(error);
+        }
+        applied.push(operation);
+    }
+    Ok(applied.iter().map(JsonEditOperation::to_event).collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::{Value, json};
+
+    use super::*;
+
+    fn replace(pointer: &str, value: Value) -> JsonEditEvent<Value> {
+        JsonEditEvent::Replace {
+            pointer: pointer.to_string(),
+            value,
+        }
+    }
+
+    #[test]
+    fn undo_and_redo_each_kind_of_edit() {
+        let original = json!({ "a": [1, 2], "b": { "c": null } });
+        let mut value = original.clone();
+        let mut history = JsonEditHistory::new();
+        let events = [
+            replace("/a/0", json!("one")),
+            JsonEditEvent::Add {
+                pointer: "/a/-".to_string(),
+                value: json!(3),
+            },
+            JsonEditEvent::RenameKey {
+                object_pointer: "/b".to_string(),
+                key: "c".to_string(),
+                new_key: "d/e".to_string(),
+            },
+            JsonEditEvent::Remove {
+                pointer: "/a/1".to_string(),
+            },
+            JsonEditEvent::Move {
+                from: "/a/0".to_string(),
+                pointer: "/b/f".to_string(),
+            },
+        ];
+        for event in &events {
+            history.apply(&mut value, event).unwrap();
+        }
+        let edited = json!({ "a": [3], "b": { "d/e": null, "f": "one" } });
+        assert_eq!(value, edited);
+
+        while history.can_undo() {
+            history.undo(&mut value).unwrap();
+        }
+        assert_eq!(value, original);
+        assert!(history.undo(&mut value).unwrap().is_empty());
+
+        while history.can_redo() {
+            history.redo(&mut value).unwrap();
+        }
+        assert_eq!(value, edited);
+    }
+
+    #[test]
+    fn undo_and_redo_rename_keep_key_order() {
+        // The keys are chosen so that the expected order is the same whether or not serde_json's `preserve_order` feature is enabled.
+        // Undo and redo apply the rename itself, rather than a move, which would place the entry last with `preserve_order`.
+        let mut value = json!({ "a": 1, "b": 2, "c": 3 });
+        let mut history = JsonEditHistory::new();
+        let rename = JsonEditEvent::RenameKey {
+            object_pointer: "".to_string(),
+            key: "b".to_string(),
+            new_key: "b/c".to_string(),
+        };
+        history.apply(&mut value, &rename).unwrap();
+        let keys = |value: &Value| {
+            value
+                .as_object()
+                .unwrap()
+                .keys()
+                .cloned()
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(keys(&value), ["a", "b/c", "c"]);
+
+        assert_eq!(
+            history.undo(&mut value),
+            Ok(vec![JsonEditEvent::RenameKey {
+                object_pointer: "".to_string(),
+                key: "b/c".to_string(),
+                new_key: "b".to_string(),
+            }])
+        );
+        assert_eq!(keys(&value), ["a", "b", "c"]);
+        assert_eq!(history.redo(&mut value), Ok(vec![rename]));
+        assert_eq!(keys(&value), ["a", "b/c", "c"]);
+    }
+
+    #[test]
+    fn undo_returns_inverse_events() {
+        let mut value = json!({ "a": 1 });
+        let mut history = JsonEditHistory::new();
+        history
+            .apply(
+                &mut value,
+                &JsonEditEvent::Remove {
+                    pointer: "/a".to_string(),
+                },
+            )
+            .unwrap();
+
+        assert_eq!(
+            history.undo(&mut value),
+            Ok(vec![JsonEditEvent::Add {
+                pointer: "/a".to_string(),
+                value: json!(1)
+            }])
+        );
+        assert_eq!(
+            history.redo(&mut value),
+            Ok(vec![JsonEditEvent::Remove {
+                pointer: "/a".to_string()
+            }])
+        );
+    }
+
+    #[test]
+    fn groups_are_undone_together() {
+        let mut value = json!({ "a": 1, "b": 2 });
+        let mut history = JsonEditHistory::new();
+        history.begin_group();
+        history
+            .apply(&mut value, &replace("/a", json!(10)))
+            .unwrap();
+        history.begin_group();
+        history
+            .apply(&mut value, &replace("/b", json!(20)))
+            .unwrap();
+        history.end_group();
+        history.end_group();
+        history
+            .apply(&mut value, &replace("/a", json!(100)))
+            .unwrap();
+
+        history.undo(&mut value).unwrap();
+        assert_eq!(value, json!({ "a": 10, "b": 20 }));
+        history.undo(&mut value).unwrap();
+        assert_eq!(value, json!({ "a": 1, "b": 2 }));
+        assert!(!history.can_undo());
+    }
+
+    #[test]
+    fn new_edits_clear_redo_steps() {
+        let mut value = json!({ "a": 1 });
+        let mut history = JsonEditHistory::new();
+        history.apply(&mut value, &replace("/a", json!(2))).unwrap();
+        history.undo(&mut value).unwrap();
+        assert!(history.can_redo());
+
+        history.apply(&mut value, &replace("/a", json!(3))).unwrap();
+        assert!(!history.can_redo());
+    }
+
+    #[test]
+    fn limit_forgets_oldest_steps() {
+        let mut value = json!(0);
+        let mut history = JsonEditHistory::new().limit(2);
+        for n in 1..=3 {
+            history.apply(&mut value, &replace("", json!(n))).unwrap();
+        }
+        history.undo(&mut value).unwrap();
+        history.undo(&mut value).unwrap();
+        assert_eq!(value, json!(1));
+        assert!(!history.can_undo());
+    }
+
+    #[test]
+    fn failed_edits_are_not_recorded() {
+        let mut value = json!({ "a": 1 });
+        let mut history = JsonEditHistory::new();
+        assert_eq!(
+            history.apply(&mut value, &replace("/b", json!(2))),
+            Err(JsonEditError::NotFound("/b".to_string()))
+        );
+        assert!(!history.can_undo());
+    }
+
+    #[test]
+    fn failed_undo_and_redo_leave_document_and_steps_unchanged() {
+        let mut value = json!({ "a": 1, "b": 2 });
+        let mut history = JsonEditHistory::new();
+        history.begin_group();
+        history
+            .apply(&mut value, &replace("/a", json!(10)))
+            .unwrap();
+        history
+            .apply(&mut value, &replace("/b", json!(20)))
+            .unwrap();
+        history.end_group();
+
+        // Changed outside of the history, so the first operation to undo, of `/b`, applies but that of `/a` does not.
+        value.as_object_mut().unwrap().remove("a");
+        let changed = value.clone();
+        assert_eq!(
+            history.undo(&mut value),
+            Err(JsonEditError::NotFound("/a".to_string()))
+        );
+        assert_eq!(value, changed);
+        assert!(history.can_undo());
+        assert!(!history.can_redo());
+
+        value["a"] = json!(10);
+        assert_eq!(history.undo(&mut value).unwrap().len(), 2);
+        assert_eq!(value, json!({ "a": 1, "b": 2 }));
+
+        value.as_object_mut().unwrap().remove("b");
+        let changed = value.clone();
+        assert_eq!(
+            history.redo(&mut value),
+            Err(JsonEditError::NotFound("/b".to_string()))
+        );
+        assert_eq!(value, changed);
+        assert!(history.can_redo());
+        assert!(!history.can_undo());
+    }
+
+    #[test]
+    fn operations_as_json_patch() {
+        let mut value = json!({ "a": [1], "b": true });
+        let mut history = JsonEditHistory::new();
+        let events = [
+            JsonEditEvent::Add {
+                pointer: "/a/-".to_string(),
+                value: json!({ "c": 2 }),
+            },
+            JsonEditEvent::RenameKey {
+                object_pointer: "".to_string(),
+                key: "b".to_string(),
+                new_key: "x~y".to_string(),
+            },
+            JsonEditEvent::Remove {
+                pointer: "/a/0".to_string(),
+            },
+        ];
+        for event in &events {
+            history.apply(&mut value, event).unwrap();
+        }
+
+        assert_eq!(
+            history.to_patch_string(&JsonSerializer::new()),
+            r#"[{"op":"add","path":"/a/1","value":{"c":2}},{"op":"move","from":"/b","path":"/x~0y"},{"op":"remove","path":"/a/0"}]"#
+        );
+        let operation = history.operations().last().unwrap();
+        assert_eq!(
+            operation
+                .inverse()
+                .to_patch_string(&JsonSerializer::pretty()),
+            "{\n  \"op\": \"add\",\n  \"path\": \"/a/0\",\n  \"value\": 1\n}"
+        );
+    }
+}
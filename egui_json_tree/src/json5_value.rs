@@ -0,0 +1,423 @@
+//! Visualisation of JSON5 documents parsed by [`json5format`], including their comments.
+//!
+//! `json5format` keeps array items and object property values behind `Rc<RefCell<_>>`, so they cannot be borrowed
+//! for as long as their parent. [`Json5Value::from_json5format`] therefore copies a `json5format::Value` into a [`Json5Value`] tree,
+//! which implements [`ToJsonTreeValue`]. Build it once when the document changes, rather than every frame.
+//! - Escape sequences in strings and quoted property names are unescaped, e.g. `'it\'s'` is the string `it's`.
+//! - Numbers are shown and copied in JSON notation where possible, e.g. `0x1F` as `31`,
+//!   and `Infinity` and `NaN` as written. The number as written in the source is kept in [`Json5Number::source`].
+//! - Comments are provided via [`ToJsonTreeValue::comments`].
+//!
+//! ```rust
+//! # use egui_json_tree::json5_value::{Json5Number, Json5Value, Json5ValueKind};
+//! # use egui_json_tree::value::ToJsonTreeValue;
+//! let value = Json5Value::parse("{\n  // The answer\n  answer: 0x2A,\n}").unwrap();
+//!
+//! let Json5ValueKind::Object(entries) = &value.kind else { unreachable!() };
+//! assert_eq!(entries[0].0, "answer");
+//! let Json5ValueKind::Number(Json5Number { source, json }) = &entries[0].1.kind else { unreachable!() };
+//! assert_eq!((source.as_str(), json.as_deref()), ("0x2A", Some("42")));
+//! assert_eq!(entries[0].1.leading_comments, ["// The answer"]);
+//! assert_eq!(value.to_json_string(false), r#"{"answer":42}"#);
+//! ```
+
+use std::{borrow::Cow, iter::Peekable, str::Chars};
+
+use crate::{
+    pointer::JsonPointerSegment,
+    serialize::is_json_number,
+    value::{BaseValueType, ExpandableType, JsonTreeComments, JsonTreeValue, ToJsonTreeValue},
+};
+
+/// A JSON5 value along with its comments, copied from a `json5format::Value`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Json5Value {
+    pub kind: Json5ValueKind,
+    /// The comments on the lines before the value, including their delimiters, e.g. `// comment` or `/* comment */`.
+    pub leading_comments: Vec<String>,
+    /// The comment after the value on the same line, including its delimiter.
+    pub trailing_comment: Option<String>,
+}
+
+/// The kind of a [`Json5Value`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Json5ValueKind {
+    Null,
+    Bool(bool),
+    Number(Json5Number),
+    /// A string, without its quotes and with its escape sequences unescaped.
+    String(String),
+    Array(Vec<Json5Value>),
+    /// The properties of an object, keyed by their unescaped names without quotes.
+    Object(Vec<(String, Json5Value)>),
+}
+
+/// A JSON5 number, along with its JSON notation.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Json5Number {
+    /// The number exactly as written in the source, e.g. `0x1F`, `+1` or `Infinity`.
+    pub source: String,
+    /// The number in JSON notation, e.g. `31` or `1`, or `None` if it has none, e.g. `Infinity` or `NaN`.
+    pub json: Option<String>,
+}
+
+impl Json5Number {
+    /// Converts a number as written in a JSON5 document to its JSON notation.
+    pub fn new(source: impl Into<String>) -> Self {
+        let source = source.into();
+        Self {
+            json: json_number(&source),
+            source,
+        }
+    }
+}
+
+impl Json5Value {
+    /// Parses a JSON5 document. A document without a value is [`Json5ValueKind::Null`].
+    pub fn parse(src: &str) -> Result<Self, json5format::Error> {
+        let document = json5format::ParsedDocument::from_str(src, None)?;
+        Ok(document
+            .content
+            .items()
+            .next()
+            .map_or_else(Self::null, |value| Self::from_json5format(&value)))
+    }
+
+    /// Copies a `json5format::Value`, along with every value and comment within it.
+    pub fn from_json5format(value: &json5format::Value) -> Self {
+        let kind = match value {
+            json5format::Value::Primitive { val, .. } => match val.as_str() {
+                "null" => Json5ValueKind::Null,
+                "true" => Json5ValueKind::Bool(true),
+                "false" => Json5ValueKind::Bool(false),
+                s if s.starts_with(['"', '\'']) => {
+                    Json5ValueKind::String(unescape_json5(unquote_json5(s)))
+                }
+                s => Json5ValueKind::Number(Json5Number::new(s)),
+            },
+            json5format::Value::Array { val, .. } => Json5ValueKind::Array(
+                val.items()
+                    .map(|elem| Self::from_json5format(&elem))
+                    .collect(),
+            ),
+            json5format::Value::Object { val, .. } => Json5ValueKind::Object(
+                val.properties()
+                    .map(|property| {
+                        (
+                            unescape_json5(unquote_json5(property.name())),
+                            Self::from_json5format(&property.value()),
+                        )
+                    })
+                    .collect(),
+            ),
+        };
+
+        let comments = value.comments();
+        Self {
+            kind,
+            leading_comments: comments
+                .before_value()
+                .iter()
+                .filter_map(|comment| match comment {
+                    json5format::Comment::Line(line) => Some(format!("//{line}")),
+                    json5format::Comment::Block { lines, .. } => {
+                        Some(format!("/*{}*/", lines.join("\n")))
+                    }
+                    json5format::Comment::Break => None,
+                })
+                .collect(),
+            trailing_comment: comments.end_of_line().as_ref().map(|comment| {
+                comment
+                    .lines()
+                    .map(|line| format!("//{line}"))
+                    .collect::<Vec<_>>()
+                    .join("\n")
+            }),
+        }
+    }
+
+    fn null() -> Self {
+        Self {
+            kind: Json5ValueKind::Null,
+            leading_comments: vec![],
+            trailing_comment: None,
+        }
+    }
+}
+
+impl ToJsonTreeValue for Json5Value {
+    fn to_json_tree_value(&self) -> JsonTreeValue<'_, Self> {
+        match &self.kind {
+            Json5ValueKind::Null => JsonTreeValue::Base(self, &"null", BaseValueType::Null),
+            Json5ValueKind::Bool(b) => JsonTreeValue::Base(self, b, BaseValueType::Bool),
+            // Shown in JSON notation where possible, so that it is also serialized as a JSON number.
+            Json5ValueKind::Number(n) => JsonTreeValue::Base(
+                self,
+                n.json.as_ref().unwrap_or(&n.source),
+                BaseValueType::Number,
+            ),
+            Json5ValueKind::String(s) => JsonTreeValue::Base(self, s, BaseValueType::String),
+            Json5ValueKind::Array(arr) => JsonTreeValue::Expandable(
+                arr.iter()
+                    .enumerate()
+                    .map(|(idx, elem)| (JsonPointerSegment::Index(idx), elem))
+                    .collect(),
+                ExpandableType::Array,
+            ),
+            Json5ValueKind::Object(obj) => JsonTreeValue::Expandable(
+                obj.iter()
+                    .map(|(key, val)| (JsonPointerSegment::Key(key), val))
+                    .collect(),
+                ExpandableType::Object,
+            ),
+        }
+    }
+
+    fn is_expandable(&self) -> bool {
+        matches!(
+            self.kind,
+            Json5ValueKind::Array(_) | Json5ValueKind::Object(_)
+        )
+    }
+
+    fn comments(&self) -> JsonTreeComments<'_> {
+        JsonTreeComments {
+            leading: self
+                .leading_comments
+                .iter()
+                .map(|comment| Cow::Borrowed(comment.as_str()))
+                .collect(),
+            trailing: self.trailing_comment.as_deref().map(Cow::Borrowed),
+        }
+    }
+}
+
+/// Removes the quotes from a JSON5 string or quoted property name, if any.
+fn unquote_json5(s: &str) -> &str {
+    match s.as_bytes() {
+        [b'"', .., b'"'] | [b'\'', .., b'\''] => &s[1..s.len() - 1],
+        _ => s,
+    }
+}
+
+/// Unescapes the escape sequences of a JSON5 string or property name, without its quotes.
+///
+/// Unlike JSON, JSON5 allows escaping any character, e.g. `\'` or `\a`, hexadecimal escapes (`\x41`),
+/// `\v` and `\0`, and escaping a line terminator to continue the string on the next line.
+fn unescape_json5(s: &str) -> String {
+    let mut out = String::with_capacity(s.len());
+    let mut chars = s.chars().peekable();
+    while let Some(c) = chars.next() {
+        if c != '\\' {
+            out.push(c);
+            continue;
+        }
+        let Some(escaped) = chars.next() else {
+            out.push(c);
+            break;
+        };
+        match escaped {
+            'b' => out.push('\u{08}'),
+            'f' => out.push('\u{0C}'),
+            'n' => out.push('\n'),
+            'r' => out.push('\r'),
+            't' => out.push('\t'),
+            'v' => out.push('\u{0B}'),
+            '0' => out.push('\0'),
+            'x' => push_hex_escape(&mut out, &mut chars, 2),
+            'u' => push_hex_escape(&mut out, &mut chars, 4),
+            // A line continuation, which is not part of the string.
+            '\r' => {
+                chars.next_if_eq(&'\n');
+            }
+            '\n' | '\u{2028}' | '\u{2029}' => {}
+            c => out.push(c),
+        }
+    }
+    out
+}
+
+/// Pushes the character of a `\x` or `\u` escape sequence with the given number of hexadecimal digits,
+/// combining a `\u` escaped surrogate pair into a single character.
+fn push_hex_escape(out: &mut String, chars: &mut Peekable<Chars>, digits: usize) {
+    let mut code = 0;
+    for _ in 0..digits {
+        match chars.peek().and_then(|c| c.to_digit(16)) {
+            Some(digit) => {
+                code = code * 16 + digit;
+                chars.next();
+            }
+            None => break,
+        }
+    }
+
+    if (0xD800..0xDC00).contains(&code) {
+        let mut lookahead = chars.clone();
+        if lookahead.next() == Some('\\') && lookahead.next() == Some('u') {
+            let low: String = lookahead.by_ref().take(4).collect();
+            if let Ok(low @ 0xDC00..0xE000) = u32::from_str_radix(&low, 16) {
+                code = 0x10000 + ((code - 0xD800) << 10) + (low - 0xDC00);
+                *chars = lookahead;
+            }
+        }
+    }
+    out.push(char::from_u32(code).unwrap_or(char::REPLACEMENT_CHARACTER));
+}
+
+/// Returns a JSON5 number in JSON notation, e.g. `0x1F` as `31`, `+1` as `1`, `.5` as `0.5` or `5.` as `5.0`,
+/// or `None` if it has none, e.g. `Infinity` or `NaN`.
+fn json_number(s: &str) -> Option<String> {
+    let (sign, unsigned) = match s.strip_prefix('-') {
+        Some(unsigned) => ("-", unsigned),
+        None => ("", s.strip_prefix('+').unwrap_or(s)),
+    };
+    if unsigned.starts_with(['+', '-']) {
+        return None;
+    }
+    let number = match unsigned
+        .strip_prefix("0x")
+        .or_else(|| unsigned.strip_prefix("0X"))
+    {
+        Some(hex) => format!("{sign}{}", hex_to_decimal(hex)?),
+        None => {
+            let mantissa = unsigned.split(['e', 'E']).next().unwrap_or_default();
+            if !mantissa.bytes().any(|b| b.is_ascii_digit()) {
+                return None;
+            }
+            let (int, rest) =
+                unsigned.split_at(unsigned.find(['.', 'e', 'E']).unwrap_or(unsigned.len()));
+            let int = if int.is_empty() { "0" } else { int };
+            match rest.strip_prefix('.') {
+                Some(frac) if !frac.starts_with(|c: char| c.is_ascii_digit()) => {
+                    format!("{sign}{int}.0{frac}")
+                }
+                _ => format!("{sign}{int}{rest}"),
+            }
+        }
+    };
+    is_json_number(&number).then_some(number)
+}
+
+/// Converts hexadecimal digits of any length to decimal digits, without losing precision.
+fn hex_to_decimal(hex: &str) -> Option<String> {
+    const BASE: u64 = 1_000_000_000;
+
+    if hex.is_empty() {
+        return None;
+    }
+    // Little-endian chunks of 9 decimal digits.
+    let mut chunks: Vec<u64> = vec![0];
+    for c in hex.chars() {
+        let mut carry = u64::from(c.to_digit(16)?);
+        for chunk in &mut chunks {
+            let value = *chunk * 16 + carry;
+            *chunk = value % BASE;
+            carry = value / BASE;
+        }
+        if carry > 0 {
+            chunks.push(carry);
+        }
+    }
+
+    let mut chunks = chunks.iter().rev();
+    let mut decimal = chunks.next().map(u64::to_string).unwrap_or_default();
+    for chunk in chunks {
+        decimal.push_str(&format!("{chunk:09}"));
+    }
+    Some(decimal)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_values_with_comments() {
+        let value = Json5Value::parse(
+            "/* head */ {\n  'single': 'a\\nb', // trailing\n  \"double\": [null, true, +.5, Infinity],\n}",
+        )
+        .unwrap();
+
+        let number = |n: &str| Json5Value {
+            kind: Json5ValueKind::Number(Json5Number::new(n)),
+            ..Json5Value::null()
+        };
+        assert_eq!(value.leading_comments, ["/* head */"]);
+        assert_eq!(
+            value.kind,
+            Json5ValueKind::Object(vec![
+                (
+                    "single".to_string(),
+                    Json5Value {
+                        kind: Json5ValueKind::String("a\nb".to_string()),
+                        leading_comments: vec![],
+                        trailing_comment: Some("// trailing".to_string()),
+                    }
+                ),
+                (
+                    "double".to_string(),
+                    Json5Value {
+                        kind: Json5ValueKind::Array(vec![
+                            Json5Value::null(),
+                            Json5Value {
+                                kind: Json5ValueKind::Bool(true),
+                                ..Json5Value::null()
+                            },
+                            number("+.5"),
+                            number("Infinity"),
+                        ]),
+                        ..Json5Value::null()
+                    }
+                ),
+            ])
+        );
+    }
+
+    #[test]
+    fn serialize_json5_numbers_as_json() {
+        let value = Json5Value::parse("[0x1F, -0XfF, +1, .5, 5., 1.e3, Infinity, NaN]").unwrap();
+        assert_eq!(
+            value.to_json_string(false),
+            "[31,-255,1,0.5,5.0,1.0e3,null,null]"
+        );
+    }
+
+    #[test]
+    fn serialize_json5_strings_as_json() {
+        let value = Json5Value::parse(
+            "{'k\\'ey': ['a\\nb', 'it\\'s', \"say \\\"hi\\\"\", '\\x41\\u00e9\\uD83D\\uDE00', 'line \\\ncontinued']}",
+        )
+        .unwrap();
+        assert_eq!(
+            value.to_json_string(false),
+            r#"{"k'ey":["a\nb","it's","say \"hi\"","Aé😀","line continued"]}"#
+        );
+    }
+
+    #[test]
+    fn json_number_converts_json5_numbers() {
+        for (number, json) in [
+            ("12", "12"),
+            ("0x1F", "31"),
+            ("-0XfF", "-255"),
+            ("+1", "1"),
+            (".5", "0.5"),
+            ("-5.", "-5.0"),
+            ("5.e3", "5.0e3"),
+            (
+                "0x100000000000000000000000000000000",
+                "340282366920938463463374607431768211456",
+            ),
+        ] {
+            assert_eq!(json_number(number).as_deref(), Some(json), "{number}");
+        }
+        for invalid in ["", "0x", "0xG", "Infinity", "NaN", "+-1", "."] {
+            assert_eq!(json_number(invalid), None, "{invalid}");
+        }
+    }
+
+    #[test]
+    fn parse_empty_document_as_null() {
+        assert_eq!(Json5Value::parse("// nothing").unwrap(), Json5Value::null());
+    }
+}
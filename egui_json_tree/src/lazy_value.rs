@@ -0,0 +1,416 @@
+//! A lazily parsed JSON value, for browsing JSON documents that are too large to parse into memory, e.g. multi-gigabyte dumps.
+//!
+//! [`LazyJsonFile::open`] reads a file, or [`LazyJsonFile::map`] memory-maps it, and [`LazyJsonFile::parse`] validates it once,
+//! without building a tree, returning a [`LazyValue`] that implements [`ToJsonTreeValue`]:
+//! - The entries of an array or object are indexed, i.e. the byte range of each entry is recorded, the first time it is converted via
+//!   [`ToJsonTreeValue::to_json_tree_value`], e.g. when a [`JsonTree`](crate::JsonTree) shows it.
+//!   The index is cached within the value, so the entries are borrowed from it like those of any other [`ToJsonTreeValue`],
+//!   and values that are never shown are never indexed.
+//! - Strings are unescaped when they are first shown, borrowing from the source if they contain no escape sequences.
+//!   Numbers are shown exactly as written.
+//! - [`ToJsonTreeValue::visit`] streams over the source text rather than indexing values, so searching the tree does not
+//!   index or cache anything. Enable [`JsonTree::cache_search_results`](crate::JsonTree::cache_search_results)
+//!   to avoid searching a large document on every frame.
+//! - The byte range of every value and object key within the source text is reported, as for [`RawValue`](crate::raw_value::RawValue).
+//!
+//! Unlike [`RawValue`](crate::raw_value::RawValue), duplicate keys within an object are not flagged,
+//! as that would require scanning every entry of an object before its first entry could be visited.
+//!
+//! Note that anything which converts every value, e.g. exporting the tree via [`JsonTreeResponse::to_text`](crate::JsonTreeResponse::to_text)
+//! or copying the root value, indexes the whole document.
+//!
+//! ```rust
+//! # use egui_json_tree::{lazy_value::LazyValue, value::ToJsonTreeValue};
+//! let value = LazyValue::parse(r#"{ "items": [1, 2, 3], "name": "dump" }"#).unwrap();
+//! assert!(value.is_expandable());
+//! assert_eq!(value.source(), r#"{ "items": [1, 2, 3], "name": "dump" }"#);
+//! ```
+
+use std::{borrow::Cow, cell::OnceCell, fs::File, io, ops::Range, path::Path};
+
+use memmap2::Mmap;
+
+use crate::{
+    JsonParseError,
+    pointer::JsonPointerSegment,
+    raw_value::Parser,
+    value::{BaseValueType, ExpandableType, JsonTreeValue, JsonTreeVisit, ToJsonTreeValue},
+};
+
+/// The text of a JSON file, from which a [`LazyValue`] can be parsed.
+#[derive(Debug)]
+pub struct LazyJsonFile {
+    bytes: LazyJsonFileBytes,
+}
+
+#[derive(Debug)]
+enum LazyJsonFileBytes {
+    Read(Vec<u8>),
+    Mapped(Mmap),
+}
+
+impl LazyJsonFile {
+    /// Reads the file at the given path into memory.
+    ///
+    /// Only the text of the file is held in memory, which is much smaller than a parsed tree,
+    /// and values are still parsed lazily. Use [`LazyJsonFile::map`] to avoid reading the file up front.
+    pub fn open(path: impl AsRef<Path>) -> io::Result<Self> {
+        Ok(Self {
+            bytes: LazyJsonFileBytes::Read(std::fs::read(path)?),
+        })
+    }
+
+    /// Memory-maps the file at the given path, so that only the parts of the file that are parsed or shown are read.
+    ///
+    /// # Safety
+    ///
+    /// The file must not be modified, e.g. written to or truncated by another process, while it is mapped,
+    /// as the mapped bytes are borrowed as a `&str`, which must not change while it is borrowed. See [`Mmap::map`].
+    /// Nothing within this crate can ensure that, as the file can be modified outside of this process,
+    /// e.g. only map files that are not written to once created.
+    pub unsafe fn map(path: impl AsRef<Path>) -> io::Result<Self> {
+        let file = File::open(path)?;
+        // SAFETY: The caller guarantees that the file is not modified while it is mapped.
+        let mmap = unsafe { Mmap::map(&file)? };
+        Ok(Self {
+            bytes: LazyJsonFileBytes::Mapped(mmap),
+        })
+    }
+
+    /// Validates the file as UTF-8 and as a JSON document, returning its lazily parsed root value.
+    pub fn parse(&self) -> Result<LazyValue<'_>, JsonParseError> {
+        let bytes = match &self.bytes {
+            LazyJsonFileBytes::Read(bytes) => bytes,
+            LazyJsonFileBytes::Mapped(mmap) => &mmap[..],
+        };
+        let src = std::str::from_utf8(bytes).map_err(|err| {
+            let offset = err.valid_up_to();
+            let valid = std::str::from_utf8(&bytes[..offset]).expect("validated UTF-8 prefix");
+            JsonParseError::new(valid, offset, "invalid UTF-8")
+        })?;
+        LazyValue::parse(src)
+    }
+}
+
+/// A JSON value within its source text, whose entries are indexed when first converted via [`ToJsonTreeValue::to_json_tree_value`].
+#[derive(Debug)]
+pub struct LazyValue<'a> {
+    src: &'a str,
+    span: Range<usize>,
+    display_value: OnceCell<Cow<'a, str>>,
+    entries: OnceCell<Vec<LazyEntry<'a>>>,
+}
+
+#[derive(Debug)]
+struct LazyEntry<'a> {
+    /// The key of an object entry and the byte range of the key, including its quotes.
+    key: Option<(Cow<'a, str>, Range<usize>)>,
+    value: LazyValue<'a>,
+}
+
+impl<'a> LazyValue<'a> {
+    /// Validates a JSON document, as defined by [RFC 8259](https://www.rfc-editor.org/rfc/rfc8259), without building a tree.
+    pub fn parse(src: &'a str) -> Result<Self, JsonParseError> {
+        let mut parser = Parser { src, pos: 0 };
+        parser.skip_whitespace();
+        let start = parser.pos;
+        parser.skip_value(0)?;
+        let end = parser.pos;
+        parser.skip_whitespace();
+        if parser.pos < src.len() {
+            return Err(parser.error("trailing characters"));
+        }
+        Ok(Self::new(src, start..end))
+    }
+
+    fn new(src: &'a str, span: Range<usize>) -> Self {
+        Self {
+            src,
+            span,
+            display_value: OnceCell::new(),
+            entries: OnceCell::new(),
+        }
+    }
+
+    /// Returns the text of this value exactly as written in the source.
+    pub fn source(&self) -> &'a str {
+        &self.src[self.span.clone()]
+    }
+
+    fn first_byte(&self) -> u8 {
+        self.src.as_bytes()[self.span.start]
+    }
+
+    fn entries(&self) -> &[LazyEntry<'a>] {
+        self.entries.get_or_init(|| {
+            let mut parser = Parser {
+                src: self.src,
+                pos: self.span.start,
+            };
+            let mut entries = vec![];
+            parser
+                .parse_entries(0, |parser, key| {
+                    parser.skip_whitespace();
+                    let start = parser.pos;
+                    parser.skip_value(0)?;
+                    entries.push(LazyEntry {
+                        key,
+                        value: LazyValue::new(parser.src, start..parser.pos),
+                    });
+                    Ok(())
+                })
+                .expect("validated by `LazyValue::parse`");
+            entries
+        })
+    }
+}
+
+impl ToJsonTreeValue for LazyValue<'_> {
+    fn to_json_tree_value(&self) -> JsonTreeValue<'_, Self> {
+        match self.first_byte() {
+            b'[' => JsonTreeValue::Expandable(
+                self.entries()
+                    .iter()
+                    .enumerate()
+                    .map(|(idx, entry)| (JsonPointerSegment::Index(idx), &entry.value))
+                    .collect(),
+                ExpandableType::Array,
+            ),
+            b'{' => JsonTreeValue::Expandable(
+                self.entries()
+                    .iter()
+                    .map(|entry| {
+                        let (key, _) = entry.key.as_ref().expect("objects have keys");
+                        (JsonPointerSegment::Key(key), &entry.value)
+                    })
+                    .collect(),
+                ExpandableType::Object,
+            ),
+            first_byte => {
+                let display_value = self.display_value.get_or_init(|| {
+                    if first_byte == b'"' {
+                        unescape(self.src, self.span.start)
+                    } else {
+                        Cow::Borrowed(self.source())
+                    }
+                });
+                JsonTreeValue::Base(self, display_value, base_value_type(first_byte))
+            }
+        }
+    }
+
+    fn is_expandable(&self) -> bool {
+        matches!(self.first_byte(), b'[' | b'{')
+    }
+
+    fn span(&self) -> Option<Range<usize>> {
+        Some(self.span.clone())
+    }
+
+    fn key_span(&self, idx: usize) -> Option<Range<usize>> {
+        if self.first_byte() != b'{' {
+            return None;
+        }
+        self.entries()
+            .get(idx)
+            .and_then(|entry| entry.key.as_ref())
+            .map(|(_, key_span)| key_span.clone())
+    }
+
+    fn visit(&self, visitor: &mut dyn FnMut(&[JsonPointerSegment<'_>], JsonTreeVisit<'_>)) {
+        let mut parser = Parser {
+            src: self.src,
+            pos: self.span.start,
+        };
+        visit_impl(&mut parser, &mut vec![], &mut vec![], visitor)
+            .expect("validated by `LazyValue::parse`");
+    }
+}
+
+/// The type of a non-recursive value, given the first byte of its source text.
+fn base_value_type(first_byte: u8) -> BaseValueType {
+    match first_byte {
+        b'n' => BaseValueType::Null,
+        b't' | b'f' => BaseValueType::Bool,
+        b'"' => BaseValueType::String,
+        _ => BaseValueType::Number,
+    }
+}
+
+fn unescape(src: &str, pos: usize) -> Cow<'_, str> {
+    Parser { src, pos }
+        .parse_string()
+        .expect("validated by `LazyValue::parse`")
+}
+
+/// A segment of the path to a value visited by [`visit_impl`], which owns unescaped keys.
+enum PathSegment<'a> {
+    Index(usize),
+    Key(Cow<'a, str>),
+}
+
+/// Visits the value at the position of the parser, and then its entries.
+///
+/// The path segments passed to the visitor borrow the keys within `path`, so they are collected into a new vector for each value,
+/// which reuses the allocation of `scratch`.
+fn visit_impl<'a>(
+    parser: &mut Parser<'a>,
+    path: &mut Vec<PathSegment<'a>>,
+    scratch: &mut Vec<JsonPointerSegment<'static>>,
+    visitor: &mut dyn FnMut(&[JsonPointerSegment<'_>], JsonTreeVisit<'_>),
+) -> Result<(), JsonParseError> {
+    parser.skip_whitespace();
+    let start = parser.pos;
+    match parser.peek() {
+        Some(open @ (b'[' | b'{')) => {
+            let expandable_type = if open == b'[' {
+                ExpandableType::Array
+            } else {
+                ExpandableType::Object
+            };
+            visit_path(
+                path,
+                scratch,
+                visitor,
+                JsonTreeVisit::Expandable(expandable_type),
+            );
+
+            let mut idx = 0;
+            parser.parse_entries(0, |parser, key| {
+                path.push(match key {
+                    Some((key, _)) => PathSegment::Key(key),
+                    None => PathSegment::Index(idx),
+                });
+                idx += 1;
+                visit_impl(parser, path, scratch, visitor)?;
+                path.pop();
+                Ok(())
+            })
+        }
+        Some(first_byte) => {
+            let display_value = if first_byte == b'"' {
+                parser.parse_string()?
+            } else {
+                parser.skip_value(0)?;
+                Cow::Borrowed(&parser.src[start..parser.pos])
+            };
+            visit_path(
+                path,
+                scratch,
+                visitor,
+                JsonTreeVisit::Base(&display_value, base_value_type(first_byte)),
+            );
+            Ok(())
+        }
+        None => Err(parser.error("unexpected end of input")),
+    }
+}
+
+fn visit_path(
+    path: &[PathSegment],
+    scratch: &mut Vec<JsonPointerSegment<'static>>,
+    visitor: &mut dyn FnMut(&[JsonPointerSegment<'_>], JsonTreeVisit<'_>),
+    visit: JsonTreeVisit<'_>,
+) {
+    let mut path_segments = recycle(std::mem::take(scratch));
+    path_segments.extend(path.iter().map(|segment| match segment {
+        PathSegment::Index(idx) => JsonPointerSegment::Index(*idx),
+        PathSegment::Key(key) => JsonPointerSegment::Key(key),
+    }));
+    visitor(&path_segments, visit);
+    *scratch = recycle(path_segments);
+}
+
+/// Clears a vector of path segments, so that its allocation can be reused for path segments that borrow from elsewhere.
+fn recycle<'b>(mut path_segments: Vec<JsonPointerSegment<'_>>) -> Vec<JsonPointerSegment<'b>> {
+    path_segments.clear();
+    // Collecting from the vector's own iterator reuses its allocation, as the element types have the same layout.
+    path_segments
+        .into_iter()
+        .map(|_| unreachable!("the vector is empty"))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{pointer::JsonPointer, raw_value::RawValue, serialize::JsonSerializer};
+
+    const SRC: &str = r#"{"a": [1, {"b\n": "x\"y"}, []], "c": null, "d": {"e": [true, -1.5e3]}}"#;
+
+    fn visited<T: ToJsonTreeValue + ?Sized>(value: &T) -> Vec<String> {
+        let mut visited = vec![];
+        value.visit(&mut |path_segments, visit| {
+            let pointer = JsonPointer(path_segments).to_json_pointer_string();
+            visited.push(match visit {
+                JsonTreeVisit::Base(display_value, value_type) => {
+                    format!("{pointer} {value_type:?} {display_value}")
+                }
+                JsonTreeVisit::Expandable(expandable_type) => {
+                    format!("{pointer} {expandable_type:?}")
+                }
+            });
+        });
+        visited
+    }
+
+    #[test]
+    fn lazy_value_indexes_entries_when_first_converted() {
+        let value = LazyValue::parse(SRC).unwrap();
+        assert!(value.entries.get().is_none());
+
+        let JsonTreeValue::Expandable(entries, ExpandableType::Object) = value.to_json_tree_value()
+        else {
+            panic!("expected an object");
+        };
+        let properties: Vec<_> = entries.iter().map(|(property, _)| *property).collect();
+        assert_eq!(
+            properties,
+            [
+                JsonPointerSegment::Key("a"),
+                JsonPointerSegment::Key("c"),
+                JsonPointerSegment::Key("d"),
+            ]
+        );
+        assert!(entries[0].1.entries.get().is_none());
+        assert_eq!(entries[0].1.source(), r#"[1, {"b\n": "x\"y"}, []]"#);
+        assert_eq!(&SRC[value.key_span(2).unwrap()], r#""d""#);
+
+        assert_eq!(
+            JsonSerializer::new().to_string(&value),
+            SRC.replace(' ', "")
+        );
+    }
+
+    #[test]
+    fn lazy_value_visits_like_a_parsed_tree_without_indexing() {
+        let value = LazyValue::parse(SRC).unwrap();
+        let raw_value = RawValue::parse(SRC).unwrap();
+
+        assert_eq!(visited(&value), visited(&raw_value));
+        assert!(value.entries.get().is_none());
+    }
+
+    #[test]
+    fn lazy_json_file_parses_a_read_or_mapped_file() {
+        let path =
+            std::env::temp_dir().join(format!("egui_json_tree_lazy_{}.json", std::process::id()));
+        std::fs::write(&path, SRC).unwrap();
+
+        let file = LazyJsonFile::open(&path).unwrap();
+        assert_eq!(file.parse().unwrap().source(), SRC);
+        // SAFETY: The file is not modified while it is mapped.
+        let file = unsafe { LazyJsonFile::map(&path) }.unwrap();
+        assert_eq!(file.parse().unwrap().source(), SRC);
+        drop(file);
+
+        std::fs::write(&path, b"[\"\xff\"]").unwrap();
+        let err = LazyJsonFile::open(&path).unwrap().parse().unwrap_err();
+        assert_eq!(
+            (err.message.as_ref(), err.offset, err.column),
+            ("invalid UTF-8", 2, 3)
+        );
+
+        std::fs::remove_file(path).unwrap();
+    }
+}
@@ -3,7 +3,7 @@
 //! See the demo [source code](https://github.com/dmackdev/egui_json_tree/blob/main/demo) and [webpage](https://dmackdev.github.io/egui_json_tree) for detailed use cases, including:
 //! - Automatic expansion of arrays/objects and highlighting, based on search term matches.
 //! - Copying JSON paths and values to the clipboard.
-//! - A JSON editor UI.
+//! - A JSON editor UI, via [`JsonTreeEditor`].
 //!
 //! # Usage
 //! ```rust
@@ -71,30 +71,66 @@
 //! [`JsonTree`] can visualise any type that implements [`ToJsonTreeValue`](trait@value::ToJsonTreeValue).
 //! See the table of crate features below for provided implementations.
 //!
-//! | Feature/Dependency | JSON Type                 | Default |
-//! | ------------------ | ------------------------- | ------- |
-//! | `serde_json`       | `serde_json::Value`       | Yes     |
-//! | `simd_json`        | `simd_json::owned::Value` | No      |
+//! | Feature/Dependency | JSON Type                                                      | Default |
+//! | ------------------ | -------------------------------------------------------------- | ------- |
+//! | `serde_json`       | `serde_json::Value`                                            | Yes     |
+//! | `simd_json`        | `simd_json::owned::Value`, `simd_json::borrowed::Value`        | No      |
+//! | `sonic_rs`         | `sonic_rs::Value`                                              | No      |
+//! | `yaml_rust2`       | `yaml_rust2::Yaml`                                             | No      |
+//! | `toml`             | `toml::Value`                                                  | No      |
+//! | `ciborium`         | `ciborium::Value`                                              | No      |
+//! | `rmpv`             | `rmpv::Value`                                                  | No      |
+//! | `json5`            | `json5format::Value`, via `json5_value::Json5Value`            | No      |
+//! | `serde`            | Any `serde::Serialize` type, via `serde_value::SerdeValue`     | No      |
+//! | `derive`           | Any type deriving `dyn_value::ToDynJsonTreeValue`              | No      |
+//! | `raw`              | `raw_value::RawValue`, preserving key order and duplicate keys | No      |
+//! | `lazy`             | `lazy_value::LazyValue`, lazily parsed from a file             | No      |
 //!
 //! If you wish to use a different JSON type, see the [`value`](mod@value) module,
 //! and disable default features in your `Cargo.toml` if you do not need the `serde_json` dependency.
+mod change_flash;
 mod default_expand;
+mod diff_tree;
+mod editor;
+mod export;
+mod lines_tree;
 mod node;
+mod parse_error;
 mod response;
 mod search;
 mod style;
 mod toggle_buttons_state;
 mod tree;
 
+pub mod context_menu;
 pub mod delimiters;
+pub mod diff;
+pub mod dyn_value;
+pub mod edit;
+pub mod history;
+#[cfg(feature = "json5")]
+pub mod json5_value;
+#[cfg(feature = "lazy")]
+pub mod lazy_value;
+pub mod patch;
 pub mod pointer;
+#[cfg(feature = "raw")]
+pub mod raw_value;
 pub mod render;
+#[cfg(feature = "serde")]
+pub mod serde_value;
+pub mod serialize;
 pub mod value;
 
 pub use default_expand::DefaultExpand;
+pub use diff_tree::{JsonDiffLayout, JsonDiffTree, JsonLayoutDiff};
+pub use editor::{JsonTreeEditor, JsonTreeEditorResponse};
+pub use lines_tree::JsonLinesTree;
+pub use parse_error::JsonParseError;
 pub use response::JsonTreeResponse;
 pub use style::{
-    JsonTreeMaxWidth, JsonTreeStyle, JsonTreeVisuals, JsonTreeWrapping, JsonTreeWrappingConfig,
+    BytesFormat, JsonTreeMaxWidth, JsonTreeStyle, JsonTreeVisuals, JsonTreeWrapping,
+    JsonTreeWrappingConfig, NumberFormat, NumberNotation, OtherValueVisuals,
 };
 pub use toggle_buttons_state::ToggleButtonsState;
 pub use tree::JsonTree;
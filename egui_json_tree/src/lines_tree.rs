@@ -0,0 +1,199 @@
+use crate::{
+    DefaultExpand, JsonTreeResponse, JsonTreeStyle,
+    context_menu::ContextMenuContext,
+    node::{JsonTreeNode, JsonTreeRoot},
+    pointer::JsonPointerSegment,
+    render::RenderContext,
+    tree::JsonTreeConfig,
+    value::ToJsonTreeValue,
+};
+use egui::{AsId, Id, Ui};
+
+/// An interactive visualiser for a sequence of JSON documents, e.g. parsed from [JSON Lines](https://jsonlines.org/) (NDJSON),
+/// with one document per line.
+///
+/// Each document is shown as a collapsible top-level row, labeled by its line number.
+/// Every path within the tree is prefixed by the index of its document, e.g. the JSON pointer `/3/level`
+/// refers to the `"level"` key of the fourth document, and searching the tree searches all documents.
+///
+/// When using [`DefaultExpand::ToLevel`], the documents themselves are at level 1, e.g. `DefaultExpand::ToLevel(0)` collapses all documents.
+///
+/// ```rust
+/// use egui_json_tree::{DefaultExpand, JsonLinesTree};
+/// use serde_json::Value;
+///
+/// # egui::__run_test_ui(|ui| {
+/// let ndjson = "{\"level\": \"info\"}\n\n{\"level\": \"error\", \"code\": 500}\n";
+/// let (line_numbers, documents): (Vec<usize>, Vec<Value>) = ndjson
+///     .lines()
+///     .enumerate()
+///     .filter(|(_, line)| !line.trim().is_empty())
+///     .map(|(idx, line)| (idx + 1, serde_json::from_str(line).unwrap()))
+///     .unzip();
+///
+/// JsonLinesTree::new("logs", &documents)
+///     .line_numbers(&line_numbers)
+///     .default_expand(DefaultExpand::SearchResults("error"))
+///     .show(ui);
+/// # });
+/// ```
+#[must_use = "You should call .show()"]
+pub struct JsonLinesTree<'a, T: ToJsonTreeValue> {
+    id: Id,
+    documents: &'a [T],
+    line_numbers: Option<&'a [usize]>,
+    config: JsonTreeConfig<'a, T>,
+}
+
+impl<'a, T: ToJsonTreeValue> JsonLinesTree<'a, T> {
+    /// Creates a new [`JsonLinesTree`].
+    /// `id` must be a globally unique identifier.
+    pub fn new(id: impl AsId, documents: &'a [T]) -> Self {
+        Self {
+            id: Id::new(id),
+            documents,
+            line_numbers: None,
+            config: JsonTreeConfig::default(),
+        }
+    }
+
+    /// Override the one-based line number shown for each document, e.g. if blank lines were skipped when parsing.
+    /// Defaults to the index of each document plus one.
+    pub fn line_numbers(mut self, line_numbers: &'a [usize]) -> Self {
+        self.line_numbers = Some(line_numbers);
+        self
+    }
+
+    /// Override colors for JSON syntax highlighting, and search match highlighting.
+    pub fn style(mut self, style: JsonTreeStyle) -> Self {
+        self.config.style = Some(style);
+        self
+    }
+
+    /// Override how the [`JsonLinesTree`] expands arrays/objects by default.
+    pub fn default_expand(mut self, default_expand: DefaultExpand<'a>) -> Self {
+        self.config.default_expand = Some(default_expand);
+        self
+    }
+
+    /// If enabled, automatically reset expanded arrays/objects to respect the [`DefaultExpand`] setting when it changes for this tree Id.
+    /// See [`JsonTree::auto_reset_expanded`](crate::JsonTree::auto_reset_expanded).
+    /// Defaults to enabled.
+    pub fn auto_reset_expanded(mut self, auto_reset_expanded: bool) -> Self {
+        self.config.auto_reset_expanded = auto_reset_expanded;
+        self
+    }
+
+    /// A convenience method for conditionally registering a custom rendering hook.
+    /// See [`JsonLinesTree::on_render`].
+    pub fn on_render_if(
+        self,
+        condition: bool,
+        render_hook: impl FnMut(&mut Ui, RenderContext<'a, '_, T>) + 'a,
+    ) -> Self {
+        if condition {
+            self.on_render(render_hook)
+        } else {
+            self
+        }
+    }
+
+    /// Customise rendering of the [`JsonLinesTree`], and/or handle interactions.
+    /// See [`JsonTree::on_render`](crate::JsonTree::on_render).
+    ///
+    /// The property of each top-level row is its line number, as a [`JsonPointerSegment::Index`],
+    /// whereas its pointer is prefixed by the index of the document.
+    pub fn on_render(
+        mut self,
+        render_hook: impl FnMut(&mut Ui, RenderContext<'a, '_, T>) + 'a,
+    ) -> Self {
+        self.config.renderer.render_hook = Some(Box::new(render_hook));
+        self
+    }
+
+    /// Append custom items to the built-in context menu, after the default copy actions.
+    /// See [`JsonTree::on_context_menu`](crate::JsonTree::on_context_menu).
+    pub fn on_context_menu(
+        mut self,
+        context_menu_hook: impl FnMut(&mut Ui, ContextMenuContext<'a, '_, T>) + 'a,
+    ) -> Self {
+        *self.config.context_menu.hook.get_mut() = Some(Box::new(context_menu_hook));
+        self
+    }
+
+    /// Show the documents within the `Ui`.
+    ///
+    /// Note that the export methods of the returned [`JsonTreeResponse`], e.g. [`JsonTreeResponse::to_text`],
+    /// only support the value of a [`JsonTree`](crate::JsonTree), not the documents of a [`JsonLinesTree`].
+    pub fn show(self, ui: &mut Ui) -> JsonTreeResponse {
+        let roots: Vec<_> = self
+            .documents
+            .iter()
+            .enumerate()
+            .map(|(idx, document)| {
+                let line_number = self
+                    .line_numbers
+                    .and_then(|line_numbers| line_numbers.get(idx).copied())
+                    .unwrap_or(idx + 1);
+                JsonTreeRoot {
+                    value: document,
+                    document: Some((
+                        JsonPointerSegment::Index(idx),
+                        JsonPointerSegment::Index(line_number),
+                    )),
+                }
+            })
+            .collect();
+        JsonTreeNode::show_roots(self.id, self.config, &[&roots], ui)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::LazyLock;
+
+    use egui::accesskit::Role;
+    use egui_kittest::{Harness, kittest::Queryable};
+    use serde_json::{Value, json};
+
+    use crate::{DefaultExpand, JsonLinesTree};
+
+    static DOCUMENTS: LazyLock<Vec<Value>> = LazyLock::new(|| {
+        vec![
+            json!({ "level": "info" }),
+            json!({ "level": "error", "code": "E_TIMEOUT" }),
+            json!("plain"),
+        ]
+    });
+
+    #[test]
+    fn render_documents_labeled_by_line_number() {
+        let harness = Harness::new_ui(|ui| {
+            JsonLinesTree::new("id", &DOCUMENTS)
+                .line_numbers(&[1, 3, 4])
+                .default_expand(DefaultExpand::None)
+                .show(ui);
+        });
+
+        // Toggle buttons are labeled by the JSON pointer of their value, prefixed by the document index.
+        assert_eq!(harness.query_all_by_role(Role::Button).count(), 2);
+        harness.get_by_role_and_label(Role::Button, "/0");
+        harness.get_by_role_and_label(Role::Button, "/1");
+        assert!(harness.query_by_label("1").is_some());
+        assert!(harness.query_by_label("3").is_some());
+        assert!(harness.query_by_label("4").is_some());
+        assert!(harness.query_by_label("\"plain\"").is_some());
+    }
+
+    #[test]
+    fn render_documents_expanding_search_results_across_documents() {
+        let harness = Harness::new_ui(|ui| {
+            JsonLinesTree::new("id", &DOCUMENTS)
+                .default_expand(DefaultExpand::SearchResults("timeout"))
+                .show(ui);
+        });
+
+        assert!(harness.query_by_label("\"code\"").is_some());
+        assert_eq!(harness.query_all_by_label("\"level\"").count(), 1);
+    }
+}
@@ -1,3 +1,5 @@
+use std::{collections::HashSet, ops::Range, sync::Arc};
+
 use egui::{
     Id, Ui,
     collapsing_header::{CollapsingState, paint_default_icon},
@@ -5,95 +7,238 @@ use egui::{
 
 use crate::{
     DefaultExpand, JsonTree, JsonTreeStyle, ToggleButtonsState,
+    change_flash::ChangeFlashes,
+    context_menu::JsonTreeContextMenu,
     default_expand::InnerDefaultExpand,
     delimiters::{ARRAY_DELIMITERS, OBJECT_DELIMITERS, SpacingDelimiter},
     pointer::{JsonPointer, JsonPointerSegment},
     render::{
         JsonTreeRenderer, ParentStatus, RenderBaseValueContext, RenderExpandableDelimiterContext,
-        RenderPropertyContext, RenderSpacingDelimiterContext,
+        RenderPropertyContext, RenderSpacingDelimiterContext, render_comment,
     },
     response::JsonTreeResponse,
     search::SearchTerm,
-    value::{ExpandableType, JsonTreeValue, ToJsonTreeValue},
+    tree::JsonTreeConfig,
+    value::{ExpandableType, JsonTreeComments, JsonTreeValue, ToJsonTreeValue},
 };
 
-pub(crate) struct JsonTreeNode<'a, 'b, T: ToJsonTreeValue> {
+pub(crate) struct JsonTreeNode<'a, 'b, T: ToJsonTreeValue + ?Sized> {
     /// The Id of the entire tree that this node is part of.
     tree_id: Id,
     value: &'a T,
     parent: Option<JsonPointerSegment<'a>>,
+    /// The byte range within the source text of the object key of `parent`, see [`ToJsonTreeValue::key_span`].
+    parent_key_span: Option<Range<usize>>,
     make_persistent_id: &'b dyn Fn(&[JsonPointerSegment]) -> Id,
-    config: &'b JsonTreeNodeConfig,
+    /// The index of the column that this node is shown in, if the tree is laid out in more than one column.
+    column: Option<usize>,
+    config: &'b JsonTreeNodeConfig<T>,
+    context_menu: &'b JsonTreeContextMenu<'a, T>,
 }
 
-impl<'a, 'b, T: ToJsonTreeValue> JsonTreeNode<'a, 'b, T> {
+impl<'a, 'b, T: ToJsonTreeValue + ?Sized> JsonTreeNode<'a, 'b, T> {
     pub(crate) fn show(tree: JsonTree<'a, T>, ui: &mut Ui) -> JsonTreeResponse {
+        let roots = [JsonTreeRoot {
+            value: tree.value,
+            document: None,
+        }];
+        Self::show_roots(tree.id, tree.config, &[&roots], ui)
+    }
+
+    /// Shows the given roots, laid out in columns if there is more than one column.
+    ///
+    /// The roots of every column share the Ids of their paths, so the same path is expanded or collapsed in every column.
+    /// The widgets of each column are salted with the column index so that their Ids don't clash.
+    pub(crate) fn show_roots(
+        tree_id: Id,
+        config: JsonTreeConfig<'a, T>,
+        columns: &[&[JsonTreeRoot<'a, T>]],
+        ui: &mut Ui,
+    ) -> JsonTreeResponse {
+        let roots = || columns.iter().flat_map(|roots| roots.iter());
         let persistent_id = ui.id();
-        let tree_id = tree.id;
         let make_persistent_id =
             |path_segments: &[JsonPointerSegment]| persistent_id.with(tree_id.with(path_segments));
 
-        let style = tree.config.style.unwrap_or_default();
-        let default_expand = tree.config.default_expand.unwrap_or_default();
-
-        let (inner_default_expand, search_term) = match default_expand {
-            DefaultExpand::All => (InnerDefaultExpand::All, None),
-            DefaultExpand::None => (InnerDefaultExpand::None, None),
-            DefaultExpand::ToLevel(l) => (InnerDefaultExpand::ToLevel(l), None),
-            DefaultExpand::SearchResults("") => (InnerDefaultExpand::None, None),
-            DefaultExpand::SearchResultsOrAll("") => (InnerDefaultExpand::All, None),
-            DefaultExpand::SearchResults(search_str)
-            | DefaultExpand::SearchResultsOrAll(search_str) => {
-                let search_term = SearchTerm::new(search_str);
-                let search_match_path_ids = search_term.find_matching_paths_in(
-                    tree.value,
-                    style.abbreviate_root,
-                    &make_persistent_id,
-                );
-                (
-                    InnerDefaultExpand::Paths(search_match_path_ids),
-                    Some(search_term),
-                )
-            }
-        };
-
-        let node = JsonTreeNode {
-            tree_id,
-            value: tree.value,
-            parent: None,
-            make_persistent_id: &make_persistent_id,
-            config: &JsonTreeNodeConfig {
-                inner_default_expand,
-                style,
-                search_term,
-            },
-        };
+        let style = config.style.unwrap_or_default();
+        let visuals = style.resolve_visuals(ui).clone();
+        let response_style = style.clone();
+        let background_color = ui.visuals().panel_fill;
+        let default_expand = config.default_expand.unwrap_or_default();
 
         let should_reset_expanded = ui.ctx().data_mut(|d| {
-            if tree.config.auto_reset_expanded {
+            if config.auto_reset_expanded {
                 let default_expand_hash_id = ResetExpandedHashId(egui::util::hash(default_expand));
                 let default_expand_changed =
-                    d.get_temp::<ResetExpandedHashId>(tree.id) != Some(default_expand_hash_id);
+                    d.get_temp::<ResetExpandedHashId>(tree_id) != Some(default_expand_hash_id);
                 if default_expand_changed {
-                    d.insert_temp(tree.id, default_expand_hash_id);
-                    d.insert_temp(tree.id, ShouldResetExpanded);
+                    d.insert_temp(tree_id, default_expand_hash_id);
+                    d.insert_temp(tree_id, ShouldResetExpanded);
                 }
             }
             d.remove_temp::<ShouldResetExpanded>(tree_id).is_some()
         });
 
-        let mut renderer = tree.config.renderer;
+        let (inner_default_expand, search_term) =
+            match (config.default_expand, config.default_expanded) {
+                (None, Some(is_expanded)) => (InnerDefaultExpand::Predicate(is_expanded), None),
+                _ => match default_expand {
+                    DefaultExpand::All => (InnerDefaultExpand::All, None),
+                    DefaultExpand::None => (InnerDefaultExpand::None, None),
+                    DefaultExpand::ToLevel(l) => (InnerDefaultExpand::ToLevel(l), None),
+                    DefaultExpand::SearchResults("") => (InnerDefaultExpand::None, None),
+                    DefaultExpand::SearchResultsOrAll("") => (InnerDefaultExpand::All, None),
+                    DefaultExpand::SearchResults(search_str)
+                    | DefaultExpand::SearchResultsOrAll(search_str) => {
+                        let search_term = SearchTerm::new(search_str);
+                        let search = || -> Arc<HashSet<Id>> {
+                            Arc::new(
+                                roots()
+                                    .flat_map(|root| {
+                                        search_term.find_matching_paths_in(
+                                            root.value,
+                                            root.document.map(|(segment, _)| segment),
+                                            &style,
+                                            &make_persistent_id,
+                                        )
+                                    })
+                                    .collect(),
+                            )
+                        };
+                        let search_match_path_ids = if config.cache_search_results {
+                            // Search the tree only when the search term, the values or the formatting of matched text change,
+                            // or when resetting expanded arrays/objects, rather than on every frame.
+                            let search_hash = egui::util::hash((
+                                search_str,
+                                roots()
+                                    .map(|root| std::ptr::from_ref(root.value).cast::<()>().addr())
+                                    .collect::<Vec<_>>(),
+                                style.bytes_format,
+                                style.number_format,
+                                &style.number_format_overrides,
+                            ));
+                            let search_matches_id = persistent_id.with(tree_id).with("search");
+                            let cached = ui.ctx().data(|d| {
+                                d.get_temp::<SearchMatches>(search_matches_id)
+                                    .filter(|cached| cached.hash == search_hash)
+                            });
+                            match cached {
+                                Some(cached) if !should_reset_expanded => cached.path_ids,
+                                _ => {
+                                    let path_ids = search();
+                                    ui.ctx().data_mut(|d| {
+                                        d.insert_temp(
+                                            search_matches_id,
+                                            SearchMatches {
+                                                hash: search_hash,
+                                                path_ids: path_ids.clone(),
+                                            },
+                                        );
+                                    });
+                                    path_ids
+                                }
+                            }
+                        } else {
+                            search()
+                        };
+                        (
+                            InnerDefaultExpand::Paths(search_match_path_ids),
+                            Some(search_term),
+                        )
+                    }
+                },
+            };
+
+        let change_flashes = match style.change_flash_duration {
+            Some(duration) => ChangeFlashes::detect(
+                ui,
+                tree_id,
+                roots(),
+                &make_persistent_id,
+                duration,
+                visuals.changed_color,
+            ),
+            None => ChangeFlashes::default(),
+        };
+
+        let context_menu = config.context_menu;
+        let node_config = JsonTreeNodeConfig {
+            inner_default_expand,
+            style,
+            search_term,
+            change_flashes,
+        };
+
+        let mut renderer = config.renderer;
+        let toggle_buttons_hidden =
+            node_config.style.toggle_buttons_state == ToggleButtonsState::Hidden;
+
+        let mut show_column = |ui: &mut Ui,
+                               roots: &[JsonTreeRoot<'a, T>],
+                               column: Option<usize>| {
+            for root in roots {
+                let node = JsonTreeNode {
+                    tree_id,
+                    value: root.value,
+                    parent: root.document.map(|(_, label)| label),
+                    parent_key_span: None,
+                    make_persistent_id: &make_persistent_id,
+                    column,
+                    config: &node_config,
+                    context_menu: &context_menu,
+                };
+                let mut path_segments: Vec<_> = root
+                    .document
+                    .map(|(segment, _)| segment)
+                    .into_iter()
+                    .collect();
+
+                if root.document.is_none() || root.value.is_expandable() || toggle_buttons_hidden {
+                    node.show_impl(ui, &mut path_segments, &mut renderer, should_reset_expanded);
+                } else {
+                    // Align non-expandable documents with the properties of expandable documents, after their toggle buttons.
+                    let path_id = make_persistent_id(&path_segments);
+                    ui.scope(|ui| {
+                        ui.visuals_mut().indent_has_left_vline = false;
+                        ui.spacing_mut().indent =
+                            ui.spacing().icon_width + ui.spacing().icon_spacing;
+                        ui.indent(path_id, |ui| {
+                            node.show_impl(
+                                ui,
+                                &mut path_segments,
+                                &mut renderer,
+                                should_reset_expanded,
+                            );
+                        });
+                    });
+                }
+            }
+        };
 
         // Wrap in a vertical layout in case this tree is placed directly in a horizontal layout,
         // which does not allow indent layouts as direct children.
         ui.vertical(|ui| {
             // Centres the collapsing header icon.
-            ui.spacing_mut().interact_size.y = node.config.style.resolve_font_id(ui).size;
+            ui.spacing_mut().interact_size.y = node_config.style.resolve_font_id(ui).size;
 
-            node.show_impl(ui, &mut vec![], &mut renderer, should_reset_expanded);
+            if let [roots] = columns {
+                show_column(ui, roots, None);
+            } else {
+                ui.columns(columns.len(), |column_uis| {
+                    for (column, (ui, roots)) in column_uis.iter_mut().zip(columns).enumerate() {
+                        show_column(ui, roots, Some(column));
+                    }
+                });
+            }
         });
 
-        JsonTreeResponse { tree_id }
+        JsonTreeResponse {
+            tree_id,
+            persistent_id,
+            style: response_style,
+            visuals,
+            background_color,
+        }
     }
 
     fn show_impl(
@@ -105,6 +250,9 @@ impl<'a, 'b, T: ToJsonTreeValue> JsonTreeNode<'a, 'b, T> {
     ) {
         match self.value.to_json_tree_value() {
             JsonTreeValue::Base(value, display_value, value_type) => {
+                let comments = self.value.comments();
+                show_leading_comments(ui, &self.config.style, &comments);
+
                 // Use horizontal instead of horizontal_wrapped so that the
                 // base value always starts inline with the property and not below it.
                 ui.horizontal(|ui| {
@@ -120,6 +268,9 @@ impl<'a, 'b, T: ToJsonTreeValue> JsonTreeNode<'a, 'b, T> {
                                 style: &self.config.style,
                                 search_term: self.config.search_term.as_ref(),
                                 collapsing_state: None,
+                                key_span: self.parent_key_span.clone(),
+                                background: None,
+                                context_menu: self.context_menu,
                             },
                         );
                         renderer.render_spacing_delimiter(
@@ -145,8 +296,15 @@ impl<'a, 'b, T: ToJsonTreeValue> JsonTreeNode<'a, 'b, T> {
                             } else {
                                 ParentStatus::NoParent
                             },
+                            background: self
+                                .config
+                                .change_flashes
+                                .color(self.make_persistent_id, path_segments),
+                            context_menu: self.context_menu,
                         },
                     );
+
+                    show_trailing_comment(ui, &self.config.style, &comments);
                 });
             }
             JsonTreeValue::Expandable(entries, expandable_type) => {
@@ -175,8 +333,12 @@ impl<'a, 'b, T: ToJsonTreeValue> JsonTreeNode<'a, 'b, T> {
             inner_default_expand,
             style,
             search_term,
+            change_flashes,
         } = self.config;
 
+        let comments = self.value.comments();
+        show_leading_comments(ui, style, &comments);
+
         let delimiters = match expandable_type {
             ExpandableType::Array => &ARRAY_DELIMITERS,
             ExpandableType::Object => &OBJECT_DELIMITERS,
@@ -193,9 +355,20 @@ impl<'a, 'b, T: ToJsonTreeValue> JsonTreeNode<'a, 'b, T> {
             InnerDefaultExpand::Paths(search_match_path_ids) => {
                 search_match_path_ids.contains(&path_id)
             }
+            InnerDefaultExpand::Predicate(is_expanded) => is_expanded(self.value),
         };
 
         let mut state = CollapsingState::load_with_default_open(ui.ctx(), path_id, default_open);
+        // Each column has its own state so that the Ids of their toggle buttons don't clash,
+        // which is kept in sync with the state at `path_id` that is shared by every column.
+        let mut shared_state = None;
+        if let Some(column) = self.column {
+            let is_open = state.is_open();
+            let mut column_state =
+                CollapsingState::load_with_default_open(ui.ctx(), path_id.with(column), is_open);
+            column_state.set_open(is_open);
+            shared_state = Some(std::mem::replace(&mut state, column_state));
+        }
         if should_reset_expanded {
             state.set_open(default_open);
         }
@@ -228,6 +401,9 @@ impl<'a, 'b, T: ToJsonTreeValue> JsonTreeNode<'a, 'b, T> {
                             pointer: JsonPointer(path_segments),
                             style,
                             collapsing_state: &mut state,
+                            background: change_flashes
+                                .color(self.make_persistent_id, path_segments),
+                            context_menu: self.context_menu,
                         },
                     );
                     return;
@@ -241,6 +417,8 @@ impl<'a, 'b, T: ToJsonTreeValue> JsonTreeNode<'a, 'b, T> {
                         pointer: JsonPointer(path_segments),
                         style,
                         collapsing_state: &mut state,
+                        background: None,
+                        context_menu: self.context_menu,
                     },
                 );
                 renderer.render_spacing_delimiter(
@@ -267,6 +445,9 @@ impl<'a, 'b, T: ToJsonTreeValue> JsonTreeNode<'a, 'b, T> {
                                 style,
                                 search_term: search_term.as_ref(),
                                 collapsing_state: Some(&mut state),
+                                key_span: self.value.key_span(idx),
+                                background: None,
+                                context_menu: self.context_menu,
                             },
                         );
                         renderer.render_spacing_delimiter(
@@ -290,6 +471,9 @@ impl<'a, 'b, T: ToJsonTreeValue> JsonTreeNode<'a, 'b, T> {
                                     style,
                                     search_term: search_term.as_ref(),
                                     parent_status: ParentStatus::CollapsedRoot,
+                                    background: change_flashes
+                                        .color(self.make_persistent_id, path_segments),
+                                    context_menu: self.context_menu,
                                 },
                             );
                         }
@@ -313,6 +497,9 @@ impl<'a, 'b, T: ToJsonTreeValue> JsonTreeNode<'a, 'b, T> {
                                     pointer: JsonPointer(path_segments),
                                     style,
                                     collapsing_state: &mut state,
+                                    background: change_flashes
+                                        .color(self.make_persistent_id, path_segments),
+                                    context_menu: self.context_menu,
                                 },
                             );
                         }
@@ -343,6 +530,8 @@ impl<'a, 'b, T: ToJsonTreeValue> JsonTreeNode<'a, 'b, T> {
                         pointer: JsonPointer(path_segments),
                         style,
                         collapsing_state: &mut state,
+                        background: None,
+                        context_menu: self.context_menu,
                     },
                 );
             } else {
@@ -356,6 +545,9 @@ impl<'a, 'b, T: ToJsonTreeValue> JsonTreeNode<'a, 'b, T> {
                             style,
                             search_term: self.config.search_term.as_ref(),
                             collapsing_state: Some(&mut state),
+                            key_span: self.parent_key_span.clone(),
+                            background: None,
+                            context_menu: self.context_menu,
                         },
                     );
                     renderer.render_spacing_delimiter(
@@ -376,6 +568,8 @@ impl<'a, 'b, T: ToJsonTreeValue> JsonTreeNode<'a, 'b, T> {
                             pointer: JsonPointer(path_segments),
                             style,
                             collapsing_state: &mut state,
+                            background: None,
+                            context_menu: self.context_menu,
                         },
                     );
                 } else {
@@ -392,10 +586,15 @@ impl<'a, 'b, T: ToJsonTreeValue> JsonTreeNode<'a, 'b, T> {
                             pointer: JsonPointer(path_segments),
                             style,
                             collapsing_state: &mut state,
+                            background: change_flashes
+                                .color(self.make_persistent_id, path_segments),
+                            context_menu: self.context_menu,
                         },
                     );
                 }
             }
+
+            show_trailing_comment(ui, style, &comments);
         });
 
         let toggle_buttons_hidden = style.toggle_buttons_state == ToggleButtonsState::Hidden;
@@ -405,8 +604,9 @@ impl<'a, 'b, T: ToJsonTreeValue> JsonTreeNode<'a, 'b, T> {
         }
 
         state.show_body_indented(&header_res.response, ui, |ui| {
-            for (property, elem) in entries {
+            for (idx, (property, elem)) in entries.into_iter().enumerate() {
                 let is_expandable = elem.is_expandable();
+                let key_span = self.value.key_span(idx);
 
                 path_segments.push(property);
 
@@ -415,8 +615,11 @@ impl<'a, 'b, T: ToJsonTreeValue> JsonTreeNode<'a, 'b, T> {
                         tree_id: self.tree_id,
                         value: elem,
                         parent: Some(property),
+                        parent_key_span: key_span.clone(),
                         make_persistent_id: self.make_persistent_id,
+                        column: self.column,
                         config: self.config,
+                        context_menu: self.context_menu,
                     };
 
                     nested_tree.show_impl(ui, path_segments, renderer, should_reset_expanded);
@@ -456,19 +659,45 @@ impl<'a, 'b, T: ToJsonTreeValue> JsonTreeNode<'a, 'b, T> {
                         pointer: JsonPointer(path_segments),
                         style,
                         collapsing_state: &mut state,
+                        background: None,
+                        context_menu: self.context_menu,
                     },
                 );
             });
         }
         // Ensure we store any change to the state if we reset expanded or if the render hook mutated it.
         state.store(ui.ctx());
+        if let Some(mut shared_state) = shared_state {
+            shared_state.set_open(state.is_open());
+            shared_state.store(ui.ctx());
+        }
     }
 }
 
-struct JsonTreeNodeConfig {
-    inner_default_expand: InnerDefaultExpand,
+fn show_leading_comments(ui: &mut Ui, style: &JsonTreeStyle, comments: &JsonTreeComments) {
+    for comment in &comments.leading {
+        render_comment(ui, style, comment);
+    }
+}
+
+fn show_trailing_comment(ui: &mut Ui, style: &JsonTreeStyle, comments: &JsonTreeComments) {
+    if let Some(comment) = &comments.trailing {
+        render_comment(ui, style, &format!(" {comment}"));
+    }
+}
+
+/// A top-level value of a tree, i.e. the value of a [`JsonTree`], or a document of a [`JsonLinesTree`](crate::JsonLinesTree).
+pub(crate) struct JsonTreeRoot<'a, T: ?Sized> {
+    pub(crate) value: &'a T,
+    /// For a document, the path segment that prefixes the paths of its values, and the property to show for it.
+    pub(crate) document: Option<(JsonPointerSegment<'a>, JsonPointerSegment<'a>)>,
+}
+
+struct JsonTreeNodeConfig<T: ?Sized> {
+    inner_default_expand: InnerDefaultExpand<T>,
     style: JsonTreeStyle,
     search_term: Option<SearchTerm>,
+    change_flashes: ChangeFlashes,
 }
 
 /// Stored in `egui`'s `IdTypeMap` to represent a hashed value to indicate whether to reset expanded arrays/objects when this changes for a particular tree Id.
@@ -476,6 +705,15 @@ struct JsonTreeNodeConfig {
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 struct ResetExpandedHashId(u64);
 
+/// Stored in `egui`'s `IdTypeMap` against the tree Id within the Id of its `Ui`, to cache the Ids of the paths to expand to show search matches,
+/// see [`JsonTree::cache_search_results`].
+#[derive(Clone)]
+struct SearchMatches {
+    /// The hash of the search term, the addresses of the values that were searched and the formatting of numbers and bytes.
+    hash: u64,
+    path_ids: Arc<HashSet<Id>>,
+}
+
 /// Stored in `egui`'s `IdTypeMap` to indicate that the tree should reset its expanded arrays/objects before rendering on a given frame.
 /// Avoids potential conflicts in case a `bool` happened to be stored against the same tree Id.
 #[derive(Clone, Copy, PartialEq, Eq, Default)]
@@ -0,0 +1,60 @@
+use std::{borrow::Cow, fmt};
+
+/// An error encountered when parsing JSON text, at a byte offset within the text.
+///
+/// This is returned by [`EditableJsonValue::parse_json`](crate::edit::EditableJsonValue::parse_json),
+/// and by the parsers of the `raw` and `lazy` features.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct JsonParseError {
+    /// A description of the error, excluding its position.
+    pub message: Cow<'static, str>,
+    /// The byte offset within the source text at which the error was encountered.
+    pub offset: usize,
+    /// The one-based line of the offset.
+    pub line: usize,
+    /// The one-based column of the offset, in characters.
+    pub column: usize,
+}
+
+impl JsonParseError {
+    /// Creates an error at the given byte offset within the source text, e.g. in a custom
+    /// [`EditableJsonValue::parse_json`](crate::edit::EditableJsonValue::parse_json), computing its line and column.
+    pub fn new(src: &str, offset: usize, message: impl Into<Cow<'static, str>>) -> Self {
+        let (line, column) = line_column(src, offset);
+        Self {
+            message: message.into(),
+            offset,
+            line,
+            column,
+        }
+    }
+}
+
+impl fmt::Display for JsonParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{} at line {} column {}",
+            self.message, self.line, self.column
+        )
+    }
+}
+
+impl std::error::Error for JsonParseError {}
+
+/// Returns the one-based line and column, in characters, of a byte offset within the source text,
+/// e.g. of the start of a [`ToJsonTreeValue::span`](crate::value::ToJsonTreeValue::span).
+///
+/// Offsets past the end of the source text are clamped to it.
+pub fn line_column(src: &str, offset: usize) -> (usize, usize) {
+    let mut offset = offset.min(src.len());
+    while !src.is_char_boundary(offset) {
+        offset -= 1;
+    }
+    let before = &src[..offset];
+    let line_start = before.rfind('\n').map_or(0, |idx| idx + 1);
+    (
+        before.matches('\n').count() + 1,
+        before[line_start..].chars().count() + 1,
+    )
+}
@@ -0,0 +1,694 @@
+//! Generation of [JSON Patch (RFC 6902)](https://datatracker.ietf.org/doc/html/rfc6902) and
+//! [JSON Merge Patch (RFC 7396)](https://datatracker.ietf.org/doc/html/rfc7396) documents that transform one value into another.
+//!
+//! Patches are generated from a [`JsonDiffValue`], so they work for any pair of [`ToJsonTreeValue`] values,
+//! and are written as JSON text by a [`JsonSerializer`].
+//!
+//! ```rust
+//! # use egui_json_tree::{diff::JsonDiffValue, patch::JsonPatchFormat, serialize::JsonSerializer};
+//! let old = serde_json::json!({ "replicas": 2, "image": "app:1.0", "debug": true });
+//! let new = serde_json::json!({ "replicas": 3, "image": "app:1.0" });
+//! let diff = JsonDiffValue::new(&old, &new);
+//!
+//! assert_eq!(
+//!     diff.to_patch_string(JsonPatchFormat::JsonPatch, &JsonSerializer::new()),
+//!     Ok(r#"[{"op":"remove","path":"/debug"},{"op":"replace","path":"/replicas","value":3}]"#.to_string())
+//! );
+//! assert_eq!(
+//!     diff.to_patch_string(JsonPatchFormat::MergePatch, &JsonSerializer::new()),
+//!     Ok(r#"{"debug":null,"replicas":3}"#.to_string())
+//! );
+//! ```
+
+use std::fmt::{self, Write};
+
+use crate::{
+    diff::{JsonDiffEntry, JsonDiffStatus, JsonDiffValue},
+    pointer::{JsonPointer, JsonPointerSegment},
+    serialize::{JsonSerializer, KeyOrder, write_escaped_str},
+    value::{BaseValueType, ExpandableType, JsonTreeValue, ToJsonTreeValue},
+};
+
+/// The kind of patch document to generate from a [`JsonDiffValue`].
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub enum JsonPatchFormat {
+    /// A [JSON Patch (RFC 6902)](https://datatracker.ietf.org/doc/html/rfc6902) array of operations.
+    #[default]
+    JsonPatch,
+    /// A [JSON Merge Patch (RFC 7396)](https://datatracker.ietf.org/doc/html/rfc7396) object.
+    MergePatch,
+}
+
+impl<T: ToJsonTreeValue + ?Sized> JsonDiffValue<'_, T> {
+    /// Writes a patch document of the given format that transforms the old value into the new value.
+    ///
+    /// Returns an error if the diff cannot be expressed as a merge patch, see [`JsonMergePatch::from_diff`].
+    pub fn to_patch_string(
+        &self,
+        format: JsonPatchFormat,
+        serializer: &JsonSerializer,
+    ) -> Result<String, JsonMergePatchError> {
+        match format {
+            JsonPatchFormat::JsonPatch => Ok(JsonPatch::from_diff(self).to_string(serializer)),
+            JsonPatchFormat::MergePatch => {
+                Ok(JsonMergePatch::from_diff(self)?.to_string(serializer))
+            }
+        }
+    }
+}
+
+/// A single operation of a [`JsonPatch`].
+#[derive(Debug, Clone, PartialEq)]
+pub enum JsonPatchOperation<'a, T: ?Sized> {
+    Add {
+        path: Vec<JsonPointerSegment<'a>>,
+        value: &'a T,
+    },
+    Remove {
+        path: Vec<JsonPointerSegment<'a>>,
+    },
+    Replace {
+        path: Vec<JsonPointerSegment<'a>>,
+        value: &'a T,
+    },
+    /// Moves an array element from one index to another, see [`JsonDiffValue::moved`].
+    Move {
+        from: Vec<JsonPointerSegment<'a>>,
+        path: Vec<JsonPointerSegment<'a>>,
+    },
+}
+
+impl<'a, T: ?Sized> JsonPatchOperation<'a, T> {
+    /// Returns the name of the operation, as written in the `"op"` member.
+    pub fn op(&self) -> &'static str {
+        match self {
+            JsonPatchOperation::Add { .. } => "add",
+            JsonPatchOperation::Remove { .. } => "remove",
+            JsonPatchOperation::Replace { .. } => "replace",
+            JsonPatchOperation::Move { .. } => "move",
+        }
+    }
+
+    /// Returns a [`JsonPointer`] to the target of the operation.
+    pub fn pointer(&self) -> JsonPointer<'a, '_> {
+        match self {
+            JsonPatchOperation::Add { path, .. }
+            | JsonPatchOperation::Remove { path }
+            | JsonPatchOperation::Replace { path, .. }
+            | JsonPatchOperation::Move { path, .. } => JsonPointer(path),
+        }
+    }
+
+    /// Returns a [`JsonPointer`] to the source of the operation, if it is a move.
+    pub fn from_pointer(&self) -> Option<JsonPointer<'a, '_>> {
+        match self {
+            JsonPatchOperation::Move { from, .. } => Some(JsonPointer(from)),
+            _ => None,
+        }
+    }
+
+    /// Returns the value of the operation, if it has one.
+    pub fn value(&self) -> Option<&'a T> {
+        match self {
+            JsonPatchOperation::Add { value, .. } | JsonPatchOperation::Replace { value, .. } => {
+                Some(*value)
+            }
+            JsonPatchOperation::Remove { .. } | JsonPatchOperation::Move { .. } => None,
+        }
+    }
+}
+
+/// A [JSON Patch (RFC 6902)](https://datatracker.ietf.org/doc/html/rfc6902) document, i.e. a sequence of operations
+/// that transforms one value into another when applied in order.
+#[derive(Debug, Clone, PartialEq)]
+pub struct JsonPatch<'a, T: ?Sized> {
+    pub operations: Vec<JsonPatchOperation<'a, T>>,
+}
+
+impl<'a, T: ToJsonTreeValue + ?Sized> JsonPatch<'a, T> {
+    /// Generates the operations that transform `old` into `new`.
+    pub fn new(old: &'a T, new: &'a T) -> Self {
+        Self::from_diff(&JsonDiffValue::new(old, new))
+    }
+
+    /// Generates the operations that transform the old value of the diff into its new value.
+    ///
+    /// Modified values are replaced as a whole. The removed elements of an array are removed from the end first,
+    /// and then each element is added, or moved to its index in the new array if it moved, from the start of the array,
+    /// so that the indices of each operation are valid when the operations are applied in order.
+    pub fn from_diff(diff: &JsonDiffValue<'a, T>) -> Self {
+        let mut operations = vec![];
+        collect_operations(diff, &mut vec![], &mut operations);
+        Self { operations }
+    }
+
+    /// Writes the patch as JSON text.
+    pub fn to_string(&self, serializer: &JsonSerializer) -> String {
+        let mut out = String::new();
+        // Writing to a `String` cannot fail.
+        let _ = self.write(serializer, &mut out);
+        out
+    }
+
+    /// Writes the patch as JSON text into the given writer.
+    pub fn write(&self, serializer: &JsonSerializer, writer: &mut impl Write) -> fmt::Result {
+        write_operations(
+            serializer,
+            writer,
+            self.operations
+                .iter()
+                .map(|operation| PatchOperationFields {
+                    op: operation.op(),
+                    from: operation
+                        .from_pointer()
+                        .map(|from| from.to_json_pointer_string()),
+                    path: operation.pointer().to_json_pointer_string(),
+                    value: operation.value(),
+                }),
+        )
+    }
+}
+
+/// The members of a JSON Patch operation, with its pointers as JSON pointer strings.
+pub(crate) struct PatchOperationFields<'v, T: ?Sized> {
+    pub(crate) op: &'static str,
+    pub(crate) from: Option<String>,
+    pub(crate) path: String,
+    pub(crate) value: Option<&'v T>,
+}
+
+/// Writes a JSON Patch array of the given operations.
+pub(crate) fn write_operations<'v, T: ToJsonTreeValue + ?Sized + 'v>(
+    serializer: &JsonSerializer,
+    writer: &mut impl Write,
+    operations: impl IntoIterator<Item = PatchOperationFields<'v, T>>,
+) -> fmt::Result {
+    let mut operations = operations.into_iter().peekable();
+    if operations.peek().is_none() {
+        return writer.write_str("[]");
+    }
+    writer.write_char('[')?;
+    for (idx, operation) in operations.enumerate() {
+        if idx > 0 {
+            writer.write_char(',')?;
+        }
+        serializer.write_newline(writer, 1)?;
+        write_operation(serializer, writer, &operation, 1)?;
+    }
+    serializer.write_newline(writer, 0)?;
+    writer.write_char(']')
+}
+
+/// Writes a single JSON Patch operation object, whose members are indented one level deeper than `depth`.
+pub(crate) fn write_operation<T: ToJsonTreeValue + ?Sized>(
+    serializer: &JsonSerializer,
+    writer: &mut impl Write,
+    operation: &PatchOperationFields<'_, T>,
+    depth: usize,
+) -> fmt::Result {
+    writer.write_char('{')?;
+    serializer.write_newline(writer, depth + 1)?;
+    serializer.write_key(writer, "op")?;
+    write_escaped_str(writer, operation.op)?;
+    writer.write_char(',')?;
+    if let Some(from) = &operation.from {
+        serializer.write_newline(writer, depth + 1)?;
+        serializer.write_key(writer, "from")?;
+        write_escaped_str(writer, from)?;
+        writer.write_char(',')?;
+    }
+    serializer.write_newline(writer, depth + 1)?;
+    serializer.write_key(writer, "path")?;
+    write_escaped_str(writer, &operation.path)?;
+    if let Some(value) = operation.value {
+        writer.write_char(',')?;
+        serializer.write_newline(writer, depth + 1)?;
+        serializer.write_key(writer, "value")?;
+        serializer.write_value(value, writer, depth + 1)?;
+    }
+    serializer.write_newline(writer, depth)?;
+    writer.write_char('}')
+}
+
+fn collect_operations<'a, T: ToJsonTreeValue + ?Sized>(
+    diff: &JsonDiffValue<'a, T>,
+    path: &mut Vec<JsonPointerSegment<'a>>,
+    operations: &mut Vec<JsonPatchOperation<'a, T>>,
+) {
+    match (diff.status, diff.old, diff.new) {
+        (JsonDiffStatus::Unchanged, _, _) => {}
+        (JsonDiffStatus::Added, _, Some(new)) => operations.push(JsonPatchOperation::Add {
+            path: path.clone(),
+            value: new,
+        }),
+        (JsonDiffStatus::Removed, _, _) => {
+            operations.push(JsonPatchOperation::Remove { path: path.clone() })
+        }
+        (JsonDiffStatus::ContainsChanges, _, _) => match diff.entries() {
+            Some((entries, ExpandableType::Array)) => {
+                collect_array_operations(entries, path, operations)
+            }
+            Some((entries, ExpandableType::Object)) => {
+                for entry in entries {
+                    path.push(entry.property);
+                    collect_operations(&entry.value, path, operations);
+                    path.pop();
+                }
+            }
+            None => {}
+        },
+        (_, _, Some(new)) => operations.push(JsonPatchOperation::Replace {
+            path: path.clone(),
+            value: new,
+        }),
+        (_, _, None) => {}
+    }
+}
+
+fn collect_array_operations<'a, T: ToJsonTreeValue + ?Sized>(
+    entries: &[JsonDiffEntry<'a, T>],
+    path: &mut Vec<JsonPointerSegment<'a>>,
+    operations: &mut Vec<JsonPatchOperation<'a, T>>,
+) {
+    let element_path = |path: &[JsonPointerSegment<'a>], idx| {
+        let mut path = path.to_vec();
+        path.push(JsonPointerSegment::Index(idx));
+        path
+    };
+
+    // The old index of the element at each index of the array as the operations are applied, or `None` for added elements.
+    let mut current: Vec<Option<usize>> = entries
+        .iter()
+        .filter(|entry| entry.value.old_index.is_some())
+        .map(|entry| entry.value.old_index)
+        .collect();
+    current.sort_unstable();
+
+    let mut removed: Vec<_> = entries
+        .iter()
+        .filter(|entry| entry.value.new_index.is_none())
+        .filter_map(|entry| entry.value.old_index)
+        .collect();
+    removed.sort_unstable_by(|a, b| b.cmp(a));
+    for old_idx in removed {
+        if let Some(idx) = current.iter().position(|elem| *elem == Some(old_idx)) {
+            current.remove(idx);
+            operations.push(JsonPatchOperation::Remove {
+                path: element_path(path, idx),
+            });
+        }
+    }
+
+    let mut elements: Vec<_> = entries
+        .iter()
+        .filter_map(|entry| Some((entry.value.new_index?, &entry.value)))
+        .collect();
+    elements.sort_unstable_by_key(|(new_idx, _)| *new_idx);
+    for (new_idx, elem) in elements {
+        match elem.old_index {
+            Some(old_idx) => {
+                let idx = current
+                    .iter()
+                    .position(|elem| *elem == Some(old_idx))
+                    .unwrap_or(new_idx);
+                if idx != new_idx {
+                    current.remove(idx);
+                    current.insert(new_idx, Some(old_idx));
+                    operations.push(JsonPatchOperation::Move {
+                        from: element_path(path, idx),
+                        path: element_path(path, new_idx),
+                    });
+                }
+            }
+            None => current.insert(new_idx, None),
+        }
+        path.push(JsonPointerSegment::Index(new_idx));
+        collect_operations(elem, path, operations);
+        path.pop();
+    }
+}
+
+/// A [JSON Merge Patch (RFC 7396)](https://datatracker.ietf.org/doc/html/rfc7396) document, i.e. an object that mirrors
+/// the structure of the target value, in which `null` removes a member.
+///
+/// Merge patches replace arrays as a whole, and cannot set a member to `null`,
+/// since applying the patch would remove the member instead, see [`JsonMergePatchError`].
+#[derive(Debug, Clone, PartialEq)]
+pub enum JsonMergePatch<'a, T: ?Sized> {
+    /// Replace the target with the given value.
+    Value(&'a T),
+    /// Remove the member from its object, written as `null`.
+    Remove,
+    /// Merge the members into the target object.
+    Object(Vec<(JsonPointerSegment<'a>, JsonMergePatch<'a, T>)>),
+}
+
+impl<'a, T: ToJsonTreeValue + ?Sized> JsonMergePatch<'a, T> {
+    /// Generates the merge patch that transforms `old` into `new`.
+    pub fn new(old: &'a T, new: &'a T) -> Result<Self, JsonMergePatchError> {
+        Self::from_diff(&JsonDiffValue::new(old, new))
+    }
+
+    /// Generates the merge patch that transforms the old value of the diff into its new value.
+    /// An unchanged value results in an empty object, which leaves the target unchanged.
+    ///
+    /// Returns an error if the new value sets an object member to `null`, either by adding or changing the member,
+    /// or within an added or replaced object, since applying the patch would remove the member instead.
+    pub fn from_diff(diff: &JsonDiffValue<'a, T>) -> Result<Self, JsonMergePatchError> {
+        Self::from_diff_at(diff, &mut vec![])
+    }
+
+    fn from_diff_at(
+        diff: &JsonDiffValue<'a, T>,
+        path: &mut Vec<JsonPointerSegment<'a>>,
+    ) -> Result<Self, JsonMergePatchError> {
+        match (diff.status, diff.entries(), diff.new) {
+            (JsonDiffStatus::Unchanged, _, _) => Ok(JsonMergePatch::Object(vec![])),
+            (JsonDiffStatus::Removed, _, _) | (_, _, None) => Ok(JsonMergePatch::Remove),
+            (JsonDiffStatus::ContainsChanges, Some((entries, ExpandableType::Object)), _) => {
+                let mut members = vec![];
+                for entry in entries.iter().filter(|entry| entry.value.is_changed()) {
+                    path.push(entry.property);
+                    if entry.value.new.is_some_and(is_null) {
+                        return Err(JsonMergePatchError::NullMember(
+                            JsonPointer(path).to_json_pointer_string(),
+                        ));
+                    }
+                    members.push((entry.property, Self::from_diff_at(&entry.value, path)?));
+                    path.pop();
+                }
+                Ok(JsonMergePatch::Object(members))
+            }
+            (_, _, Some(new)) => {
+                check_null_members(new, path)?;
+                Ok(JsonMergePatch::Value(new))
+            }
+        }
+    }
+
+    /// Writes the merge patch as JSON text.
+    pub fn to_string(&self, serializer: &JsonSerializer) -> String {
+        let mut out = String::new();
+        // Writing to a `String` cannot fail.
+        let _ = self.write(serializer, &mut out);
+        out
+    }
+
+    /// Writes the merge patch as JSON text into the given writer.
+    pub fn write(&self, serializer: &JsonSerializer, writer: &mut impl Write) -> fmt::Result {
+        self.write_at(serializer, writer, 0)
+    }
+
+    fn write_at(
+        &self,
+        serializer: &JsonSerializer,
+        writer: &mut impl Write,
+        depth: usize,
+    ) -> fmt::Result {
+        match self {
+            JsonMergePatch::Value(value) => serializer.write_value(*value, writer, depth),
+            JsonMergePatch::Remove => writer.write_str("null"),
+            JsonMergePatch::Object(members) if members.is_empty() => writer.write_str("{}"),
+            JsonMergePatch::Object(members) => {
+                let mut members: Vec<_> = members.iter().collect();
+                if serializer.key_order == KeyOrder::Sorted {
+                    members.sort_by_key(|(property, _)| property.to_string());
+                }
+                writer.write_char('{')?;
+                for (idx, (property, patch)) in members.into_iter().enumerate() {
+                    if idx > 0 {
+                        writer.write_char(',')?;
+                    }
+                    serializer.write_newline(writer, depth + 1)?;
+                    serializer.write_key(writer, &property.to_string())?;
+                    patch.write_at(serializer, writer, depth + 1)?;
+                }
+                serializer.write_newline(writer, depth)?;
+                writer.write_char('}')
+            }
+        }
+    }
+}
+
+/// An error returned when a diff cannot be expressed as a [`JsonMergePatch`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum JsonMergePatchError {
+    /// The new value has a `null` object member at the pointer, which applying a merge patch would remove instead.
+    NullMember(String),
+}
+
+impl fmt::Display for JsonMergePatchError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            JsonMergePatchError::NullMember(pointer) => {
+                write!(f, "a merge patch cannot set \"{pointer}\" to null")
+            }
+        }
+    }
+}
+
+impl std::error::Error for JsonMergePatchError {}
+
+fn is_null<T: ToJsonTreeValue + ?Sized>(value: &T) -> bool {
+    matches!(
+        value.to_json_tree_value(),
+        JsonTreeValue::Base(_, _, BaseValueType::Null)
+    )
+}
+
+/// Returns an error for the first `null` member of the value, or of its nested objects.
+/// Arrays are replaced as a whole, so their elements may be `null`.
+fn check_null_members<'a, T: ToJsonTreeValue + ?Sized>(
+    value: &'a T,
+    path: &mut Vec<JsonPointerSegment<'a>>,
+) -> Result<(), JsonMergePatchError> {
+    let JsonTreeValue::Expandable(entries, ExpandableType::Object) = value.to_json_tree_value()
+    else {
+        return Ok(());
+    };
+    for (property, elem) in entries {
+        path.push(property);
+        if is_null(elem) {
+            return Err(JsonMergePatchError::NullMember(
+                JsonPointer(path).to_json_pointer_string(),
+            ));
+        }
+        check_null_members(elem, path)?;
+        path.pop();
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::{Value, json};
+
+    use super::*;
+    use crate::diff::{ArrayAlignment, JsonDiffOptions};
+
+    /// Applies a JSON Patch with `json_patch`-like semantics, to check that the generated patch produces the new value.
+    fn apply_patch(value: &mut Value, patch: &Value) {
+        for operation in patch.as_array().unwrap() {
+            let path = operation["path"].as_str().unwrap();
+            if path.is_empty() {
+                *value = operation["value"].clone();
+                continue;
+            }
+            if operation["op"] == "move" {
+                let from = operation["from"].as_str().unwrap();
+                let (from_parent, from_last) = from.rsplit_once('/').unwrap();
+                let Value::Array(array) = value.pointer_mut(from_parent).unwrap() else {
+                    panic!("moves are only generated within arrays");
+                };
+                let elem = array.remove(from_last.parse().unwrap());
+                let to: usize = path.rsplit_once('/').unwrap().1.parse().unwrap();
+                array.insert(to, elem);
+                continue;
+            }
+            let (parent, last) = path.rsplit_once('/').unwrap();
+            let last = last.replace("~1", "/").replace("~0", "~");
+            let parent = value.pointer_mut(parent).unwrap();
+            match (operation["op"].as_str().unwrap(), parent) {
+                ("add", Value::Array(array)) => {
+                    array.insert(last.parse().unwrap(), operation["value"].clone())
+                }
+                ("add" | "replace", Value::Object(object)) => {
+                    object.insert(last, operation["value"].clone());
+                }
+                ("replace", Value::Array(array)) => {
+                    array[last.parse::<usize>().unwrap()] = operation["value"].clone()
+                }
+                ("remove", Value::Array(array)) => {
+                    array.remove(last.parse().unwrap());
+                }
+                ("remove", Value::Object(object)) => {
+                    object.remove(&last);
+                }
+                (op, _) => panic!("unexpected operation {op}"),
+            }
+        }
+    }
+
+    fn apply_merge_patch(target: &mut Value, patch: &Value) {
+        match patch {
+            Value::Object(members) => {
+                if !target.is_object() {
+                    *target = json!({});
+                }
+                let object = target.as_object_mut().unwrap();
+                for (key, member) in members {
+                    if member.is_null() {
+                        object.remove(key);
+                    } else {
+                        apply_merge_patch(object.entry(key).or_insert(Value::Null), member);
+                    }
+                }
+            }
+            _ => *target = patch.clone(),
+        }
+    }
+
+    fn cases() -> Vec<(Value, Value)> {
+        vec![
+            (json!({ "a": 1 }), json!({ "a": 1 })),
+            (
+                json!({ "a": 1, "b": [1, 2, 3], "c": { "d": "x", "e/~": true } }),
+                json!({ "a": 2, "b": [1, 4], "c": { "d": "x", "f": [null] } }),
+            ),
+            (json!([1, { "a": 1 }]), json!([0, { "a": 2 }, 3, 4])),
+            (json!({ "a": { "b": 1 } }), json!({ "a": [1] })),
+            (json!("x"), json!({ "a": 1 })),
+            (json!([1, 2, 3, 4, 5]), json!([5, 1, 3, 2, 6])),
+            (
+                json!([{ "id": 1, "v": 1 }, { "id": 2 }, { "id": 3 }, { "v": 4 }]),
+                json!([{ "id": 3 }, { "id": 0 }, { "v": 5 }, { "id": 1, "v": 2 }]),
+            ),
+        ]
+    }
+
+    #[test]
+    fn json_patch_with_aligned_arrays_transforms_old_into_new() {
+        for alignment in [
+            ArrayAlignment::Index,
+            ArrayAlignment::Lcs,
+            ArrayAlignment::Key("id"),
+        ] {
+            let options = JsonDiffOptions::new().array_alignment(alignment);
+            for (old, new) in cases() {
+                let diff = JsonDiffValue::with_options(&old, &new, &options);
+                let patch = diff
+                    .to_patch_string(JsonPatchFormat::JsonPatch, &JsonSerializer::new())
+                    .unwrap();
+                let mut patched = old.clone();
+                apply_patch(&mut patched, &serde_json::from_str(&patch).unwrap());
+                assert_eq!(patched, new, "{alignment:?} patch {patch}");
+            }
+        }
+    }
+
+    #[test]
+    fn json_patch_moves_reordered_elements() {
+        let old = json!([{ "id": 1 }, { "id": 2 }, { "id": 3 }]);
+        let new = json!([{ "id": 3 }, { "id": 1 }, { "id": 2 }]);
+        let options = JsonDiffOptions::new().array_alignment(ArrayAlignment::Key("id"));
+        let diff = JsonDiffValue::with_options(&old, &new, &options);
+        assert_eq!(
+            diff.to_patch_string(JsonPatchFormat::JsonPatch, &JsonSerializer::new()),
+            Ok(r#"[{"op":"move","from":"/2","path":"/0"}]"#.to_string())
+        );
+    }
+
+    #[test]
+    fn json_patch_transforms_old_into_new() {
+        for (old, new) in cases() {
+            let patch = JsonPatch::new(&old, &new).to_string(&JsonSerializer::new());
+            let mut patched = old.clone();
+            apply_patch(&mut patched, &serde_json::from_str(&patch).unwrap());
+            assert_eq!(patched, new, "patch {patch}");
+        }
+    }
+
+    #[test]
+    fn merge_patch_transforms_old_into_new() {
+        for (old, new) in cases() {
+            let patch = JsonMergePatch::new(&old, &new)
+                .unwrap()
+                .to_string(&JsonSerializer::new());
+            let mut patched = old.clone();
+            apply_merge_patch(&mut patched, &serde_json::from_str(&patch).unwrap());
+            assert_eq!(patched, new, "merge patch {patch}");
+        }
+    }
+
+    #[test]
+    fn json_patch_operations_in_order() {
+        let old = json!({ "a": [1, 2, 3], "b": true });
+        let new = json!({ "a": [0], "c": null });
+        let operations: Vec<_> = JsonPatch::new(&old, &new)
+            .operations
+            .iter()
+            .map(|operation| {
+                (
+                    operation.op(),
+                    operation.pointer().to_json_pointer_string(),
+                    operation.value().cloned(),
+                )
+            })
+            .collect();
+        assert_eq!(
+            operations,
+            [
+                ("remove", "/a/2".to_string(), None),
+                ("remove", "/a/1".to_string(), None),
+                ("replace", "/a/0".to_string(), Some(json!(0))),
+                ("remove", "/b".to_string(), None),
+                ("add", "/c".to_string(), Some(Value::Null)),
+            ]
+        );
+    }
+
+    #[test]
+    fn patches_are_pretty_printed() {
+        let old = json!({ "a": { "b": 1 } });
+        let new = json!({ "a": { "b": [2] } });
+        let diff = JsonDiffValue::new(&old, &new);
+        assert_eq!(
+            diff.to_patch_string(JsonPatchFormat::JsonPatch, &JsonSerializer::pretty()),
+            Ok("[\n  {\n    \"op\": \"replace\",\n    \"path\": \"/a/b\",\n    \"value\": [\n      2\n    ]\n  }\n]".to_string())
+        );
+        assert_eq!(
+            diff.to_patch_string(JsonPatchFormat::MergePatch, &JsonSerializer::pretty()),
+            Ok("{\n  \"a\": {\n    \"b\": [\n      2\n    ]\n  }\n}".to_string())
+        );
+    }
+
+    #[test]
+    fn merge_patch_cannot_set_members_to_null() {
+        for (old, new, pointer) in [
+            (json!({ "a": 1 }), json!({ "a": 1, "b": null }), "/b"),
+            (json!({ "a": 1 }), json!({ "a": null }), "/a"),
+            (
+                json!({ "a": [1] }),
+                json!({ "a": { "b": { "c": null } } }),
+                "/a/b/c",
+            ),
+            (json!([1]), json!({ "a": null }), "/a"),
+        ] {
+            assert_eq!(
+                JsonMergePatch::new(&old, &new),
+                Err(JsonMergePatchError::NullMember(pointer.to_string())),
+                "{old} -> {new}"
+            );
+        }
+        assert_eq!(
+            JsonDiffValue::new(&json!({ "a": 1 }), &json!({ "a": 1, "b": null }))
+                .to_patch_string(JsonPatchFormat::MergePatch, &JsonSerializer::new()),
+            Err(JsonMergePatchError::NullMember("/b".to_string()))
+        );
+        assert_eq!(
+            JsonDiffValue::new(&json!({ "a": 1 }), &json!({ "a": 1, "b": null }))
+                .to_patch_string(JsonPatchFormat::JsonPatch, &JsonSerializer::new()),
+            Ok(r#"[{"op":"add","path":"/b","value":null}]"#.to_string())
+        );
+    }
+}
@@ -1,6 +1,12 @@
 //! A JSON Pointer implementation for identifying specific values within a JSON document.
 
-use std::fmt;
+use std::{
+    any::{Any, TypeId},
+    fmt,
+    hash::{Hash, Hasher},
+};
+
+use crate::value::{JsonTreeValue, ToJsonTreeValue};
 
 /// A JSON Pointer implementation.
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
@@ -21,6 +27,47 @@ impl<'a> JsonPointer<'a, '_> {
             .collect()
     }
 
+    /// Returns a JSONPath expression for the value identified by this pointer, e.g. `$.foo[0]['a b']`.
+    ///
+    /// Object keys that are valid identifiers use dot notation, and all other keys use bracket notation with single quotes.
+    pub fn to_json_path_string(&self) -> String {
+        self.accessor_string("$", '\'', false)
+    }
+
+    /// Returns a JavaScript property accessor chain for the value identified by this pointer, e.g. `.foo[0]["a b"]`.
+    ///
+    /// The accessor is relative to the whole document, so it should be appended to the name of the variable holding it.
+    /// The whole document is identified by the empty string `""`.
+    pub fn to_javascript_accessor_string(&self) -> String {
+        self.accessor_string("", '"', true)
+    }
+
+    fn accessor_string(&self, root: &str, quote: char, allow_dollar: bool) -> String {
+        let mut accessor = String::from(root);
+        for segment in self.0 {
+            match segment {
+                JsonPointerSegment::Key(key) | JsonPointerSegment::DuplicateKey(key, _)
+                    if is_identifier(key, allow_dollar) =>
+                {
+                    accessor.push('.');
+                    accessor.push_str(key);
+                }
+                JsonPointerSegment::Key(key) | JsonPointerSegment::DuplicateKey(key, _) => {
+                    accessor.push('[');
+                    push_quoted(&mut accessor, key, quote);
+                    accessor.push(']');
+                }
+                JsonPointerSegment::Index(idx) => accessor.push_str(&format!("[{idx}]")),
+                JsonPointerSegment::NonStringKey(key) => {
+                    accessor.push('[');
+                    push_quoted(&mut accessor, &key.to_string(), quote);
+                    accessor.push(']');
+                }
+            }
+        }
+        accessor
+    }
+
     /// Returns the last [JsonPointerSegment] of this pointer, if it exists.
     ///
     /// This is useful for retrieving the array index or object key that points to a JSON value.
@@ -37,10 +84,55 @@ impl<'a> JsonPointer<'a, '_> {
 }
 
 /// An individual segment of a [JsonPointer] - either an array index or object key.
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[derive(Clone, Copy)]
+#[non_exhaustive]
 pub enum JsonPointerSegment<'a> {
     Index(usize),
     Key(&'a str),
+    /// An object key that is not a string, e.g. an integer or boolean key of a YAML mapping.
+    ///
+    /// It is rendered without quotes, and is compared and hashed as a value of its own type, see [`MapKey`].
+    /// In JSON pointer strings, it is represented by its displayed text, just like a string key.
+    NonStringKey(&'a dyn MapKey),
+    /// An object key that appears more than once within the same object, e.g. in a raw JSON document,
+    /// along with the zero-based index of this occurrence among the entries with that key.
+    ///
+    /// It is rendered in [`JsonTreeVisuals::warning_color`](crate::JsonTreeVisuals::warning_color).
+    /// The occurrence is compared and hashed, so that each duplicate entry is expanded and collapsed independently,
+    /// but it is not part of JSON pointer strings, so a pointer to a duplicate key is ambiguous.
+    DuplicateKey(&'a str, usize),
+}
+
+/// The key of a [`JsonPointerSegment::NonStringKey`].
+///
+/// This is implemented for every `'static` type that implements [`Display`](fmt::Display), [`PartialEq`] and [`Hash`].
+/// Keys are compared and hashed as values of their type, so keys of different types are not equal,
+/// even if they are displayed the same way, e.g. the integer `1` and the float `1`.
+pub trait MapKey: fmt::Display {
+    /// Returns the key as [`Any`], so that it can be compared with a key of the same type.
+    fn as_any(&self) -> &dyn Any;
+    /// Returns whether this key is equal to the other key, which is only the case if it has the same type.
+    fn eq_key(&self, other: &dyn MapKey) -> bool;
+    /// Hashes the type and value of this key.
+    fn hash_key(&self, state: &mut dyn Hasher);
+}
+
+impl<K: fmt::Display + PartialEq + Hash + Any> MapKey for K {
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn eq_key(&self, other: &dyn MapKey) -> bool {
+        other
+            .as_any()
+            .downcast_ref::<K>()
+            .is_some_and(|other| self == other)
+    }
+
+    fn hash_key(&self, mut state: &mut dyn Hasher) {
+        TypeId::of::<K>().hash(&mut state);
+        self.hash(&mut state);
+    }
 }
 
 impl fmt::Display for JsonPointerSegment<'_> {
@@ -48,6 +140,60 @@ impl fmt::Display for JsonPointerSegment<'_> {
         match self {
             JsonPointerSegment::Key(key) => write!(f, "{key}"),
             JsonPointerSegment::Index(idx) => write!(f, "{idx}"),
+            JsonPointerSegment::NonStringKey(key) => write!(f, "{key}"),
+            JsonPointerSegment::DuplicateKey(key, _) => write!(f, "{key}"),
+        }
+    }
+}
+
+impl fmt::Debug for JsonPointerSegment<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            JsonPointerSegment::Index(idx) => f.debug_tuple("Index").field(idx).finish(),
+            JsonPointerSegment::Key(key) => f.debug_tuple("Key").field(key).finish(),
+            JsonPointerSegment::NonStringKey(key) => f
+                .debug_tuple("NonStringKey")
+                .field(&format_args!("{key}"))
+                .finish(),
+            JsonPointerSegment::DuplicateKey(key, occurrence) => f
+                .debug_tuple("DuplicateKey")
+                .field(key)
+                .field(occurrence)
+                .finish(),
+        }
+    }
+}
+
+impl PartialEq for JsonPointerSegment<'_> {
+    fn eq(&self, other: &Self) -> bool {
+        match (self, other) {
+            (JsonPointerSegment::Index(a), JsonPointerSegment::Index(b)) => a == b,
+            (JsonPointerSegment::Key(a), JsonPointerSegment::Key(b)) => a == b,
+            (JsonPointerSegment::NonStringKey(a), JsonPointerSegment::NonStringKey(b)) => {
+                a.eq_key(*b)
+            }
+            (
+                JsonPointerSegment::DuplicateKey(a, a_occurrence),
+                JsonPointerSegment::DuplicateKey(b, b_occurrence),
+            ) => a == b && a_occurrence == b_occurrence,
+            _ => false,
+        }
+    }
+}
+
+impl Eq for JsonPointerSegment<'_> {}
+
+impl Hash for JsonPointerSegment<'_> {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        std::mem::discriminant(self).hash(state);
+        match self {
+            JsonPointerSegment::Index(idx) => idx.hash(state),
+            JsonPointerSegment::Key(key) => key.hash(state),
+            JsonPointerSegment::NonStringKey(key) => key.hash_key(state),
+            JsonPointerSegment::DuplicateKey(key, occurrence) => {
+                key.hash(state);
+                occurrence.hash(state);
+            }
         }
     }
 }
@@ -55,18 +201,95 @@ impl fmt::Display for JsonPointerSegment<'_> {
 impl JsonPointerSegment<'_> {
     pub fn to_json_pointer_segment_string(&self) -> String {
         match self {
-            JsonPointerSegment::Key(key) => {
-                format!("/{}", key.replace('~', "~0").replace('/', "~1"))
+            JsonPointerSegment::Key(key) | JsonPointerSegment::DuplicateKey(key, _) => {
+                escape_json_pointer_key(key)
             }
             JsonPointerSegment::Index(idx) => format!("/{idx}"),
+            JsonPointerSegment::NonStringKey(key) => escape_json_pointer_key(&key.to_string()),
         }
     }
 }
 
+pub(crate) fn escape_json_pointer_key(key: &str) -> String {
+    format!("/{}", key.replace('~', "~0").replace('/', "~1"))
+}
+
+/// Splits a JSON pointer string into the pointer string to its parent, and its last reference token with `~1` and `~0` unescaped.
+pub(crate) fn split_json_pointer(pointer: &str) -> Option<(&str, String)> {
+    let idx = pointer.rfind('/')?;
+    let token = pointer[idx + 1..].replace("~1", "/").replace("~0", "~");
+    Some((&pointer[..idx], token))
+}
+
+/// Looks up the value identified by a JSON pointer string, by comparing each reference token
+/// with the JSON pointer segments of the entries of each value along the way.
+pub(crate) fn resolve_json_pointer<'a, T: ToJsonTreeValue + ?Sized>(
+    value: &'a T,
+    pointer: &str,
+) -> Option<&'a T> {
+    if pointer.is_empty() {
+        return Some(value);
+    }
+    pointer
+        .strip_prefix('/')?
+        .split('/')
+        .try_fold(value, |value, token| match value.to_json_tree_value() {
+            JsonTreeValue::Expandable(entries, _) => entries
+                .into_iter()
+                .find(|(property, _)| property.to_json_pointer_segment_string()[1..] == *token)
+                .map(|(_, value)| value),
+            JsonTreeValue::Base(..) => None,
+        })
+}
+
+/// Whether an object key can be written with dot notation, e.g. `.foo`.
+fn is_identifier(key: &str, allow_dollar: bool) -> bool {
+    let is_extra = |c: char| c == '_' || (allow_dollar && c == '$');
+    let mut chars = key.chars();
+    chars
+        .next()
+        .is_some_and(|c| c.is_ascii_alphabetic() || is_extra(c))
+        && chars.all(|c| c.is_ascii_alphanumeric() || is_extra(c))
+}
+
+fn push_quoted(out: &mut String, key: &str, quote: char) {
+    out.push(quote);
+    for c in key.chars() {
+        match c {
+            '\\' => out.push_str("\\\\"),
+            '\n' => out.push_str("\\n"),
+            '\r' => out.push_str("\\r"),
+            '\t' => out.push_str("\\t"),
+            c if c == quote => {
+                out.push('\\');
+                out.push(c);
+            }
+            c if c.is_control() => out.push_str(&format!("\\u{:04x}", c as u32)),
+            c => out.push(c),
+        }
+    }
+    out.push(quote);
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    #[test]
+    fn non_string_keys_compare_typed_values() {
+        let hash = |segment: JsonPointerSegment| {
+            let mut hasher = std::hash::DefaultHasher::new();
+            segment.hash(&mut hasher);
+            hasher.finish()
+        };
+        let one = JsonPointerSegment::NonStringKey(&1);
+        assert_eq!(one, JsonPointerSegment::NonStringKey(&1));
+        assert_eq!(hash(one), hash(JsonPointerSegment::NonStringKey(&1)));
+        assert_ne!(one, JsonPointerSegment::NonStringKey(&2));
+        assert_ne!(one, JsonPointerSegment::NonStringKey(&1u8));
+        assert_ne!(hash(one), hash(JsonPointerSegment::NonStringKey(&"1")));
+    }
+
     #[test]
     fn pointer_empty_path_segments() {
         let path = [];
@@ -147,4 +370,103 @@ mod tests {
             "/ /0/  ".to_string()
         );
     }
+
+    #[test]
+    fn pointer_to_json_path_string() {
+        let path = [
+            JsonPointerSegment::Key("foo"),
+            JsonPointerSegment::Index(0),
+            JsonPointerSegment::Key("a b"),
+            JsonPointerSegment::Key("it's"),
+            JsonPointerSegment::Key("1st"),
+            JsonPointerSegment::Key("$bar"),
+        ];
+        let pointer = JsonPointer(&path);
+        assert_eq!(
+            pointer.to_json_path_string(),
+            r"$.foo[0]['a b']['it\'s']['1st']['$bar']".to_string()
+        );
+        assert_eq!(JsonPointer(&[]).to_json_path_string(), "$".to_string());
+    }
+
+    #[test]
+    fn pointer_to_javascript_accessor_string() {
+        let path = [
+            JsonPointerSegment::Key("foo"),
+            JsonPointerSegment::Index(0),
+            JsonPointerSegment::Key("$bar_1"),
+            JsonPointerSegment::Key("a-b"),
+            JsonPointerSegment::Key("say \"hi\"\n"),
+        ];
+        let pointer = JsonPointer(&path);
+        assert_eq!(
+            pointer.to_javascript_accessor_string(),
+            r#".foo[0].$bar_1["a-b"]["say \"hi\"\n"]"#.to_string()
+        );
+        assert_eq!(
+            JsonPointer(&[]).to_javascript_accessor_string(),
+            "".to_string()
+        );
+    }
+
+    #[test]
+    fn pointer_non_string_keys() {
+        let path = [
+            JsonPointerSegment::NonStringKey(&1),
+            JsonPointerSegment::NonStringKey(&true),
+            JsonPointerSegment::NonStringKey(&"a/b"),
+        ];
+        let pointer = JsonPointer(&path);
+        assert_eq!(pointer.to_json_pointer_string(), "/1/true/a~1b".to_string());
+        assert_eq!(
+            pointer.to_json_path_string(),
+            "$['1']['true']['a/b']".to_string()
+        );
+        assert_ne!(
+            JsonPointerSegment::NonStringKey(&1),
+            JsonPointerSegment::NonStringKey(&"1")
+        );
+        assert_ne!(
+            JsonPointerSegment::NonStringKey(&"a"),
+            JsonPointerSegment::Key("a")
+        );
+    }
+
+    #[test]
+    fn pointer_duplicate_keys() {
+        let path = [
+            JsonPointerSegment::DuplicateKey("a b", 1),
+            JsonPointerSegment::DuplicateKey("foo", 0),
+        ];
+        let pointer = JsonPointer(&path);
+        assert_eq!(pointer.to_json_pointer_string(), "/a b/foo".to_string());
+        assert_eq!(pointer.to_json_path_string(), "$['a b'].foo".to_string());
+        assert_ne!(
+            JsonPointerSegment::DuplicateKey("foo", 0),
+            JsonPointerSegment::DuplicateKey("foo", 1)
+        );
+        assert_ne!(
+            JsonPointerSegment::DuplicateKey("foo", 0),
+            JsonPointerSegment::Key("foo")
+        );
+    }
+
+    #[test]
+    fn split_and_resolve_pointer_strings() {
+        let value = serde_json::json!({ "a/b": [1, { "m~n": true }] });
+        assert_eq!(split_json_pointer(""), None);
+        assert_eq!(split_json_pointer("/a~1b"), Some(("", "a/b".to_string())));
+        assert_eq!(
+            split_json_pointer("/a~1b/1/m~0n"),
+            Some(("/a~1b/1", "m~n".to_string()))
+        );
+
+        assert_eq!(resolve_json_pointer(&value, ""), Some(&value));
+        assert_eq!(
+            resolve_json_pointer(&value, "/a~1b/1/m~0n"),
+            Some(&serde_json::Value::Bool(true))
+        );
+        assert_eq!(resolve_json_pointer(&value, "/a~1b/2"), None);
+        assert_eq!(resolve_json_pointer(&value, "a~1b"), None);
+    }
 }
@@ -0,0 +1,526 @@
+//! A lossless JSON value type, for visualising a JSON document exactly as it is written.
+//!
+//! [`RawValue::parse`] parses a JSON document into a tree that borrows from the source text, and implements [`ToJsonTreeValue`]:
+//! - Object entries keep the order in which they appear in the source.
+//! - Duplicate keys within an object are all kept, rather than the last one overwriting the others.
+//!   Each of them is represented by [`JsonPointerSegment::DuplicateKey`], and is rendered in
+//!   [`JsonTreeVisuals::warning_color`](crate::JsonTreeVisuals::warning_color).
+//! - Numbers are kept exactly as written, e.g. `1.0`, `1e3` or integers too large for `u64`.
+//! - Strings are unescaped, borrowing from the source if they contain no escape sequences.
+//! - The byte range of every value and object key within the source text is kept, and reported by
+//!   [`ToJsonTreeValue::span`] and [`ToJsonTreeValue::key_span`], so that e.g. [`RenderContext::span`](crate::render::RenderContext::span)
+//!   can be used with [`line_column`] to jump to a value in a text editor.
+//!
+//! ```rust
+//! # use egui_json_tree::raw_value::{line_column, RawValue, RawValueKind};
+//! let src = r#"{ "b": 1.0, "a": 2, "b": 3e2 }"#;
+//! let value = RawValue::parse(src).unwrap();
+//!
+//! let RawValueKind::Object(entries) = &value.kind else { unreachable!() };
+//! let keys: Vec<_> = entries.iter().map(|entry| (entry.key.as_ref(), entry.duplicate)).collect();
+//! assert_eq!(keys, [("b", Some(0)), ("a", None), ("b", Some(1))]);
+//! assert_eq!(entries[2].value.kind, RawValueKind::Number("3e2"));
+//! assert_eq!(&src[entries[2].value.span.clone()], "3e2");
+//! assert_eq!(line_column(src, entries[2].key_span.start), (1, 21));
+//! ```
+
+use std::{borrow::Cow, collections::HashMap, ops::Range};
+
+pub use crate::parse_error::line_column;
+use crate::{
+    JsonParseError,
+    pointer::JsonPointerSegment,
+    value::{BaseValueType, ExpandableType, JsonTreeValue, ToJsonTreeValue},
+};
+
+/// A JSON value that borrows from its source text, preserving the order of object entries, duplicate keys and numbers as written.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RawValue<'a> {
+    pub kind: RawValueKind<'a>,
+    /// The byte range of the value within the source text.
+    pub span: Range<usize>,
+}
+
+/// The kind of a [`RawValue`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum RawValueKind<'a> {
+    Null,
+    Bool(bool),
+    /// A number, exactly as written in the source.
+    Number(&'a str),
+    String(Cow<'a, str>),
+    Array(Vec<RawValue<'a>>),
+    Object(Vec<RawEntry<'a>>),
+}
+
+/// An entry of a [`RawValueKind::Object`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RawEntry<'a> {
+    pub key: Cow<'a, str>,
+    /// The byte range of the key within the source text, including its quotes.
+    pub key_span: Range<usize>,
+    pub value: RawValue<'a>,
+    /// If the key appears more than once within the object, the zero-based index of this occurrence among the entries with that key.
+    pub duplicate: Option<usize>,
+}
+
+impl<'a> RawValue<'a> {
+    /// Parses a JSON document, as defined by [RFC 8259](https://www.rfc-editor.org/rfc/rfc8259).
+    pub fn parse(src: &'a str) -> Result<Self, JsonParseError> {
+        let mut parser = Parser { src, pos: 0 };
+        let value = parser.parse_value(0)?;
+        parser.skip_whitespace();
+        if parser.pos < src.len() {
+            return Err(parser.error("trailing characters"));
+        }
+        Ok(value)
+    }
+}
+
+impl ToJsonTreeValue for RawValue<'_> {
+    fn to_json_tree_value(&self) -> JsonTreeValue<'_, Self> {
+        match &self.kind {
+            RawValueKind::Null => JsonTreeValue::Base(self, &"null", BaseValueType::Null),
+            RawValueKind::Bool(b) => JsonTreeValue::Base(self, b, BaseValueType::Bool),
+            RawValueKind::Number(n) => JsonTreeValue::Base(self, n, BaseValueType::Number),
+            RawValueKind::String(s) => JsonTreeValue::Base(self, s, BaseValueType::String),
+            RawValueKind::Array(arr) => JsonTreeValue::Expandable(
+                arr.iter()
+                    .enumerate()
+                    .map(|(idx, elem)| (JsonPointerSegment::Index(idx), elem))
+                    .collect(),
+                ExpandableType::Array,
+            ),
+            RawValueKind::Object(obj) => JsonTreeValue::Expandable(
+                obj.iter()
+                    .map(|entry| {
+                        let property = match entry.duplicate {
+                            Some(occurrence) => {
+                                JsonPointerSegment::DuplicateKey(&entry.key, occurrence)
+                            }
+                            None => JsonPointerSegment::Key(&entry.key),
+                        };
+                        (property, &entry.value)
+                    })
+                    .collect(),
+                ExpandableType::Object,
+            ),
+        }
+    }
+
+    fn is_expandable(&self) -> bool {
+        matches!(self.kind, RawValueKind::Array(_) | RawValueKind::Object(_))
+    }
+
+    fn span(&self) -> Option<Range<usize>> {
+        Some(self.span.clone())
+    }
+
+    fn key_span(&self, idx: usize) -> Option<Range<usize>> {
+        match &self.kind {
+            RawValueKind::Object(obj) => obj.get(idx).map(|entry| entry.key_span.clone()),
+            _ => None,
+        }
+    }
+}
+
+/// The maximum nesting depth of arrays and objects, to avoid overflowing the stack on malicious input.
+const MAX_DEPTH: usize = 128;
+
+pub(crate) struct Parser<'a> {
+    pub(crate) src: &'a str,
+    pub(crate) pos: usize,
+}
+
+impl<'a> Parser<'a> {
+    pub(crate) fn error(&self, message: &'static str) -> JsonParseError {
+        JsonParseError::new(self.src, self.pos, message)
+    }
+
+    pub(crate) fn peek(&self) -> Option<u8> {
+        self.src.as_bytes().get(self.pos).copied()
+    }
+
+    pub(crate) fn skip_whitespace(&mut self) {
+        while matches!(self.peek(), Some(b' ' | b'\t' | b'\n' | b'\r')) {
+            self.pos += 1;
+        }
+    }
+
+    fn expect_literal(&mut self, literal: &str) -> Result<(), JsonParseError> {
+        if self.src[self.pos..].starts_with(literal) {
+            self.pos += literal.len();
+            Ok(())
+        } else {
+            Err(self.error("expected value"))
+        }
+    }
+
+    fn parse_value(&mut self, depth: usize) -> Result<RawValue<'a>, JsonParseError> {
+        self.skip_whitespace();
+        let start = self.pos;
+        let kind = match self.peek() {
+            Some(b'n') => self.expect_literal("null").map(|_| RawValueKind::Null),
+            Some(b't') => self
+                .expect_literal("true")
+                .map(|_| RawValueKind::Bool(true)),
+            Some(b'f') => self
+                .expect_literal("false")
+                .map(|_| RawValueKind::Bool(false)),
+            Some(b'"') => self.parse_string().map(RawValueKind::String),
+            Some(b'-' | b'0'..=b'9') => self.parse_number().map(RawValueKind::Number),
+            Some(b'[') => {
+                let mut elems = vec![];
+                self.parse_entries(depth + 1, |parser, _| {
+                    elems.push(parser.parse_value(depth + 1)?);
+                    Ok(())
+                })
+                .map(|_| RawValueKind::Array(elems))
+            }
+            Some(b'{') => {
+                let mut entries = vec![];
+                self.parse_entries(depth + 1, |parser, key| {
+                    let (key, key_span) = key.expect("objects have keys");
+                    let value = parser.parse_value(depth + 1)?;
+                    entries.push(RawEntry {
+                        key,
+                        key_span,
+                        value,
+                        duplicate: None,
+                    });
+                    Ok(())
+                })?;
+                mark_duplicates(&mut entries);
+                Ok(RawValueKind::Object(entries))
+            }
+            Some(_) => Err(self.error("expected value")),
+            None => Err(self.error("unexpected end of input")),
+        }?;
+        Ok(RawValue {
+            kind,
+            span: start..self.pos,
+        })
+    }
+
+    /// Parses the entries of the array or object at the current position, calling `parse_entry` to parse the value of each entry,
+    /// along with its key and the span of its key for objects.
+    pub(crate) fn parse_entries(
+        &mut self,
+        depth: usize,
+        mut parse_entry: impl FnMut(
+            &mut Self,
+            Option<(Cow<'a, str>, Range<usize>)>,
+        ) -> Result<(), JsonParseError>,
+    ) -> Result<(), JsonParseError> {
+        if depth > MAX_DEPTH {
+            return Err(self.error("recursion limit exceeded"));
+        }
+        let is_object = self.peek() == Some(b'{');
+        let (close, expected) = if is_object {
+            (b'}', "expected `,` or `}`")
+        } else {
+            (b']', "expected `,` or `]`")
+        };
+        self.pos += 1;
+
+        self.skip_whitespace();
+        if self.peek() == Some(close) {
+            self.pos += 1;
+            return Ok(());
+        }
+
+        loop {
+            let key = if is_object {
+                self.skip_whitespace();
+                if self.peek() != Some(b'"') {
+                    return Err(self.error("expected object key"));
+                }
+                let key_start = self.pos;
+                let key = self.parse_string()?;
+                let key_span = key_start..self.pos;
+                self.skip_whitespace();
+                if self.peek() != Some(b':') {
+                    return Err(self.error("expected `:`"));
+                }
+                self.pos += 1;
+                Some((key, key_span))
+            } else {
+                None
+            };
+            parse_entry(self, key)?;
+
+            self.skip_whitespace();
+            match self.peek() {
+                Some(b',') => self.pos += 1,
+                Some(c) if c == close => {
+                    self.pos += 1;
+                    return Ok(());
+                }
+                _ => return Err(self.error(expected)),
+            }
+        }
+    }
+
+    /// Parses the value at the current position without building it, e.g. to validate it or find its end.
+    #[cfg(feature = "lazy")]
+    pub(crate) fn skip_value(&mut self, depth: usize) -> Result<(), JsonParseError> {
+        self.skip_whitespace();
+        match self.peek() {
+            Some(b'[' | b'{') => {
+                self.parse_entries(depth + 1, |parser, _| parser.skip_value(depth + 1))
+            }
+            _ => self.parse_value(depth).map(drop),
+        }
+    }
+
+    fn parse_number(&mut self) -> Result<&'a str, JsonParseError> {
+        let start = self.pos;
+        let bytes = self.src.as_bytes();
+        let digits = |pos: &mut usize| {
+            let digits_start = *pos;
+            while bytes.get(*pos).is_some_and(u8::is_ascii_digit) {
+                *pos += 1;
+            }
+            *pos > digits_start
+        };
+
+        let mut pos = self.pos;
+        if bytes.get(pos) == Some(&b'-') {
+            pos += 1;
+        }
+        match bytes.get(pos) {
+            Some(b'0') => pos += 1,
+            Some(b'1'..=b'9') => {
+                digits(&mut pos);
+            }
+            _ => {
+                self.pos = pos;
+                return Err(self.error("invalid number"));
+            }
+        }
+        if bytes.get(pos) == Some(&b'.') {
+            pos += 1;
+            if !digits(&mut pos) {
+                self.pos = pos;
+                return Err(self.error("invalid number"));
+            }
+        }
+        if matches!(bytes.get(pos), Some(b'e' | b'E')) {
+            pos += 1;
+            if matches!(bytes.get(pos), Some(b'+' | b'-')) {
+                pos += 1;
+            }
+            if !digits(&mut pos) {
+                self.pos = pos;
+                return Err(self.error("invalid number"));
+            }
+        }
+
+        self.pos = pos;
+        Ok(&self.src[start..pos])
+    }
+
+    pub(crate) fn parse_string(&mut self) -> Result<Cow<'a, str>, JsonParseError> {
+        self.pos += 1;
+        let start = self.pos;
+        let mut unescaped: Option<String> = None;
+
+        loop {
+            let Some(c) = self.src[self.pos..].chars().next() else {
+                return Err(self.error("unterminated string"));
+            };
+            match c {
+                '"' => {
+                    let raw = &self.src[start..self.pos];
+                    self.pos += 1;
+                    return Ok(match unescaped {
+                        Some(s) => Cow::Owned(s),
+                        None => Cow::Borrowed(raw),
+                    });
+                }
+                '\\' => {
+                    let s = unescaped.get_or_insert_with(|| self.src[start..self.pos].to_string());
+                    self.pos += 1;
+                    let escaped = match self.peek() {
+                        Some(b'"') => '"',
+                        Some(b'\\') => '\\',
+                        Some(b'/') => '/',
+                        Some(b'b') => '\u{8}',
+                        Some(b'f') => '\u{c}',
+                        Some(b'n') => '\n',
+                        Some(b'r') => '\r',
+                        Some(b't') => '\t',
+                        Some(b'u') => {
+                            self.pos += 1;
+                            let c = self.parse_unicode_escape()?;
+                            s.push(c);
+                            continue;
+                        }
+                        _ => return Err(self.error("invalid escape sequence")),
+                    };
+                    s.push(escaped);
+                    self.pos += 1;
+                }
+                c if (c as u32) < 0x20 => {
+                    return Err(self.error("control character in string"));
+                }
+                c => {
+                    if let Some(s) = &mut unescaped {
+                        s.push(c);
+                    }
+                    self.pos += c.len_utf8();
+                }
+            }
+        }
+    }
+
+    /// Parses the hexadecimal digits of a `\u` escape, after the `\u`, including a following low surrogate escape if required.
+    fn parse_unicode_escape(&mut self) -> Result<char, JsonParseError> {
+        let high = self.parse_hex4()?;
+        let code_point = match high {
+            0xD800..=0xDBFF => {
+                if !self.src[self.pos..].starts_with("\\u") {
+                    return Err(self.error("unpaired surrogate in unicode escape"));
+                }
+                self.pos += 2;
+                let low = self.parse_hex4()?;
+                if !(0xDC00..=0xDFFF).contains(&low) {
+                    return Err(self.error("unpaired surrogate in unicode escape"));
+                }
+                0x10000 + ((high - 0xD800) << 10) + (low - 0xDC00)
+            }
+            0xDC00..=0xDFFF => return Err(self.error("unpaired surrogate in unicode escape")),
+            _ => high,
+        };
+        char::from_u32(code_point).ok_or_else(|| self.error("invalid unicode escape"))
+    }
+
+    fn parse_hex4(&mut self) -> Result<u32, JsonParseError> {
+        let hex = self
+            .src
+            .get(self.pos..self.pos + 4)
+            .filter(|hex| hex.bytes().all(|b| b.is_ascii_hexdigit()))
+            .ok_or_else(|| self.error("invalid unicode escape"))?;
+        self.pos += 4;
+        Ok(u32::from_str_radix(hex, 16).expect("validated hex digits"))
+    }
+}
+
+/// Sets [`RawEntry::duplicate`] for every entry whose key appears more than once.
+fn mark_duplicates(entries: &mut [RawEntry]) {
+    let mut counts: HashMap<&str, usize> = HashMap::new();
+    for entry in entries.iter() {
+        *counts.entry(&entry.key).or_default() += 1;
+    }
+    if counts.len() == entries.len() {
+        return;
+    }
+
+    let mut seen: HashMap<&str, usize> = HashMap::new();
+    let duplicates: Vec<Option<usize>> = entries
+        .iter()
+        .map(|entry| {
+            (counts[entry.key.as_ref()] > 1).then(|| {
+                let occurrence = seen.entry(&entry.key).or_default();
+                *occurrence += 1;
+                *occurrence - 1
+            })
+        })
+        .collect();
+    for (entry, duplicate) in entries.iter_mut().zip(duplicates) {
+        entry.duplicate = duplicate;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::serialize::JsonSerializer;
+
+    #[test]
+    fn raw_value_preserves_order_duplicates_and_numbers() {
+        let src = r#"{"z": [1.50, -0, 1e400, 123456789012345678901234567890], "a": null, "z": {"y": true, "y": false}}"#;
+        let value = RawValue::parse(src).unwrap();
+
+        assert_eq!(
+            JsonSerializer::new().to_string(&value),
+            r#"{"z":[1.50,-0,1e400,123456789012345678901234567890],"a":null,"z":{"y":true,"y":false}}"#
+        );
+
+        let JsonTreeValue::Expandable(entries, ExpandableType::Object) = value.to_json_tree_value()
+        else {
+            panic!("expected an object");
+        };
+        let properties: Vec<_> = entries.iter().map(|(property, _)| *property).collect();
+        assert_eq!(
+            properties,
+            [
+                JsonPointerSegment::DuplicateKey("z", 0),
+                JsonPointerSegment::Key("a"),
+                JsonPointerSegment::DuplicateKey("z", 1),
+            ]
+        );
+    }
+
+    #[test]
+    fn raw_value_unescapes_strings() {
+        let value = RawValue::parse(r#"["plain", "tab\tquote\" é 😀"]"#).unwrap();
+        let RawValueKind::Array(elems) = value.kind else {
+            panic!("expected an array");
+        };
+        assert!(matches!(
+            &elems[0].kind,
+            RawValueKind::String(Cow::Borrowed("plain"))
+        ));
+        assert_eq!(
+            elems[1].kind,
+            RawValueKind::String(Cow::Owned("tab\tquote\" é 😀".to_string()))
+        );
+    }
+
+    #[test]
+    fn raw_value_reports_spans_of_values_and_keys() {
+        let src = "{\n  \"é\": [true, {}],\n  \"b\": \"x\"\n}";
+        let value = RawValue::parse(src).unwrap();
+        let text = |span: Option<Range<usize>>| &src[span.unwrap()];
+
+        assert_eq!(text(value.span()), src);
+        assert_eq!(text(value.key_span(0)), "\"é\"");
+        assert_eq!(text(value.key_span(1)), "\"b\"");
+        assert_eq!(value.key_span(2), None);
+
+        let JsonTreeValue::Expandable(entries, _) = value.to_json_tree_value() else {
+            panic!("expected an object");
+        };
+        assert_eq!(text(entries[0].1.span()), "[true, {}]");
+        assert_eq!(text(entries[1].1.span()), "\"x\"");
+        assert_eq!(entries[0].1.key_span(0), None);
+
+        let JsonTreeValue::Expandable(elems, _) = entries[0].1.to_json_tree_value() else {
+            panic!("expected an array");
+        };
+        assert_eq!(text(elems[1].1.span()), "{}");
+        assert_eq!(line_column(src, elems[1].1.span().unwrap().start), (2, 15));
+        assert_eq!(line_column(src, value.key_span(1).unwrap().start), (3, 3));
+    }
+
+    #[test]
+    fn raw_value_rejects_invalid_json() {
+        let error = |src| RawValue::parse(src).unwrap_err();
+
+        assert_eq!(error("[1,]").message, "expected value");
+        assert_eq!(error("01").message, "trailing characters");
+        assert_eq!(error("1.").message, "invalid number");
+        assert_eq!(
+            error(r#""\ud800""#).message,
+            "unpaired surrogate in unicode escape"
+        );
+        assert_eq!(error("{\"a\" 1}").message, "expected `:`");
+        let deeply_nested = "[".repeat(200);
+        assert_eq!(error(&deeply_nested).message, "recursion limit exceeded");
+
+        let err = error("{\n  \"a\": tru\n}");
+        assert_eq!((err.line, err.column, err.offset), (2, 8, 9));
+        assert_eq!(err.to_string(), "expected value at line 2 column 8");
+    }
+}
@@ -1,16 +1,18 @@
 //! Rendering implementation for a [`JsonTree`](crate::JsonTree).
 
-use std::fmt::Display;
+use std::{fmt::Display, ops::Range};
 
 use egui::{
-    Color32, FontId, Label, Response, Sense, TextFormat, Ui,
+    Color32, FontId, Label, Response, Sense, Shape, TextFormat, Ui,
     cache::{ComputerMut, FrameCache},
     collapsing_header::CollapsingState,
+    layers::ShapeIdx,
     text::LayoutJob,
 };
 
 use crate::{
     JsonTreeStyle, JsonTreeVisuals,
+    context_menu::JsonTreeContextMenu,
     delimiters::{ExpandableDelimiter, SpacingDelimiter},
     pointer::{JsonPointer, JsonPointerSegment},
     search::SearchTerm,
@@ -26,7 +28,7 @@ pub trait DefaultRender {
 }
 
 /// A handle to the information of a render call.
-pub enum RenderContext<'a, 'b, T: ToJsonTreeValue> {
+pub enum RenderContext<'a, 'b, T: ToJsonTreeValue + ?Sized> {
     /// A render call for an array index or an object key.
     Property(RenderPropertyContext<'a, 'b, T>),
     /// A render call for a non-recursive JSON value.
@@ -35,7 +37,7 @@ pub enum RenderContext<'a, 'b, T: ToJsonTreeValue> {
     ExpandableDelimiter(RenderExpandableDelimiterContext<'a, 'b, T>),
 }
 
-impl<T: ToJsonTreeValue> DefaultRender for RenderContext<'_, '_, T> {
+impl<T: ToJsonTreeValue + ?Sized> DefaultRender for RenderContext<'_, '_, T> {
     fn render_default(&self, ui: &mut Ui) -> Response {
         match self {
             RenderContext::Property(context) => context.render_default(ui),
@@ -45,7 +47,7 @@ impl<T: ToJsonTreeValue> DefaultRender for RenderContext<'_, '_, T> {
     }
 }
 
-impl<'a, T: ToJsonTreeValue> RenderContext<'a, '_, T> {
+impl<'a, T: ToJsonTreeValue + ?Sized> RenderContext<'a, '_, T> {
     /// Convenience method to access the JSON value involved in this render call.
     pub fn value(&self) -> &'a T {
         match self {
@@ -63,10 +65,21 @@ impl<'a, T: ToJsonTreeValue> RenderContext<'a, '_, T> {
             RenderContext::ExpandableDelimiter(context) => context.pointer,
         }
     }
+
+    /// Convenience method to access the byte range within the source text of the element being rendered, if known.
+    ///
+    /// See [`RenderPropertyContext::span`], [`RenderBaseValueContext::span`] and [`RenderExpandableDelimiterContext::span`].
+    pub fn span(&self) -> Option<Range<usize>> {
+        match self {
+            RenderContext::Property(context) => context.span(),
+            RenderContext::BaseValue(context) => context.span(),
+            RenderContext::ExpandableDelimiter(context) => context.span(),
+        }
+    }
 }
 
 /// A handle to the information of a render call for an array index or object key.
-pub struct RenderPropertyContext<'a, 'b, T: ToJsonTreeValue> {
+pub struct RenderPropertyContext<'a, 'b, T: ToJsonTreeValue + ?Sized> {
     /// The array index or object key being rendered.
     pub property: JsonPointerSegment<'a>,
     /// The JSON value under this property.
@@ -78,12 +91,30 @@ pub struct RenderPropertyContext<'a, 'b, T: ToJsonTreeValue> {
     /// If an array/object is under this property, contains the [`egui::collapsing_header::CollapsingState`] for it.
     /// This can be used to toggle or check whether the array/object is expanded. Any mutations will be stored after the render hook.
     pub collapsing_state: Option<&'b mut CollapsingState>,
+    pub(crate) key_span: Option<Range<usize>>,
     pub(crate) search_term: Option<&'b SearchTerm>,
+    pub(crate) background: Option<Color32>,
+    pub(crate) context_menu: &'b JsonTreeContextMenu<'a, T>,
+}
+
+impl<T: ToJsonTreeValue + ?Sized> RenderPropertyContext<'_, '_, T> {
+    /// Returns the byte range within the source text of the object key being rendered, see [`ToJsonTreeValue::key_span`],
+    /// or of the value under the array index being rendered, see [`ToJsonTreeValue::span`].
+    pub fn span(&self) -> Option<Range<usize>> {
+        match self.property {
+            JsonPointerSegment::Index(_) => self.value.span(),
+            _ => self.key_span.clone(),
+        }
+    }
 }
 
-impl<T: ToJsonTreeValue> DefaultRender for RenderPropertyContext<'_, '_, T> {
+impl<T: ToJsonTreeValue + ?Sized> DefaultRender for RenderPropertyContext<'_, '_, T> {
     fn render_default(&self, ui: &mut Ui) -> Response {
-        render_property(ui, self.style, &self.property, self.search_term)
+        let background = Background::add(ui, self.background);
+        let response = render_property(ui, self.style, &self.property, self.search_term);
+        background.paint(ui, &response);
+        self.context_menu
+            .attach(response, self.style, self.value, self.pointer)
     }
 }
 
@@ -95,7 +126,7 @@ pub(crate) enum ParentStatus {
 }
 
 /// A handle to the information of a render call for a non-recursive JSON value.
-pub struct RenderBaseValueContext<'a, 'b, T: ToJsonTreeValue> {
+pub struct RenderBaseValueContext<'a, 'b, T: ToJsonTreeValue + ?Sized> {
     /// The non-recursive JSON value being rendered.
     pub value: &'a T,
     /// A reference to a value that visually represents the JSON value being rendered.
@@ -108,23 +139,38 @@ pub struct RenderBaseValueContext<'a, 'b, T: ToJsonTreeValue> {
     pub style: &'b JsonTreeStyle,
     pub(crate) search_term: Option<&'b SearchTerm>,
     pub(crate) parent_status: ParentStatus,
+    pub(crate) background: Option<Color32>,
+    pub(crate) context_menu: &'b JsonTreeContextMenu<'a, T>,
+}
+
+impl<T: ToJsonTreeValue + ?Sized> RenderBaseValueContext<'_, '_, T> {
+    /// Returns the byte range within the source text of the value being rendered, see [`ToJsonTreeValue::span`].
+    pub fn span(&self) -> Option<Range<usize>> {
+        self.value.span()
+    }
 }
 
-impl<T: ToJsonTreeValue> DefaultRender for RenderBaseValueContext<'_, '_, T> {
+impl<T: ToJsonTreeValue + ?Sized> DefaultRender for RenderBaseValueContext<'_, '_, T> {
     fn render_default(&self, ui: &mut Ui) -> Response {
-        render_value(
+        let background = Background::add(ui, self.background);
+        let response = render_value(
             ui,
             self.style,
-            &self.display_value.to_string(),
+            &self
+                .style
+                .format_value(self.display_value, &self.value_type, self.pointer.0),
             &self.value_type,
             self.search_term,
             self.parent_status,
-        )
+        );
+        background.paint(ui, &response);
+        self.context_menu
+            .attach(response, self.style, self.value, self.pointer)
     }
 }
 
 /// A handle to the information of a render call for array brackets or object braces.
-pub struct RenderExpandableDelimiterContext<'a, 'b, T: ToJsonTreeValue> {
+pub struct RenderExpandableDelimiterContext<'a, 'b, T: ToJsonTreeValue + ?Sized> {
     /// The specific token of the array bracket or object brace being rendered.
     pub delimiter: ExpandableDelimiter,
     /// The JSON array or object that the delimiter belongs to.
@@ -136,11 +182,41 @@ pub struct RenderExpandableDelimiterContext<'a, 'b, T: ToJsonTreeValue> {
     /// The [`egui::collapsing_header::CollapsingState`] for the array or object that this delimiter belongs to.
     /// This can be used to toggle or check whether the array/object is expanded. Any mutations will be stored after the render hook.
     pub collapsing_state: &'b mut CollapsingState,
+    pub(crate) background: Option<Color32>,
+    pub(crate) context_menu: &'b JsonTreeContextMenu<'a, T>,
 }
 
-impl<T: ToJsonTreeValue> DefaultRender for RenderExpandableDelimiterContext<'_, '_, T> {
+impl<T: ToJsonTreeValue + ?Sized> RenderExpandableDelimiterContext<'_, '_, T> {
+    /// Returns the byte range within the source text of the whole array or object that the delimiter belongs to, see [`ToJsonTreeValue::span`].
+    pub fn span(&self) -> Option<Range<usize>> {
+        self.value.span()
+    }
+}
+
+impl<T: ToJsonTreeValue + ?Sized> DefaultRender for RenderExpandableDelimiterContext<'_, '_, T> {
     fn render_default(&self, ui: &mut Ui) -> Response {
-        render_delimiter(ui, self.style, self.delimiter.as_ref())
+        let background = Background::add(ui, self.background);
+        let response = render_delimiter(ui, self.style, self.delimiter.as_ref());
+        background.paint(ui, &response);
+        self.context_menu
+            .attach(response, self.style, self.value, self.pointer)
+    }
+}
+
+/// A placeholder for the background color of an element, which is painted behind the element once it has been rendered,
+/// e.g. for values that changed recently (see [`JsonTreeStyle::change_flash_duration`]) or the nodes of a [`JsonDiffTree`](crate::JsonDiffTree).
+struct Background(Option<(ShapeIdx, Color32)>);
+
+impl Background {
+    fn add(ui: &Ui, background: Option<Color32>) -> Self {
+        Self(background.map(|color| (ui.painter().add(Shape::Noop), color)))
+    }
+
+    fn paint(self, ui: &Ui, response: &Response) {
+        if let Some((idx, color)) = self.0 {
+            ui.painter()
+                .set(idx, Shape::rect_filled(response.rect, 2.0, color));
+        }
     }
 }
 
@@ -155,17 +231,17 @@ impl DefaultRender for RenderSpacingDelimiterContext<'_> {
     }
 }
 
-pub(crate) struct JsonTreeRenderer<'a, T: ToJsonTreeValue> {
+pub(crate) struct JsonTreeRenderer<'a, T: ToJsonTreeValue + ?Sized> {
     pub(crate) render_hook: Option<Box<RenderHook<'a, T>>>,
 }
 
-impl<T: ToJsonTreeValue> Default for JsonTreeRenderer<'_, T> {
+impl<T: ToJsonTreeValue + ?Sized> Default for JsonTreeRenderer<'_, T> {
     fn default() -> Self {
         Self { render_hook: None }
     }
 }
 
-impl<'a, T: ToJsonTreeValue> JsonTreeRenderer<'a, T> {
+impl<'a, T: ToJsonTreeValue + ?Sized> JsonTreeRenderer<'a, T> {
     pub(crate) fn render_property<'b>(
         &mut self,
         ui: &mut Ui,
@@ -233,13 +309,14 @@ impl ValueLayoutJobCreator {
         font_id: &FontId,
     ) -> LayoutJob {
         let color = visuals.get_color(value_type);
-        let add_quote_if_string = |job: &mut LayoutJob| {
-            if *value_type == BaseValueType::String {
+        let is_quoted = visuals.is_quoted(value_type);
+        let add_quote_if_quoted = |job: &mut LayoutJob| {
+            if is_quoted {
                 append(job, "\"", color, None, font_id)
             };
         };
         let mut job = LayoutJob::default();
-        add_quote_if_string(&mut job);
+        add_quote_if_quoted(&mut job);
         add_text_with_highlighting(
             &mut job,
             value_str,
@@ -248,7 +325,7 @@ impl ValueLayoutJobCreator {
             visuals.highlight_color,
             font_id,
         );
-        add_quote_if_string(&mut job);
+        add_quote_if_quoted(&mut job);
         job
     }
 }
@@ -332,6 +409,22 @@ impl PropertyLayoutJobCreator {
                 visuals.highlight_color,
                 font_id,
             ),
+            JsonPointerSegment::NonStringKey(_) => add_text_with_highlighting(
+                &mut job,
+                &property.to_string(),
+                visuals.object_key_color,
+                search_term,
+                visuals.highlight_color,
+                font_id,
+            ),
+            JsonPointerSegment::DuplicateKey(..) => add_object_key(
+                &mut job,
+                &property.to_string(),
+                visuals.warning_color,
+                search_term,
+                visuals.highlight_color,
+                font_id,
+            ),
         };
         job
     }
@@ -455,6 +548,19 @@ fn append(
     job.append(text_str, 0.0, text_format);
 }
 
+/// Renders a comment attached to a JSON value, see [`ToJsonTreeValue::comments`].
+pub(crate) fn render_comment(ui: &mut Ui, style: &JsonTreeStyle, comment: &str) -> Response {
+    let mut job = LayoutJob::default();
+    append(
+        &mut job,
+        comment,
+        style.resolve_visuals(ui).comment_color,
+        None,
+        &style.resolve_font_id(ui),
+    );
+    render_job(ui, job)
+}
+
 fn render_delimiter(ui: &mut Ui, style: &JsonTreeStyle, delimiter_str: &str) -> Response {
     let mut job = LayoutJob::default();
     append(
@@ -1,10 +1,18 @@
-use egui::{Id, Ui};
+use egui::{Color32, Id, Ui, collapsing_header::CollapsingState};
 
-use crate::node::ShouldResetExpanded;
+use crate::{
+    JsonTreeStyle, JsonTreeVisuals, export, node::ShouldResetExpanded, pointer::JsonPointerSegment,
+    value::ToJsonTreeValue,
+};
 
 /// The response from showing a [`JsonTree`](crate::JsonTree).
 pub struct JsonTreeResponse {
     pub(crate) tree_id: Id,
+    /// The Id of the `Ui` the tree was shown in, from which the Ids of its arrays/objects are derived.
+    pub(crate) persistent_id: Id,
+    pub(crate) style: JsonTreeStyle,
+    pub(crate) visuals: JsonTreeVisuals,
+    pub(crate) background_color: Color32,
 }
 
 impl JsonTreeResponse {
@@ -17,4 +25,57 @@ impl JsonTreeResponse {
         ui.ctx()
             .data_mut(|d| d.insert_temp(self.tree_id, ShouldResetExpanded));
     }
+
+    /// Exports the visible contents of the [`JsonTree`](crate::JsonTree) that provided this response as indented plain text,
+    /// e.g. for pasting into a bug report.
+    ///
+    /// The text is laid out the same way as the tree is rendered: only expanded arrays/objects show their contents,
+    /// and collapsed arrays/objects are shown as `[...]`/`{...}`.
+    ///
+    /// `value` must be the same value that the tree was shown with.
+    pub fn to_text<T: ToJsonTreeValue + ?Sized>(&self, ui: &Ui, value: &T) -> String {
+        export::to_text(
+            value,
+            &|path_segments| self.is_expanded(ui, path_segments),
+            &self.style,
+            &self.visuals,
+            false,
+        )
+    }
+
+    /// Same as [`JsonTreeResponse::to_text`], but colors the text with ANSI escape codes
+    /// according to the [`JsonTreeVisuals`] the tree was rendered with, e.g. for printing to a terminal.
+    pub fn to_ansi_text<T: ToJsonTreeValue + ?Sized>(&self, ui: &Ui, value: &T) -> String {
+        export::to_text(
+            value,
+            &|path_segments| self.is_expanded(ui, path_segments),
+            &self.style,
+            &self.visuals,
+            true,
+        )
+    }
+
+    /// Exports the [`JsonTree`](crate::JsonTree) that provided this response as a self-contained HTML snippet,
+    /// e.g. for pasting into incident reports and wikis.
+    ///
+    /// Arrays/objects are rendered as `<details>`/`<summary>` elements which are open if they are currently expanded in the tree,
+    /// and can still be expanded/collapsed in the HTML. All colors are inline styles, taken from the [`JsonTreeVisuals`] the tree was rendered with,
+    /// and the panel fill color of the `Ui`.
+    ///
+    /// `value` must be the same value that the tree was shown with.
+    pub fn to_html<T: ToJsonTreeValue + ?Sized>(&self, ui: &Ui, value: &T) -> String {
+        export::to_html(
+            value,
+            &|path_segments| self.is_expanded(ui, path_segments),
+            &self.style,
+            &self.visuals,
+            self.background_color,
+        )
+    }
+
+    /// Whether the array/object at the given path was expanded when the tree was last shown.
+    fn is_expanded(&self, ui: &Ui, path_segments: &[JsonPointerSegment]) -> bool {
+        let path_id = self.persistent_id.with(self.tree_id.with(path_segments));
+        CollapsingState::load(ui.ctx(), path_id).is_some_and(|state| state.is_open())
+    }
 }
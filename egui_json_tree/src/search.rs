@@ -1,10 +1,11 @@
-use std::collections::HashSet;
+use std::{borrow::Cow, collections::HashSet};
 
 use egui::Id;
 
 use crate::{
+    JsonTreeStyle,
     pointer::JsonPointerSegment,
-    value::{ExpandableType, JsonTreeValue, ToJsonTreeValue},
+    value::{JsonTreeVisit, ToJsonTreeValue},
 };
 
 #[derive(Debug, Clone, Hash)]
@@ -27,23 +28,46 @@ impl SearchTerm {
         self.0.len()
     }
 
-    pub(crate) fn find_matching_paths_in<T: ToJsonTreeValue>(
+    /// Finds the Ids of the expandable paths to expand in order to show every match in the given value.
+    ///
+    /// `document` is the path segment of the value within a [`JsonLinesTree`](crate::JsonLinesTree), which prefixes every path.
+    pub(crate) fn find_matching_paths_in<T: ToJsonTreeValue + ?Sized>(
         &self,
         value: &T,
-        abbreviate_root: bool,
+        document: Option<JsonPointerSegment>,
+        style: &JsonTreeStyle,
         make_persistent_id: &dyn Fn(&[JsonPointerSegment]) -> Id,
     ) -> HashSet<Id> {
         let mut search_match_path_ids = HashSet::new();
 
-        search_impl(
-            value,
-            self,
-            &mut vec![],
-            &mut search_match_path_ids,
-            make_persistent_id,
-        );
+        value.visit(&mut |relative_path_segments, visit| {
+            let path_segments: Cow<[JsonPointerSegment]> = match document {
+                Some(document) => std::iter::once(document)
+                    .chain(relative_path_segments.iter().copied())
+                    .collect(),
+                None => Cow::Borrowed(relative_path_segments),
+            };
+            // Ignore matches for indices in an array.
+            let property_matches = relative_path_segments.last().is_some_and(|property| {
+                !matches!(property, JsonPointerSegment::Index(_)) && self.matches(property)
+            });
+            // Match against the text as it is shown, e.g. formatted numbers or bytes formatted as base64.
+            let value_matches = match visit {
+                JsonTreeVisit::Base(display_value, value_type) => {
+                    self.matches(&style.format_value(display_value, &value_type, &path_segments))
+                }
+                JsonTreeVisit::Expandable(_) => false,
+            };
+            if property_matches || value_matches {
+                update_matches(
+                    &path_segments,
+                    &mut search_match_path_ids,
+                    make_persistent_id,
+                );
+            }
+        });
 
-        if !abbreviate_root && search_match_path_ids.len() == 1 {
+        if document.is_none() && !style.abbreviate_root && search_match_path_ids.len() == 1 {
             // The only match was a top level key or value - no need to expand anything.
             search_match_path_ids.clear();
         }
@@ -56,41 +80,6 @@ impl SearchTerm {
     }
 }
 
-fn search_impl<'a, T: ToJsonTreeValue>(
-    value: &'a T,
-    search_term: &SearchTerm,
-    path_segments: &mut Vec<JsonPointerSegment<'a>>,
-    search_match_path_ids: &mut HashSet<Id>,
-    make_persistent_id: &dyn Fn(&[JsonPointerSegment]) -> Id,
-) {
-    match value.to_json_tree_value() {
-        JsonTreeValue::Base(_, display_value, _) => {
-            if search_term.matches(display_value) {
-                update_matches(path_segments, search_match_path_ids, make_persistent_id);
-            }
-        }
-        JsonTreeValue::Expandable(entries, expandable_type) => {
-            for (property, val) in entries.iter() {
-                path_segments.push(*property);
-
-                // Ignore matches for indices in an array.
-                if expandable_type == ExpandableType::Object && search_term.matches(property) {
-                    update_matches(path_segments, search_match_path_ids, make_persistent_id);
-                }
-
-                search_impl(
-                    *val,
-                    search_term,
-                    path_segments,
-                    search_match_path_ids,
-                    make_persistent_id,
-                );
-                path_segments.pop();
-            }
-        }
-    };
-}
-
 fn update_matches(
     path_segments: &[JsonPointerSegment],
     search_match_path_ids: &mut HashSet<Id>,
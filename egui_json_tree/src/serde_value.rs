@@ -0,0 +1,593 @@
+//! Visualisation of any [`serde::Serialize`] type, without converting it to a JSON value type first.
+//!
+//! [`SerdeValue::from_serialize`] walks the serde data model of a value and builds a lightweight [`SerdeValue`] tree,
+//! which implements [`ToJsonTreeValue`].
+//!
+//! The serde data model is mapped the same way as `serde_json` would serialize it:
+//! - `bool` is a [`BaseValueType::Bool`].
+//! - Integers and finite floats are a [`BaseValueType::Number`]. Non-finite floats (`NaN` and infinities) are a [`BaseValueType::Null`].
+//! - `char` and strings are a [`BaseValueType::String`].
+//! - Byte arrays are a [`BaseValueType::Bytes`].
+//! - `()`, `None` and unit structs (e.g. `struct Unit;`) are a [`BaseValueType::Null`].
+//! - `Some(value)` and newtype structs (e.g. `struct Meters(u32);`) are their inner value.
+//! - Sequences, tuples and tuple structs (e.g. `struct Point(i32, i32);`) are an array.
+//! - Maps and structs are an object. Map keys that are not strings are represented by
+//!   [`JsonPointerSegment::NonStringKey`], displaying scalar keys as-is and any other keys as compact JSON.
+//! - Enum variants are externally tagged:
+//!   - A unit variant (e.g. `E::A`) is a string of the variant name, e.g. `"A"`.
+//!   - Any other variant (e.g. `E::B(1)`, `E::C(1, 2)` or `E::D { x: 1 }`) is an object with a single entry,
+//!     keyed by the variant name, of its inner value, e.g. `{ "B": 1 }`, `{ "C": [1, 2] }` or `{ "D": { "x": 1 } }`.
+//!
+//! Serde attributes such as `#[serde(rename = "...")]` and `#[serde(skip)]` are respected, since they change what is serialized.
+//!
+//! ```rust
+//! # use egui_json_tree::serde_value::{SerdeKey, SerdeValue};
+//! # use std::collections::BTreeMap;
+//! let value = SerdeValue::from_serialize(&(Some(1), "two", BTreeMap::from([(3, [4.5])]))).unwrap();
+//!
+//! assert_eq!(
+//!     value,
+//!     SerdeValue::Array(vec![
+//!         SerdeValue::Number("1".to_string()),
+//!         SerdeValue::String("two".to_string()),
+//!         SerdeValue::Object(vec![(
+//!             SerdeKey::NonString("3".to_string()),
+//!             SerdeValue::Array(vec![SerdeValue::Number("4.5".to_string())]),
+//!         )]),
+//!     ])
+//! );
+//! ```
+
+use std::fmt;
+
+use ::serde::ser::{self, Serialize};
+
+use crate::{
+    pointer::JsonPointerSegment,
+    serialize::JsonSerializer,
+    value::{BaseValueType, DisplayBytes, ExpandableType, JsonTreeValue, ToJsonTreeValue},
+};
+
+/// A lightweight value tree built from any [`serde::Serialize`] type, see the [module docs](self) for how it is mapped.
+#[derive(Debug, Clone, PartialEq)]
+pub enum SerdeValue {
+    Null,
+    Bool(bool),
+    /// A number, as its displayed text.
+    Number(String),
+    String(String),
+    Bytes(Vec<u8>),
+    Array(Vec<SerdeValue>),
+    Object(Vec<(SerdeKey, SerdeValue)>),
+}
+
+/// The key of an entry in a [`SerdeValue::Object`].
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum SerdeKey {
+    /// A string key, i.e. a struct field name, enum variant name or string map key.
+    String(String),
+    /// A map key that is not a string, as its displayed text.
+    NonString(String),
+}
+
+/// An error raised by a [`serde::Serialize`] implementation while building a [`SerdeValue`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SerdeValueError(String);
+
+impl fmt::Display for SerdeValueError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.0)
+    }
+}
+
+impl std::error::Error for SerdeValueError {}
+
+impl ser::Error for SerdeValueError {
+    fn custom<T: fmt::Display>(msg: T) -> Self {
+        Self(msg.to_string())
+    }
+}
+
+impl SerdeValue {
+    /// Builds a [`SerdeValue`] by serializing the given value.
+    ///
+    /// Fails if the [`serde::Serialize`] implementation of the value raises an error.
+    pub fn from_serialize<T: Serialize + ?Sized>(value: &T) -> Result<Self, SerdeValueError> {
+        value.serialize(SerdeValueSerializer)
+    }
+}
+
+impl ToJsonTreeValue for SerdeValue {
+    fn to_json_tree_value(&self) -> JsonTreeValue<'_, Self> {
+        match self {
+            SerdeValue::Null => JsonTreeValue::Base(self, &"null", BaseValueType::Null),
+            SerdeValue::Bool(b) => JsonTreeValue::Base(self, b, BaseValueType::Bool),
+            SerdeValue::Number(n) => JsonTreeValue::Base(self, n, BaseValueType::Number),
+            SerdeValue::String(s) => JsonTreeValue::Base(self, s, BaseValueType::String),
+            SerdeValue::Bytes(b) => {
+                JsonTreeValue::Base(self, DisplayBytes::new(b), BaseValueType::Bytes)
+            }
+            SerdeValue::Array(arr) => JsonTreeValue::Expandable(
+                arr.iter()
+                    .enumerate()
+                    .map(|(idx, elem)| (JsonPointerSegment::Index(idx), elem))
+                    .collect(),
+                ExpandableType::Array,
+            ),
+            SerdeValue::Object(obj) => JsonTreeValue::Expandable(
+                obj.iter()
+                    .map(|(key, val)| {
+                        let property = match key {
+                            SerdeKey::String(key) => JsonPointerSegment::Key(key),
+                            SerdeKey::NonString(key) => JsonPointerSegment::NonStringKey(key),
+                        };
+                        (property, val)
+                    })
+                    .collect(),
+                ExpandableType::Object,
+            ),
+        }
+    }
+
+    fn is_expandable(&self) -> bool {
+        matches!(self, SerdeValue::Array(_) | SerdeValue::Object(_))
+    }
+}
+
+/// Wraps a value in an object with a single entry keyed by the variant name, for externally tagged enum variants.
+fn tagged(variant: &str, value: SerdeValue) -> SerdeValue {
+    SerdeValue::Object(vec![(SerdeKey::String(variant.to_string()), value)])
+}
+
+struct SerdeValueSerializer;
+
+impl ser::Serializer for SerdeValueSerializer {
+    type Ok = SerdeValue;
+    type Error = SerdeValueError;
+    type SerializeSeq = SerializeArray;
+    type SerializeTuple = SerializeArray;
+    type SerializeTupleStruct = SerializeArray;
+    type SerializeTupleVariant = SerializeArray;
+    type SerializeMap = SerializeObject;
+    type SerializeStruct = SerializeObject;
+    type SerializeStructVariant = SerializeObject;
+
+    fn serialize_bool(self, v: bool) -> Result<SerdeValue, SerdeValueError> {
+        Ok(SerdeValue::Bool(v))
+    }
+
+    fn serialize_i8(self, v: i8) -> Result<SerdeValue, SerdeValueError> {
+        Ok(SerdeValue::Number(v.to_string()))
+    }
+
+    fn serialize_i16(self, v: i16) -> Result<SerdeValue, SerdeValueError> {
+        Ok(SerdeValue::Number(v.to_string()))
+    }
+
+    fn serialize_i32(self, v: i32) -> Result<SerdeValue, SerdeValueError> {
+        Ok(SerdeValue::Number(v.to_string()))
+    }
+
+    fn serialize_i64(self, v: i64) -> Result<SerdeValue, SerdeValueError> {
+        Ok(SerdeValue::Number(v.to_string()))
+    }
+
+    fn serialize_i128(self, v: i128) -> Result<SerdeValue, SerdeValueError> {
+        Ok(SerdeValue::Number(v.to_string()))
+    }
+
+    fn serialize_u8(self, v: u8) -> Result<SerdeValue, SerdeValueError> {
+        Ok(SerdeValue::Number(v.to_string()))
+    }
+
+    fn serialize_u16(self, v: u16) -> Result<SerdeValue, SerdeValueError> {
+        Ok(SerdeValue::Number(v.to_string()))
+    }
+
+    fn serialize_u32(self, v: u32) -> Result<SerdeValue, SerdeValueError> {
+        Ok(SerdeValue::Number(v.to_string()))
+    }
+
+    fn serialize_u64(self, v: u64) -> Result<SerdeValue, SerdeValueError> {
+        Ok(SerdeValue::Number(v.to_string()))
+    }
+
+    fn serialize_u128(self, v: u128) -> Result<SerdeValue, SerdeValueError> {
+        Ok(SerdeValue::Number(v.to_string()))
+    }
+
+    fn serialize_f32(self, v: f32) -> Result<SerdeValue, SerdeValueError> {
+        if !v.is_finite() {
+            return Ok(SerdeValue::Null);
+        }
+        Ok(SerdeValue::Number(v.to_string()))
+    }
+
+    fn serialize_f64(self, v: f64) -> Result<SerdeValue, SerdeValueError> {
+        if !v.is_finite() {
+            return Ok(SerdeValue::Null);
+        }
+        Ok(SerdeValue::Number(v.to_string()))
+    }
+
+    fn serialize_char(self, v: char) -> Result<SerdeValue, SerdeValueError> {
+        Ok(SerdeValue::String(v.to_string()))
+    }
+
+    fn serialize_str(self, v: &str) -> Result<SerdeValue, SerdeValueError> {
+        Ok(SerdeValue::String(v.to_string()))
+    }
+
+    fn serialize_bytes(self, v: &[u8]) -> Result<SerdeValue, SerdeValueError> {
+        Ok(SerdeValue::Bytes(v.to_vec()))
+    }
+
+    fn serialize_none(self) -> Result<SerdeValue, SerdeValueError> {
+        Ok(SerdeValue::Null)
+    }
+
+    fn serialize_some<T: Serialize + ?Sized>(
+        self,
+        value: &T,
+    ) -> Result<SerdeValue, SerdeValueError> {
+        value.serialize(self)
+    }
+
+    fn serialize_unit(self) -> Result<SerdeValue, SerdeValueError> {
+        Ok(SerdeValue::Null)
+    }
+
+    fn serialize_unit_struct(self, _name: &'static str) -> Result<SerdeValue, SerdeValueError> {
+        Ok(SerdeValue::Null)
+    }
+
+    fn serialize_unit_variant(
+        self,
+        _name: &'static str,
+        _variant_index: u32,
+        variant: &'static str,
+    ) -> Result<SerdeValue, SerdeValueError> {
+        Ok(SerdeValue::String(variant.to_string()))
+    }
+
+    fn serialize_newtype_struct<T: Serialize + ?Sized>(
+        self,
+        _name: &'static str,
+        value: &T,
+    ) -> Result<SerdeValue, SerdeValueError> {
+        value.serialize(self)
+    }
+
+    fn serialize_newtype_variant<T: Serialize + ?Sized>(
+        self,
+        _name: &'static str,
+        _variant_index: u32,
+        variant: &'static str,
+        value: &T,
+    ) -> Result<SerdeValue, SerdeValueError> {
+        Ok(tagged(variant, value.serialize(self)?))
+    }
+
+    fn serialize_seq(self, len: Option<usize>) -> Result<SerializeArray, SerdeValueError> {
+        Ok(SerializeArray {
+            variant: None,
+            elems: Vec::with_capacity(len.unwrap_or_default()),
+        })
+    }
+
+    fn serialize_tuple(self, len: usize) -> Result<SerializeArray, SerdeValueError> {
+        self.serialize_seq(Some(len))
+    }
+
+    fn serialize_tuple_struct(
+        self,
+        _name: &'static str,
+        len: usize,
+    ) -> Result<SerializeArray, SerdeValueError> {
+        self.serialize_seq(Some(len))
+    }
+
+    fn serialize_tuple_variant(
+        self,
+        _name: &'static str,
+        _variant_index: u32,
+        variant: &'static str,
+        len: usize,
+    ) -> Result<SerializeArray, SerdeValueError> {
+        Ok(SerializeArray {
+            variant: Some(variant),
+            elems: Vec::with_capacity(len),
+        })
+    }
+
+    fn serialize_map(self, len: Option<usize>) -> Result<SerializeObject, SerdeValueError> {
+        Ok(SerializeObject {
+            variant: None,
+            entries: Vec::with_capacity(len.unwrap_or_default()),
+            next_key: None,
+        })
+    }
+
+    fn serialize_struct(
+        self,
+        _name: &'static str,
+        len: usize,
+    ) -> Result<SerializeObject, SerdeValueError> {
+        self.serialize_map(Some(len))
+    }
+
+    fn serialize_struct_variant(
+        self,
+        _name: &'static str,
+        _variant_index: u32,
+        variant: &'static str,
+        len: usize,
+    ) -> Result<SerializeObject, SerdeValueError> {
+        Ok(SerializeObject {
+            variant: Some(variant),
+            entries: Vec::with_capacity(len),
+            next_key: None,
+        })
+    }
+}
+
+struct SerializeArray {
+    /// The variant name, if this is a tuple variant.
+    variant: Option<&'static str>,
+    elems: Vec<SerdeValue>,
+}
+
+impl SerializeArray {
+    fn push<T: Serialize + ?Sized>(&mut self, value: &T) -> Result<(), SerdeValueError> {
+        self.elems.push(value.serialize(SerdeValueSerializer)?);
+        Ok(())
+    }
+
+    fn finish(self) -> SerdeValue {
+        let array = SerdeValue::Array(self.elems);
+        match self.variant {
+            Some(variant) => tagged(variant, array),
+            None => array,
+        }
+    }
+}
+
+impl ser::SerializeSeq for SerializeArray {
+    type Ok = SerdeValue;
+    type Error = SerdeValueError;
+
+    fn serialize_element<T: Serialize + ?Sized>(
+        &mut self,
+        value: &T,
+    ) -> Result<(), SerdeValueError> {
+        self.push(value)
+    }
+
+    fn end(self) -> Result<SerdeValue, SerdeValueError> {
+        Ok(self.finish())
+    }
+}
+
+impl ser::SerializeTuple for SerializeArray {
+    type Ok = SerdeValue;
+    type Error = SerdeValueError;
+
+    fn serialize_element<T: Serialize + ?Sized>(
+        &mut self,
+        value: &T,
+    ) -> Result<(), SerdeValueError> {
+        self.push(value)
+    }
+
+    fn end(self) -> Result<SerdeValue, SerdeValueError> {
+        Ok(self.finish())
+    }
+}
+
+impl ser::SerializeTupleStruct for SerializeArray {
+    type Ok = SerdeValue;
+    type Error = SerdeValueError;
+
+    fn serialize_field<T: Serialize + ?Sized>(&mut self, value: &T) -> Result<(), SerdeValueError> {
+        self.push(value)
+    }
+
+    fn end(self) -> Result<SerdeValue, SerdeValueError> {
+        Ok(self.finish())
+    }
+}
+
+impl ser::SerializeTupleVariant for SerializeArray {
+    type Ok = SerdeValue;
+    type Error = SerdeValueError;
+
+    fn serialize_field<T: Serialize + ?Sized>(&mut self, value: &T) -> Result<(), SerdeValueError> {
+        self.push(value)
+    }
+
+    fn end(self) -> Result<SerdeValue, SerdeValueError> {
+        Ok(self.finish())
+    }
+}
+
+struct SerializeObject {
+    /// The variant name, if this is a struct variant.
+    variant: Option<&'static str>,
+    entries: Vec<(SerdeKey, SerdeValue)>,
+    /// The key of a map entry whose value has not been serialized yet.
+    next_key: Option<SerdeKey>,
+}
+
+impl SerializeObject {
+    fn finish(self) -> SerdeValue {
+        let object = SerdeValue::Object(self.entries);
+        match self.variant {
+            Some(variant) => tagged(variant, object),
+            None => object,
+        }
+    }
+}
+
+impl ser::SerializeMap for SerializeObject {
+    type Ok = SerdeValue;
+    type Error = SerdeValueError;
+
+    fn serialize_key<T: Serialize + ?Sized>(&mut self, key: &T) -> Result<(), SerdeValueError> {
+        let key = match key.serialize(SerdeValueSerializer)? {
+            SerdeValue::String(key) => SerdeKey::String(key),
+            SerdeValue::Null => SerdeKey::NonString("null".to_string()),
+            SerdeValue::Bool(b) => SerdeKey::NonString(b.to_string()),
+            SerdeValue::Number(n) => SerdeKey::NonString(n),
+            key => SerdeKey::NonString(JsonSerializer::new().to_string(&key)),
+        };
+        self.next_key = Some(key);
+        Ok(())
+    }
+
+    fn serialize_value<T: Serialize + ?Sized>(&mut self, value: &T) -> Result<(), SerdeValueError> {
+        let key = self
+            .next_key
+            .take()
+            .ok_or_else(|| ser::Error::custom("serialize_value called before serialize_key"))?;
+        self.entries
+            .push((key, value.serialize(SerdeValueSerializer)?));
+        Ok(())
+    }
+
+    fn end(self) -> Result<SerdeValue, SerdeValueError> {
+        Ok(self.finish())
+    }
+}
+
+impl ser::SerializeStruct for SerializeObject {
+    type Ok = SerdeValue;
+    type Error = SerdeValueError;
+
+    fn serialize_field<T: Serialize + ?Sized>(
+        &mut self,
+        key: &'static str,
+        value: &T,
+    ) -> Result<(), SerdeValueError> {
+        self.entries.push((
+            SerdeKey::String(key.to_string()),
+            value.serialize(SerdeValueSerializer)?,
+        ));
+        Ok(())
+    }
+
+    fn end(self) -> Result<SerdeValue, SerdeValueError> {
+        Ok(self.finish())
+    }
+}
+
+impl ser::SerializeStructVariant for SerializeObject {
+    type Ok = SerdeValue;
+    type Error = SerdeValueError;
+
+    fn serialize_field<T: Serialize + ?Sized>(
+        &mut self,
+        key: &'static str,
+        value: &T,
+    ) -> Result<(), SerdeValueError> {
+        ser::SerializeStruct::serialize_field(self, key, value)
+    }
+
+    fn end(self) -> Result<SerdeValue, SerdeValueError> {
+        Ok(self.finish())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::BTreeMap;
+
+    use ::serde::Serialize;
+
+    use super::*;
+
+    #[derive(Serialize)]
+    struct Unit;
+
+    struct Checksum(&'static [u8]);
+
+    impl Serialize for Checksum {
+        fn serialize<S: ::serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+            serializer.serialize_bytes(self.0)
+        }
+    }
+
+    #[derive(Serialize)]
+    struct Meters(u32);
+
+    #[derive(Serialize)]
+    struct Point(i32, i32);
+
+    #[derive(Serialize)]
+    enum Shape {
+        Empty,
+        Circle(f64),
+        Line(Point, Point),
+        Rect { width: u8, height: u8 },
+    }
+
+    #[derive(Serialize)]
+    struct Scene {
+        #[serde(rename = "label")]
+        name: &'static str,
+        #[serde(skip)]
+        #[allow(dead_code)]
+        secret: u8,
+        unit: Unit,
+        distance: Meters,
+        shapes: Vec<Shape>,
+        tags: Option<BTreeMap<(u8, u8), char>>,
+        checksum: Checksum,
+    }
+
+    #[test]
+    fn serde_value_maps_data_model_like_serde_json() {
+        let scene = Scene {
+            name: "scene",
+            secret: 42,
+            unit: Unit,
+            distance: Meters(5),
+            shapes: vec![
+                Shape::Empty,
+                Shape::Circle(1.5),
+                Shape::Line(Point(0, 0), Point(1, -1)),
+                Shape::Rect {
+                    width: 2,
+                    height: 3,
+                },
+            ],
+            tags: Some(BTreeMap::from([((1, 2), 'x')])),
+            checksum: Checksum(&[0xab]),
+        };
+        let value = SerdeValue::from_serialize(&scene).unwrap();
+
+        assert_eq!(
+            JsonSerializer::new().to_string(&value),
+            concat!(
+                r#"{"label":"scene","unit":null,"distance":5,"#,
+                r#""shapes":["Empty",{"Circle":1.5},{"Line":[[0,0],[1,-1]]},{"Rect":{"width":2,"height":3}}],"#,
+                r#""tags":{"[1,2]":"x"},"checksum":"h'ab'"}"#
+            )
+        );
+
+        let SerdeValue::Object(entries) = &value else {
+            panic!("expected an object");
+        };
+        let (_, tags) = entries
+            .iter()
+            .find(|(key, _)| *key == SerdeKey::String("tags".to_string()))
+            .unwrap();
+        let JsonTreeValue::Expandable(tag_entries, ExpandableType::Object) =
+            tags.to_json_tree_value()
+        else {
+            panic!("expected tags to be an object");
+        };
+        assert!(
+            matches!(tag_entries[0].0, JsonPointerSegment::NonStringKey(key) if key.to_string() == "[1,2]")
+        );
+    }
+}
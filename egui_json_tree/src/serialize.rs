@@ -0,0 +1,353 @@
+//! Serialization of any [`ToJsonTreeValue`] back to JSON text.
+//!
+//! [`JsonSerializer`] walks the [`JsonTreeValue`] representation of a value, so it works for any JSON type
+//! that [`JsonTree`](crate::JsonTree) can visualise, including custom implementations.
+//!
+//! ```rust
+//! # use egui_json_tree::serialize::{JsonSerializer, KeyOrder};
+//! let value = serde_json::json!({ "foo": "bar", "baz": [1, 2] });
+//!
+//! assert_eq!(
+//!     JsonSerializer::new().to_string(&value),
+//!     r#"{"baz":[1,2],"foo":"bar"}"#
+//! );
+//!
+//! assert_eq!(
+//!     JsonSerializer::new()
+//!         .indent("  ")
+//!         .key_order(KeyOrder::Sorted)
+//!         .to_string(&value),
+//!     "{\n  \"baz\": [\n    1,\n    2\n  ],\n  \"foo\": \"bar\"\n}"
+//! );
+//! ```
+
+use std::fmt::{self, Write};
+
+use crate::{
+    pointer::JsonPointerSegment,
+    value::{BaseValueType, ExpandableType, JsonTreeValue, ToJsonTreeValue},
+};
+
+/// Configuration for serializing a [`ToJsonTreeValue`] to JSON text.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Hash)]
+pub struct JsonSerializer {
+    pub indent: Option<String>,
+    pub key_order: KeyOrder,
+}
+
+/// The order in which to write the entries of an object.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub enum KeyOrder {
+    /// Write entries in the order provided by [`ToJsonTreeValue::to_json_tree_value`].
+    #[default]
+    Preserve,
+    /// Write entries sorted by key.
+    Sorted,
+}
+
+impl JsonSerializer {
+    /// Creates a [`JsonSerializer`] that writes compact JSON, preserving key order.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Creates a [`JsonSerializer`] that writes pretty-printed JSON indented by two spaces, preserving key order.
+    pub fn pretty() -> Self {
+        Self::new().indent("  ")
+    }
+
+    /// Pretty-print the JSON, placing each array element and object entry on its own line,
+    /// indented by `indent` for each level of nesting.
+    ///
+    /// Defaults to compact output without any whitespace.
+    pub fn indent(mut self, indent: impl Into<String>) -> Self {
+        self.indent = Some(indent.into());
+        self
+    }
+
+    /// Override the order in which object entries are written. Defaults to [`KeyOrder::Preserve`].
+    pub fn key_order(mut self, key_order: KeyOrder) -> Self {
+        self.key_order = key_order;
+        self
+    }
+
+    /// Serializes the value to a JSON string.
+    pub fn to_string<T: ToJsonTreeValue + ?Sized>(&self, value: &T) -> String {
+        let mut out = String::new();
+        // Writing to a `String` cannot fail.
+        let _ = self.write(value, &mut out);
+        out
+    }
+
+    /// Serializes the value as JSON text into the given writer.
+    pub fn write<T: ToJsonTreeValue + ?Sized>(
+        &self,
+        value: &T,
+        writer: &mut impl Write,
+    ) -> fmt::Result {
+        self.write_value(value, writer, 0)
+    }
+
+    pub(crate) fn write_value<T: ToJsonTreeValue + ?Sized>(
+        &self,
+        value: &T,
+        writer: &mut impl Write,
+        depth: usize,
+    ) -> fmt::Result {
+        match value.to_json_tree_value() {
+            JsonTreeValue::Base(_, display_value, value_type) => {
+                write_base_value(writer, &display_value.to_string(), value_type)
+            }
+            JsonTreeValue::Expandable(mut entries, expandable_type) => {
+                let (opening, closing) = match expandable_type {
+                    ExpandableType::Array => ('[', ']'),
+                    ExpandableType::Object => ('{', '}'),
+                };
+
+                if entries.is_empty() {
+                    writer.write_char(opening)?;
+                    return writer.write_char(closing);
+                }
+
+                if expandable_type == ExpandableType::Object && self.key_order == KeyOrder::Sorted {
+                    entries.sort_by_key(|(property, _)| property.to_string());
+                }
+
+                writer.write_char(opening)?;
+                for (idx, (property, elem)) in entries.iter().enumerate() {
+                    if idx > 0 {
+                        writer.write_char(',')?;
+                    }
+                    self.write_newline(writer, depth + 1)?;
+
+                    if expandable_type == ExpandableType::Object {
+                        // JSON only supports string keys, so any other keys are written as their displayed text.
+                        match property {
+                            JsonPointerSegment::Key(key)
+                            | JsonPointerSegment::DuplicateKey(key, _) => {
+                                self.write_key(writer, key)?
+                            }
+                            JsonPointerSegment::Index(_) | JsonPointerSegment::NonStringKey(_) => {
+                                self.write_key(writer, &property.to_string())?
+                            }
+                        };
+                    }
+
+                    self.write_value(*elem, writer, depth + 1)?;
+                }
+                self.write_newline(writer, depth)?;
+                writer.write_char(closing)
+            }
+        }
+    }
+
+    /// Writes an object key, followed by a colon.
+    pub(crate) fn write_key(&self, writer: &mut impl Write, key: &str) -> fmt::Result {
+        write_escaped_str(writer, key)?;
+        writer.write_char(':')?;
+        if self.indent.is_some() {
+            writer.write_char(' ')?;
+        }
+        Ok(())
+    }
+
+    pub(crate) fn write_newline(&self, writer: &mut impl Write, depth: usize) -> fmt::Result {
+        if let Some(indent) = &self.indent {
+            writer.write_char('\n')?;
+            for _ in 0..depth {
+                writer.write_str(indent)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+fn write_base_value(
+    writer: &mut impl Write,
+    value_str: &str,
+    value_type: BaseValueType,
+) -> fmt::Result {
+    match value_type {
+        BaseValueType::Null => writer.write_str("null"),
+        // JSON has no byte string type or custom types, so these are written as a string of their displayed text,
+        // e.g. `"h'0102'"`.
+        BaseValueType::String | BaseValueType::Bytes | BaseValueType::Other(_) => {
+            write_escaped_str(writer, value_str)
+        }
+        // Numbers without a JSON representation, e.g. non-finite floats, are written as null like serde_json does.
+        BaseValueType::Number if !is_json_number(value_str) => writer.write_str("null"),
+        BaseValueType::Bool | BaseValueType::Number => writer.write_str(value_str),
+    }
+}
+
+/// Whether the text is a number as defined by the JSON grammar, e.g. not `NaN`, `inf` or YAML's `.inf`.
+pub(crate) fn is_json_number(s: &str) -> bool {
+    let count_digits = |s: &str| s.bytes().take_while(u8::is_ascii_digit).count();
+
+    let s = s.strip_prefix('-').unwrap_or(s);
+    let int_len = count_digits(s);
+    if int_len == 0 || (int_len > 1 && s.starts_with('0')) {
+        return false;
+    }
+    let mut rest = &s[int_len..];
+
+    if let Some(frac) = rest.strip_prefix('.') {
+        let frac_len = count_digits(frac);
+        if frac_len == 0 {
+            return false;
+        }
+        rest = &frac[frac_len..];
+    }
+
+    if let Some(exp) = rest.strip_prefix(['e', 'E']) {
+        let exp = exp.strip_prefix(['+', '-']).unwrap_or(exp);
+        let exp_len = count_digits(exp);
+        if exp_len == 0 {
+            return false;
+        }
+        rest = &exp[exp_len..];
+    }
+
+    rest.is_empty()
+}
+
+/// Writes a string as a quoted JSON string, escaping quotes, backslashes and control characters.
+pub(crate) fn write_escaped_str(writer: &mut impl Write, s: &str) -> fmt::Result {
+    writer.write_char('"')?;
+    let mut start = 0;
+    for (idx, c) in s.char_indices() {
+        let escaped = match c {
+            '"' => "\\\"",
+            '\\' => "\\\\",
+            '\n' => "\\n",
+            '\r' => "\\r",
+            '\t' => "\\t",
+            '\u{08}' => "\\b",
+            '\u{0C}' => "\\f",
+            c if c < ' ' => "",
+            _ => continue,
+        };
+        writer.write_str(&s[start..idx])?;
+        if escaped.is_empty() {
+            write!(writer, "\\u{:04x}", c as u32)?;
+        } else {
+            writer.write_str(escaped)?;
+        }
+        start = idx + c.len_utf8();
+    }
+    writer.write_str(&s[start..])?;
+    writer.write_char('"')
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+
+    #[test]
+    fn serialize_compact_matches_serde_json() {
+        let value = json!({
+            "foo": [1, 2.5, -3, null, true, "two"],
+            "bar": { "baz": {}, "qux": [] },
+            "escapes": "quote \" backslash \\ newline \n tab \t bell \u{07} unicode é"
+        });
+        assert_eq!(
+            JsonSerializer::new().to_string(&value),
+            serde_json::to_string(&value).unwrap()
+        );
+    }
+
+    #[test]
+    fn serialize_pretty_matches_serde_json() {
+        let value = json!({
+            "foo": [1, { "a/b": "m~n" }, [[]]],
+            "bar": { "baz": {}, "qux": [null] },
+        });
+        assert_eq!(
+            JsonSerializer::pretty().to_string(&value),
+            serde_json::to_string_pretty(&value).unwrap()
+        );
+    }
+
+    #[test]
+    fn serialize_base_values() {
+        assert_eq!(JsonSerializer::new().to_string(&json!(null)), "null");
+        assert_eq!(JsonSerializer::new().to_string(&json!(false)), "false");
+        assert_eq!(JsonSerializer::new().to_string(&json!(1.5)), "1.5");
+        assert_eq!(
+            JsonSerializer::pretty().to_string(&json!("a\"b")),
+            r#""a\"b""#
+        );
+    }
+
+    #[test]
+    fn serialize_with_custom_indent_and_sorted_keys() {
+        let value = json!({ "b": [1], "a": { "d": null, "c": 2 } });
+        let serializer = JsonSerializer::new()
+            .indent("\t")
+            .key_order(KeyOrder::Sorted);
+        assert_eq!(
+            serializer.to_string(&value),
+            "{\n\t\"a\": {\n\t\t\"c\": 2,\n\t\t\"d\": null\n\t},\n\t\"b\": [\n\t\t1\n\t]\n}"
+        );
+    }
+
+    #[cfg(feature = "yaml_rust2")]
+    #[test]
+    fn serialize_yaml_with_non_string_keys() {
+        let value = yaml_rust2::YamlLoader::load_from_str(
+            "1: one\ntrue: yes\n~: none\n[a, b]: seq\n1.5: real\nref: !Ref foo\nnan: .nan",
+        )
+        .unwrap()
+        .remove(0);
+        assert_eq!(
+            JsonSerializer::new().to_string(&value),
+            r#"{"1":"one","true":"yes","null":"none","[\"a\",\"b\"]":"seq","1.5":"real","ref":"foo","nan":null}"#
+        );
+    }
+
+    #[cfg(feature = "toml")]
+    #[test]
+    fn serialize_toml_with_datetimes_as_strings() {
+        let value: toml::Value = toml::from_str(
+            "title = \"config\"\n[owner]\ndob = 1979-05-27T07:32:00-08:00\n[database]\nports = [8000, 8001]\nratio = 0.5\nenabled = true",
+        )
+        .unwrap();
+        assert_eq!(
+            JsonSerializer::new().to_string(&value),
+            r#"{"database":{"enabled":true,"ports":[8000,8001],"ratio":0.5},"owner":{"dob":"1979-05-27T07:32:00-08:00"},"title":"config"}"#
+        );
+    }
+
+    #[cfg(feature = "ciborium")]
+    #[test]
+    fn serialize_cbor_with_non_string_keys_bytes_and_tags() {
+        use ciborium::Value;
+
+        let value = Value::Map(vec![
+            (Value::Integer(1.into()), Value::Bytes(vec![0x01, 0xff])),
+            (Value::Bytes(vec![0x0a]), Value::Null),
+            (
+                Value::Text("time".to_string()),
+                Value::Tag(1, Box::new(Value::Integer(1363896240.into()))),
+            ),
+        ]);
+        assert_eq!(
+            JsonSerializer::new().to_string(&value),
+            r#"{"1":"h'01ff'","h'0a'":null,"time":{"tag(1)":1363896240}}"#
+        );
+    }
+
+    #[test]
+    fn is_json_number_follows_json_grammar() {
+        for valid in ["0", "-0", "12", "1.5", "-1.5e10", "1E+2", "0.0e-3"] {
+            assert!(is_json_number(valid), "{valid}");
+        }
+        for invalid in [
+            "", "-", "01", "1.", ".5", "1e", "NaN", "inf", "-inf", ".inf", "0x1F",
+        ] {
+            assert!(!is_json_number(invalid), "{invalid}");
+        }
+    }
+}
@@ -1,6 +1,13 @@
+use std::{collections::BTreeMap, fmt::Display};
+
 use egui::{Color32, FontId, TextStyle, Ui};
 
-use crate::{ToggleButtonsState, render::ParentStatus, value::BaseValueType};
+use crate::{
+    ToggleButtonsState,
+    pointer::{JsonPointer, JsonPointerSegment},
+    render::ParentStatus,
+    value::BaseValueType,
+};
 
 /// Styling configuration to control the appearance of the [`JsonTree`](crate::JsonTree).
 #[derive(Debug, Clone, Default)]
@@ -10,6 +17,14 @@ pub struct JsonTreeStyle {
     pub abbreviate_root: bool,
     pub toggle_buttons_state: ToggleButtonsState,
     pub wrapping_config: JsonTreeWrappingConfig,
+    pub context_menu: bool,
+    pub bytes_format: BytesFormat,
+    pub number_format: NumberFormat,
+    /// Overrides of [`JsonTreeStyle::number_format`] for specific numbers, by JSON pointer string.
+    pub number_format_overrides: BTreeMap<String, NumberFormat>,
+    /// The duration in seconds over which the background of a changed value fades,
+    /// see [`JsonTreeStyle::change_flash_duration`](method@JsonTreeStyle::change_flash_duration).
+    pub change_flash_duration: Option<f32>,
 }
 
 impl JsonTreeStyle {
@@ -55,14 +70,96 @@ impl JsonTreeStyle {
         self
     }
 
+    /// Override whether to show the built-in context menu when right clicking array indices, object keys, values, brackets and braces.
+    ///
+    /// The context menu provides actions for copying the JSON pointer, JSONPath, JavaScript accessor, key and value of the clicked element to the clipboard.
+    /// Custom menu items can be appended via [`JsonTree::on_context_menu`](crate::JsonTree::on_context_menu).
+    ///
+    /// The context menu is also shown when a custom render hook calls [`render_default`](crate::render::DefaultRender::render_default).
+    ///
+    /// Defaults to `false`.
+    pub fn context_menu(mut self, context_menu: bool) -> Self {
+        self.context_menu = context_menu;
+        self
+    }
+
+    /// Override how [`BaseValueType::Bytes`] values are shown. Defaults to [`BytesFormat::Hex`].
+    pub fn bytes_format(mut self, bytes_format: BytesFormat) -> Self {
+        self.bytes_format = bytes_format;
+        self
+    }
+
+    /// Override how [`BaseValueType::Number`] values are shown. Defaults to [`NumberNotation::AsDisplayed`]
+    /// without thousands separators.
+    pub fn number_format(mut self, number_format: NumberFormat) -> Self {
+        self.number_format = number_format;
+        self
+    }
+
+    /// Override how the number at the given JSON pointer string is shown, e.g. `"/price"`.
+    /// Takes precedence over [`JsonTreeStyle::number_format`].
+    pub fn number_format_at(
+        mut self,
+        pointer: impl Into<String>,
+        number_format: NumberFormat,
+    ) -> Self {
+        self.number_format_overrides
+            .insert(pointer.into(), number_format);
+        self
+    }
+
+    /// Flash the background of values that changed since the previous frame in [`JsonTreeVisuals::changed_color`],
+    /// fading out over the given duration in seconds, e.g. when showing a value that is replaced every frame with streamed updates.
+    ///
+    /// Values are compared per JSON pointer, by their type and display value. Arrays and objects flash when anything shown within them changed,
+    /// including entries being added or removed.
+    /// Only the values that are shown are compared on each frame, i.e. changes within collapsed arrays and objects are not detected.
+    ///
+    /// Defaults to `None`, i.e. changes are not detected.
+    pub fn change_flash_duration(mut self, duration: f32) -> Self {
+        self.change_flash_duration = Some(duration);
+        self
+    }
+
+    /// Formats the display value of a non-recursive JSON value at the given path as it is shown in the tree.
+    ///
+    /// This is also used for search, so that search matches the text as it is shown.
+    pub(crate) fn format_value(
+        &self,
+        display_value: &dyn Display,
+        value_type: &BaseValueType,
+        path_segments: &[JsonPointerSegment],
+    ) -> String {
+        match value_type {
+            BaseValueType::Bytes if self.bytes_format == BytesFormat::Base64 => {
+                format!("{display_value:#}")
+            }
+            BaseValueType::Number => {
+                let number_format = if self.number_format_overrides.is_empty() {
+                    &self.number_format
+                } else {
+                    self.number_format_overrides
+                        .get(&JsonPointer(path_segments).to_json_pointer_string())
+                        .unwrap_or(&self.number_format)
+                };
+                if *number_format == NumberFormat::default() {
+                    display_value.to_string()
+                } else {
+                    number_format.format(&display_value.to_string())
+                }
+            }
+            _ => display_value.to_string(),
+        }
+    }
+
     /// Resolves the [`JsonTreeVisuals`] color scheme to use.
     pub(crate) fn resolve_visuals(&self, ui: &Ui) -> &JsonTreeVisuals {
         if let Some(visuals) = &self.visuals {
             visuals
         } else if ui.visuals().dark_mode {
-            &JsonTreeVisuals::DARK
+            &DARK_VISUALS
         } else {
-            &JsonTreeVisuals::LIGHT
+            &LIGHT_VISUALS
         }
     }
 
@@ -101,6 +198,9 @@ impl JsonTreeStyle {
 }
 
 /// Colors for JSON syntax highlighting, and search match highlighting.
+///
+/// Fields are added as new kinds of values are supported, so construct it with `..Default::default()`
+/// (or `..JsonTreeVisuals::LIGHT`) rather than listing every field.
 #[derive(Debug, Clone, Hash)]
 pub struct JsonTreeVisuals {
     pub object_key_color: Color32,
@@ -109,11 +209,35 @@ pub struct JsonTreeVisuals {
     pub bool_color: Color32,
     pub number_color: Color32,
     pub string_color: Color32,
+    pub bytes_color: Color32,
+    /// The color for [`BaseValueType::Other`] values whose kind has no entry in [`JsonTreeVisuals::other_values`].
+    pub other_color: Color32,
+    /// The colors and quoting of [`BaseValueType::Other`] values, by kind.
+    pub other_values: BTreeMap<&'static str, OtherValueVisuals>,
+    /// The color for comments, see [`ToJsonTreeValue::comments`](crate::value::ToJsonTreeValue::comments).
+    pub comment_color: Color32,
+    /// The color for object keys that appear more than once within the same object, see [`JsonPointerSegment::DuplicateKey`](crate::pointer::JsonPointerSegment::DuplicateKey).
+    pub warning_color: Color32,
     pub highlight_color: Color32,
+    /// The background color of values that changed since the previous frame,
+    /// see [`JsonTreeStyle::change_flash_duration`](method@JsonTreeStyle::change_flash_duration).
+    pub changed_color: Color32,
+    /// The background color of values that were added, in a [`JsonDiffTree`](crate::JsonDiffTree).
+    pub diff_added_color: Color32,
+    /// The background color of values that were removed, in a [`JsonDiffTree`](crate::JsonDiffTree).
+    pub diff_removed_color: Color32,
+    /// The background color of values that were modified, in a [`JsonDiffTree`](crate::JsonDiffTree).
+    pub diff_modified_color: Color32,
+    /// The background color of the indices of array elements that were moved, in a [`JsonDiffTree`](crate::JsonDiffTree).
+    pub diff_moved_color: Color32,
     /// The color for array brackets, object braces, colons and commas.
     pub punctuation_color: Color32,
 }
 
+// `JsonTreeVisuals` has a destructor, so references to its constants are not promoted to `'static`.
+static DARK_VISUALS: JsonTreeVisuals = JsonTreeVisuals::DARK;
+static LIGHT_VISUALS: JsonTreeVisuals = JsonTreeVisuals::LIGHT;
+
 impl Default for JsonTreeVisuals {
     fn default() -> Self {
         Self::DARK
@@ -128,7 +252,17 @@ impl JsonTreeVisuals {
         bool_color: Color32::from_rgb(103, 154, 209),
         number_color: Color32::from_rgb(181, 199, 166),
         string_color: Color32::from_rgb(194, 146, 122),
+        bytes_color: Color32::from_rgb(215, 186, 125),
+        other_color: Color32::from_rgb(78, 201, 176),
+        other_values: BTreeMap::new(),
+        comment_color: Color32::from_rgb(106, 153, 85),
+        warning_color: Color32::from_rgb(255, 140, 0),
         highlight_color: Color32::from_rgba_premultiplied(72, 72, 72, 50),
+        changed_color: Color32::from_rgba_premultiplied(110, 90, 20, 110),
+        diff_added_color: Color32::from_rgba_premultiplied(35, 90, 45, 140),
+        diff_removed_color: Color32::from_rgba_premultiplied(110, 35, 35, 140),
+        diff_modified_color: Color32::from_rgba_premultiplied(95, 80, 20, 140),
+        diff_moved_color: Color32::from_rgba_premultiplied(30, 65, 110, 140),
         punctuation_color: Color32::from_gray(140),
     };
 
@@ -139,18 +273,370 @@ impl JsonTreeVisuals {
         bool_color: Color32::from_rgb(40, 34, 245),
         number_color: Color32::from_rgb(1, 97, 63),
         string_color: Color32::from_rgb(149, 38, 31),
+        bytes_color: Color32::from_rgb(121, 94, 38),
+        other_color: Color32::from_rgb(38, 127, 153),
+        other_values: BTreeMap::new(),
+        comment_color: Color32::from_rgb(0, 128, 0),
+        warning_color: Color32::from_rgb(196, 94, 0),
         highlight_color: Color32::from_rgba_premultiplied(181, 213, 251, 255),
+        changed_color: Color32::from_rgba_premultiplied(255, 236, 153, 255),
+        diff_added_color: Color32::from_rgba_premultiplied(204, 255, 216, 255),
+        diff_removed_color: Color32::from_rgba_premultiplied(255, 215, 213, 255),
+        diff_modified_color: Color32::from_rgba_premultiplied(255, 241, 190, 255),
+        diff_moved_color: Color32::from_rgba_premultiplied(207, 226, 255, 255),
         punctuation_color: Color32::from_gray(70),
     };
 
+    /// Sets the color and quoting of [`BaseValueType::Other`] values of the given kind.
+    ///
+    /// ```rust
+    /// # use egui::Color32;
+    /// # use egui_json_tree::{JsonTreeVisuals, OtherValueVisuals};
+    /// let visuals = JsonTreeVisuals::default().other_value(
+    ///     "uuid",
+    ///     OtherValueVisuals {
+    ///         color: Color32::LIGHT_GREEN,
+    ///         quoted: true,
+    ///     },
+    /// );
+    /// ```
+    pub fn other_value(mut self, kind: &'static str, visuals: OtherValueVisuals) -> Self {
+        self.other_values.insert(kind, visuals);
+        self
+    }
+
     pub fn get_color(&self, base_value_type: &BaseValueType) -> Color32 {
         match base_value_type {
             BaseValueType::Null => self.null_color,
             BaseValueType::Bool => self.bool_color,
             BaseValueType::Number => self.number_color,
             BaseValueType::String => self.string_color,
+            BaseValueType::Bytes => self.bytes_color,
+            BaseValueType::Other(kind) => self
+                .other_values
+                .get(kind)
+                .map_or(self.other_color, |visuals| visuals.color),
         }
     }
+
+    /// Whether values of the given type are shown surrounded by quotes.
+    pub fn is_quoted(&self, base_value_type: &BaseValueType) -> bool {
+        match base_value_type {
+            BaseValueType::String => true,
+            BaseValueType::Other(kind) => self
+                .other_values
+                .get(kind)
+                .is_some_and(|visuals| visuals.quoted),
+            BaseValueType::Null
+            | BaseValueType::Bool
+            | BaseValueType::Number
+            | BaseValueType::Bytes => false,
+        }
+    }
+}
+
+/// The color and quoting of a kind of [`BaseValueType::Other`] value.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct OtherValueVisuals {
+    pub color: Color32,
+    /// Whether values of this kind are shown surrounded by quotes, like strings.
+    pub quoted: bool,
+}
+
+/// Options for how [`BaseValueType::Bytes`] values are shown.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub enum BytesFormat {
+    /// Show bytes as hexadecimal, e.g. `h'0102ff'`.
+    #[default]
+    Hex,
+    /// Show bytes as base64, e.g. `b64'AQL/'`.
+    Base64,
+}
+
+/// Options for how [`BaseValueType::Number`] values are shown,
+/// see [`JsonTreeStyle::number_format`](crate::JsonTreeStyle::number_format).
+///
+/// Numbers are formatted from their displayed text rather than a native number type,
+/// so formatting applies to numbers of any size or precision, e.g. `u128` or arbitrary precision decimals.
+/// Text that is not a decimal number, e.g. `NaN`, is shown as is, as are numbers whose formatted text would exceed
+/// a thousand digits, e.g. `1e1000000000` in fixed notation.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub struct NumberFormat {
+    pub notation: NumberNotation,
+    /// A separator to insert between each group of three digits in the integer part of a number,
+    /// e.g. `Some(',')` shows `1234567.5` as `1,234,567.5`. Does not apply to scientific or hexadecimal notation.
+    pub thousands_separator: Option<char>,
+}
+
+/// The notation to show numbers in.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub enum NumberNotation {
+    /// Show numbers as displayed by the [`ToJsonTreeValue`](crate::value::ToJsonTreeValue) implementation.
+    #[default]
+    AsDisplayed,
+    /// Show numbers with a fixed number of decimal places, e.g. `Fixed(2)` shows `3.14159` as `3.14`.
+    Fixed(usize),
+    /// Show numbers in scientific notation with a fixed number of decimal places, e.g. `Scientific(2)` shows `123456` as `1.23e5`.
+    Scientific(usize),
+    /// Show integers in hexadecimal, e.g. `255` as `0xff`. Numbers with a fractional part are shown as displayed.
+    Hex,
+}
+
+impl NumberFormat {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Override the notation to show numbers in. Defaults to [`NumberNotation::AsDisplayed`].
+    pub fn notation(mut self, notation: NumberNotation) -> Self {
+        self.notation = notation;
+        self
+    }
+
+    /// Insert a separator between each group of three digits in the integer part of a number.
+    /// Defaults to no separator.
+    pub fn thousands_separator(mut self, separator: char) -> Self {
+        self.thousands_separator = Some(separator);
+        self
+    }
+
+    /// Formats the displayed text of a number.
+    pub(crate) fn format(&self, number_str: &str) -> String {
+        let Some(decimal) = Decimal::parse(number_str) else {
+            return number_str.to_string();
+        };
+
+        match self.notation {
+            NumberNotation::AsDisplayed => self.group_thousands(number_str),
+            NumberNotation::Fixed(places) => match decimal.to_fixed(places) {
+                Some(fixed) => self.group_thousands(&fixed),
+                None => self.group_thousands(number_str),
+            },
+            NumberNotation::Scientific(places) => decimal
+                .to_scientific(places)
+                .unwrap_or_else(|| self.group_thousands(number_str)),
+            NumberNotation::Hex => decimal
+                .to_hex()
+                .unwrap_or_else(|| self.group_thousands(number_str)),
+        }
+    }
+
+    fn group_thousands(&self, number_str: &str) -> String {
+        let Some(separator) = self.thousands_separator else {
+            return number_str.to_string();
+        };
+
+        let (sign, unsigned) = match number_str.strip_prefix(['-', '+']) {
+            Some(unsigned) => number_str.split_at(number_str.len() - unsigned.len()),
+            None => ("", number_str),
+        };
+        let int_len = unsigned.bytes().take_while(u8::is_ascii_digit).count();
+        let (int_part, rest) = unsigned.split_at(int_len);
+
+        let mut grouped = String::from(sign);
+        for (idx, digit) in int_part.chars().enumerate() {
+            if idx > 0 && (int_len - idx) % 3 == 0 {
+                grouped.push(separator);
+            }
+            grouped.push(digit);
+        }
+        grouped.push_str(rest);
+        grouped
+    }
+}
+
+/// A decimal number parsed from text, with the value `digits * 10^exponent`.
+struct Decimal {
+    negative: bool,
+    /// Decimal digits without leading or trailing zeros. Empty for zero.
+    digits: Vec<u8>,
+    exponent: i64,
+}
+
+impl Decimal {
+    /// Hexadecimal conversion of integers with more digits than this is skipped, since it is quadratic in the number of digits.
+    const MAX_HEX_DIGITS: usize = 1000;
+    /// Fixed and scientific notation with more digits than this is skipped, since the text of a number is built in one frame.
+    const MAX_NOTATION_DIGITS: usize = 1000;
+
+    fn parse(s: &str) -> Option<Self> {
+        let (negative, s) = match s.strip_prefix('-') {
+            Some(s) => (true, s),
+            None => (false, s.strip_prefix('+').unwrap_or(s)),
+        };
+        let (mantissa, exponent) = match s.split_once(['e', 'E']) {
+            Some((mantissa, exponent)) => (mantissa, exponent.parse::<i64>().ok()?),
+            None => (s, 0),
+        };
+        let (int_part, frac_part) = mantissa.split_once('.').unwrap_or((mantissa, ""));
+        if int_part.is_empty() && frac_part.is_empty()
+            || !int_part
+                .bytes()
+                .chain(frac_part.bytes())
+                .all(|b| b.is_ascii_digit())
+        {
+            return None;
+        }
+
+        let mut digits: Vec<u8> = int_part
+            .bytes()
+            .chain(frac_part.bytes())
+            .map(|b| b - b'0')
+            .skip_while(|d| *d == 0)
+            .collect();
+        let mut exponent = exponent.checked_sub(frac_part.len() as i64)?;
+        while digits.last() == Some(&0) {
+            digits.pop();
+            exponent = exponent.checked_add(1)?;
+        }
+
+        Some(Self {
+            negative,
+            digits,
+            exponent,
+        })
+    }
+
+    fn sign(&self, digits: &[u8]) -> &'static str {
+        if self.negative && digits.iter().any(|d| *d != 0) {
+            "-"
+        } else {
+            ""
+        }
+    }
+
+    fn to_fixed(&self, places: usize) -> Option<String> {
+        if places > Self::MAX_NOTATION_DIGITS {
+            return None;
+        }
+        let target_exponent = -(places as i64);
+        let mut digits = self.digits.clone();
+        if self.exponent >= target_exponent {
+            let padding = usize::try_from(self.exponent.checked_sub(target_exponent)?).ok()?;
+            if digits.len().saturating_add(padding) > Self::MAX_NOTATION_DIGITS {
+                return None;
+            }
+            digits.resize(digits.len() + padding, 0);
+        } else {
+            let excess = usize::try_from(target_exponent.checked_sub(self.exponent)?).ok()?;
+            digits = round_digits(
+                &digits,
+                digits.len().saturating_sub(excess),
+                excess <= digits.len(),
+            );
+        }
+
+        if digits.len() <= places {
+            let mut padded = vec![0; places + 1 - digits.len()];
+            padded.extend(digits);
+            digits = padded;
+        }
+
+        let (int_digits, frac_digits) = digits.split_at(digits.len() - places);
+        let mut out = String::from(self.sign(&digits));
+        out.extend(int_digits.iter().map(|d| char::from(b'0' + d)));
+        if places > 0 {
+            out.push('.');
+            out.extend(frac_digits.iter().map(|d| char::from(b'0' + d)));
+        }
+        Some(out)
+    }
+
+    fn to_scientific(&self, places: usize) -> Option<String> {
+        if places > Self::MAX_NOTATION_DIGITS {
+            return None;
+        }
+        let significant = places + 1;
+        let (mut digits, mut exponent) = if self.digits.is_empty() {
+            (vec![0], 0)
+        } else {
+            let leading_exponent = i64::try_from(self.digits.len() - 1).ok()?;
+            (
+                self.digits.clone(),
+                self.exponent.checked_add(leading_exponent)?,
+            )
+        };
+
+        if digits.len() > significant {
+            digits = round_digits(&digits, significant, true);
+            if digits.len() > significant {
+                // Rounding carried over into a new leading digit, e.g. `9.99` to `10.0`.
+                digits.pop();
+                exponent = exponent.checked_add(1)?;
+            }
+        }
+        digits.resize(significant, 0);
+
+        let mut out = String::from(self.sign(&digits));
+        out.push(char::from(b'0' + digits[0]));
+        if places > 0 {
+            out.push('.');
+            out.extend(digits[1..].iter().map(|d| char::from(b'0' + d)));
+        }
+        out.push_str(&format!("e{exponent}"));
+        Some(out)
+    }
+
+    fn to_hex(&self) -> Option<String> {
+        if self.exponent < 0 || self.digits.len() + self.exponent as usize > Self::MAX_HEX_DIGITS {
+            return None;
+        }
+
+        let mut decimal = self.digits.clone();
+        decimal.resize(decimal.len() + self.exponent as usize, 0);
+
+        // Repeatedly divide the decimal digits by 16, collecting the remainders as hexadecimal digits.
+        let mut hex_digits = vec![];
+        while !decimal.is_empty() {
+            let mut quotient = Vec::with_capacity(decimal.len());
+            let mut remainder = 0u32;
+            for digit in &decimal {
+                let n = remainder * 10 + *digit as u32;
+                if !quotient.is_empty() || n >= 16 {
+                    quotient.push((n / 16) as u8);
+                }
+                remainder = n % 16;
+            }
+            hex_digits.push(char::from_digit(remainder, 16)?);
+            decimal = quotient;
+        }
+        if hex_digits.is_empty() {
+            hex_digits.push('0');
+        }
+
+        let sign = if self.negative && !self.digits.is_empty() {
+            "-"
+        } else {
+            ""
+        };
+        Some(format!(
+            "{sign}0x{}",
+            hex_digits.iter().rev().collect::<String>()
+        ))
+    }
+}
+
+/// Keeps the first `keep` digits, rounding half up based on the next digit if `has_round_digit`.
+/// The result may have one more digit than `keep` if rounding carries over, e.g. `99` to `100`.
+fn round_digits(digits: &[u8], keep: usize, has_round_digit: bool) -> Vec<u8> {
+    let mut kept = digits[..keep].to_vec();
+    if has_round_digit && digits.get(keep).is_some_and(|d| *d >= 5) {
+        let mut idx = kept.len();
+        loop {
+            if idx == 0 {
+                kept.insert(0, 1);
+                break;
+            }
+            idx -= 1;
+            if kept[idx] == 9 {
+                kept[idx] = 0;
+            } else {
+                kept[idx] += 1;
+                break;
+            }
+        }
+    }
+    kept
 }
 
 /// Container for text wrapping configurations of JSON elements in various scenarios and visual states.
@@ -190,3 +676,100 @@ pub enum JsonTreeMaxWidth {
     Points(f32),
     UiAvailableWidth,
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn number_format_as_displayed_with_thousands_separator() {
+        let format = NumberFormat::new().thousands_separator(',');
+        assert_eq!(format.format("1234567.891"), "1,234,567.891");
+        assert_eq!(format.format("-123456"), "-123,456");
+        assert_eq!(format.format("999"), "999");
+        assert_eq!(format.format("1.5e10"), "1.5e10");
+        assert_eq!(
+            format.format("340282366920938463463374607431768211455"),
+            "340,282,366,920,938,463,463,374,607,431,768,211,455"
+        );
+        assert_eq!(format.format("NaN"), "NaN");
+    }
+
+    #[test]
+    fn number_format_fixed() {
+        let format = |places, s| {
+            NumberFormat::new()
+                .notation(NumberNotation::Fixed(places))
+                .format(s)
+        };
+        assert_eq!(format(2, "3.14159"), "3.14");
+        assert_eq!(format(2, "2.675"), "2.68");
+        assert_eq!(format(2, "9.999"), "10.00");
+        assert_eq!(format(0, "0.5"), "1");
+        assert_eq!(format(2, "0.001"), "0.00");
+        assert_eq!(format(2, "-0.001"), "0.00");
+        assert_eq!(format(3, "-42"), "-42.000");
+        assert_eq!(format(1, "1.5e3"), "1500.0");
+        assert_eq!(
+            format(1, "12345678901234567890.25"),
+            "12345678901234567890.3"
+        );
+        assert_eq!(
+            NumberFormat::new()
+                .notation(NumberNotation::Fixed(1))
+                .thousands_separator(' ')
+                .format("1234.56"),
+            "1 234.6"
+        );
+        assert_eq!(format(2, "1e1000000000"), "1e1000000000");
+        assert_eq!(
+            format(2, "10e9223372036854775807"),
+            "10e9223372036854775807"
+        );
+        assert_eq!(format(2, "1e-9223372036854775808"), "0.00");
+        assert_eq!(format(usize::MAX, "1"), "1");
+    }
+
+    #[test]
+    fn number_format_scientific() {
+        let format = |places, s| {
+            NumberFormat::new()
+                .notation(NumberNotation::Scientific(places))
+                .format(s)
+        };
+        assert_eq!(format(2, "123456"), "1.23e5");
+        assert_eq!(format(2, "0.000999"), "9.99e-4");
+        assert_eq!(format(1, "9.96"), "1.0e1");
+        assert_eq!(format(0, "-0.05"), "-5e-2");
+        assert_eq!(format(2, "0"), "0.00e0");
+        assert_eq!(format(3, "1.5E+300"), "1.500e300");
+        assert_eq!(
+            format(2, "10e9223372036854775807"),
+            "10e9223372036854775807"
+        );
+        assert_eq!(
+            format(2, "99.9e9223372036854775806"),
+            "9.99e9223372036854775807"
+        );
+        assert_eq!(
+            format(1, "9.99e9223372036854775807"),
+            "9.99e9223372036854775807"
+        );
+        assert_eq!(format(usize::MAX, "1"), "1");
+    }
+
+    #[test]
+    fn number_format_hex() {
+        let format = |s| NumberFormat::new().notation(NumberNotation::Hex).format(s);
+        assert_eq!(format("255"), "0xff");
+        assert_eq!(format("-16"), "-0x10");
+        assert_eq!(format("0"), "0x0");
+        assert_eq!(format("1e3"), "0x3e8");
+        assert_eq!(
+            format("340282366920938463463374607431768211455"),
+            "0xffffffffffffffffffffffffffffffff"
+        );
+        assert_eq!(format("1.5"), "1.5");
+        assert_eq!(format("1e100000"), "1e100000");
+    }
+}
@@ -1,38 +1,46 @@
 use crate::{
     DefaultExpand, JsonTreeResponse, JsonTreeStyle,
+    context_menu::{ContextMenuContext, JsonTreeContextMenu},
     node::JsonTreeNode,
     render::{JsonTreeRenderer, RenderContext},
     value::ToJsonTreeValue,
 };
 use egui::{AsId, Id, Ui};
 
-pub(crate) struct JsonTreeConfig<'a, T: ToJsonTreeValue> {
+pub(crate) struct JsonTreeConfig<'a, T: ToJsonTreeValue + ?Sized> {
     pub(crate) style: Option<JsonTreeStyle>,
     pub(crate) default_expand: Option<DefaultExpand<'a>>,
     pub(crate) auto_reset_expanded: bool,
+    pub(crate) cache_search_results: bool,
     pub(crate) renderer: JsonTreeRenderer<'a, T>,
+    pub(crate) context_menu: JsonTreeContextMenu<'a, T>,
+    /// Whether an array/object is expanded by default when `default_expand` is not set, e.g. the changed nodes of a [`JsonDiffTree`](crate::JsonDiffTree).
+    pub(crate) default_expanded: Option<fn(&T) -> bool>,
 }
 
-impl<T: ToJsonTreeValue> Default for JsonTreeConfig<'_, T> {
+impl<T: ToJsonTreeValue + ?Sized> Default for JsonTreeConfig<'_, T> {
     fn default() -> Self {
         Self {
             style: Default::default(),
             default_expand: Default::default(),
             auto_reset_expanded: true,
+            cache_search_results: false,
             renderer: Default::default(),
+            context_menu: Default::default(),
+            default_expanded: None,
         }
     }
 }
 
 /// An interactive JSON tree visualiser.
 #[must_use = "You should call .show()"]
-pub struct JsonTree<'a, T: ToJsonTreeValue> {
+pub struct JsonTree<'a, T: ToJsonTreeValue + ?Sized> {
     pub(crate) id: Id,
     pub(crate) value: &'a T,
     pub(crate) config: JsonTreeConfig<'a, T>,
 }
 
-impl<'a, T: ToJsonTreeValue> JsonTree<'a, T> {
+impl<'a, T: ToJsonTreeValue + ?Sized> JsonTree<'a, T> {
     /// Creates a new [`JsonTree`].
     /// `id` must be a globally unique identifier.
     pub fn new(id: impl AsId, value: &'a T) -> Self {
@@ -63,6 +71,19 @@ impl<'a, T: ToJsonTreeValue> JsonTree<'a, T> {
         self
     }
 
+    /// If enabled, the paths that match the search term of [`DefaultExpand::SearchResults`] or [`DefaultExpand::SearchResultsOrAll`]
+    /// are cached, rather than searched for on every frame. The tree is searched again only when the search term,
+    /// the shown value (by address) or the number or bytes format changes, or when
+    /// [`JsonTreeResponse::reset_expanded`](crate::JsonTreeResponse::reset_expanded) is called.
+    ///
+    /// This is useful for large values that are not modified in place, e.g. a `lazy_value::LazyValue`
+    /// of a large file. If the value is modified in place, call `reset_expanded` afterwards so that new matches are revealed.
+    /// Defaults to disabled.
+    pub fn cache_search_results(mut self, cache_search_results: bool) -> Self {
+        self.config.cache_search_results = cache_search_results;
+        self
+    }
+
     /// A convenience method for conditionally registering a custom rendering hook.
     /// See [`JsonTree::on_render`].
     pub fn on_render_if(
@@ -92,9 +113,8 @@ impl<'a, T: ToJsonTreeValue> JsonTree<'a, T> {
     /// You may also call [`render_ctx.render_default(ui)`](crate::render::DefaultRender) on this argument
     /// (or on any of the render contexts contained within its enum variants) to render as normal.
     ///
-    /// See [`copy_to_clipboard.rs`](https://github.com/dmackdev/egui_json_tree/blob/main/demo/src/apps/copy_to_clipboard.rs)
-    /// and [`editor.rs`](https://github.com/dmackdev/egui_json_tree/blob/main/demo/src/apps/editor.rs)
-    /// from the demo for detailed examples and usage.
+    /// See [`editor.rs`](https://github.com/dmackdev/egui_json_tree/blob/main/egui_json_tree/src/editor.rs),
+    /// the implementation of [`JsonTreeEditor`](crate::JsonTreeEditor), for detailed examples and usage.
     pub fn on_render(
         mut self,
         render_hook: impl FnMut(&mut Ui, RenderContext<'a, '_, T>) + 'a,
@@ -103,6 +123,22 @@ impl<'a, T: ToJsonTreeValue> JsonTree<'a, T> {
         self
     }
 
+    /// Append custom items to the built-in context menu, after the default copy actions.
+    ///
+    /// The built-in context menu must be enabled via [`JsonTreeStyle::context_menu`] for this hook to be called.
+    ///
+    /// The [`ContextMenuContext`] argument to the hook provides the JSON value that the context menu was opened for, and a JSON pointer to it.
+    ///
+    /// See [`copy_to_clipboard.rs`](https://github.com/dmackdev/egui_json_tree/blob/main/demo/src/apps/copy_to_clipboard.rs)
+    /// from the demo for example usage.
+    pub fn on_context_menu(
+        mut self,
+        context_menu_hook: impl FnMut(&mut Ui, ContextMenuContext<'a, '_, T>) + 'a,
+    ) -> Self {
+        *self.config.context_menu.hook.get_mut() = Some(Box::new(context_menu_hook));
+        self
+    }
+
     /// Show the JSON tree visualisation within the `Ui`.
     pub fn show(self, ui: &mut Ui) -> JsonTreeResponse {
         JsonTreeNode::show(self, ui)
@@ -257,6 +293,73 @@ mod tests {
         assert_eq!(harness.query_all_by_role(Role::Label).count(), 25);
     }
 
+    #[test]
+    fn render_object_with_search_results_reveals_matches_added_in_place() {
+        for cache_search_results in [false, true] {
+            let mut harness = Harness::new_ui_state(
+                |ui, value: &mut Value| {
+                    JsonTree::new("id", &*value)
+                        .default_expand(DefaultExpand::SearchResults("needle"))
+                        .cache_search_results(cache_search_results)
+                        .show(ui);
+                },
+                json!({ "a": { "b": "needle" } }),
+            );
+
+            // Run a frame with the state at its final address, as the harness is moved after running the first frame.
+            harness.run();
+            assert!(harness.query_by_label("\"b\"").is_some());
+
+            harness.state_mut()["c"] = json!({ "d": "needle" });
+            harness.run();
+            // A cached search is not repeated for a value modified in place, until it is reset.
+            assert_eq!(
+                harness.query_by_label("\"d\"").is_some(),
+                !cache_search_results
+            );
+        }
+    }
+
+    #[test]
+    fn render_object_with_context_menu_shows_copy_actions_and_custom_items() {
+        let mut harness = Harness::new_ui(|ui| {
+            JsonTree::new("id", &*OBJECT)
+                .default_expand(DefaultExpand::All)
+                .style(JsonTreeStyle::new().context_menu(true))
+                .on_context_menu(|ui, _| {
+                    let _ = ui.button("Custom item");
+                })
+                .show(ui);
+        });
+        assert!(harness.query_by_label("Copy JSON pointer").is_none());
+
+        harness.get_by_label("\"baz\"").click_secondary();
+        harness.run();
+        assert!(harness.query_by_label("Copy JSON pointer").is_some());
+        assert!(harness.query_by_label("Copy JSONPath").is_some());
+        assert!(harness.query_by_label("Copy key").is_some());
+        assert!(harness.query_by_label("Copy value").is_some());
+        assert!(harness.query_by_label("Copy as compact JSON").is_none());
+        assert!(harness.query_by_label("Custom item").is_some());
+    }
+
+    #[test]
+    fn render_object_and_export_visible_tree_as_text() {
+        let harness = Harness::new_ui_state(
+            |ui, text: &mut String| {
+                let response = JsonTree::new("id", &*OBJECT)
+                    .default_expand(DefaultExpand::ToLevel(0))
+                    .show(ui);
+                *text = response.to_text(ui, &*OBJECT);
+            },
+            String::new(),
+        );
+        assert_eq!(
+            harness.state(),
+            "{\n  \"bar\": {...}\n  \"baz\": null\n  \"foo\": [...]\n}\n"
+        );
+    }
+
     fn query_all_collapsing_headers<'a, S>(
         harness: &'a Harness<'_, S>,
     ) -> impl Iterator<Item = Node<'a>> {
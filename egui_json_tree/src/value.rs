@@ -6,10 +6,39 @@
 //! For reference, see the provided [`ToJsonTreeValue`] implementations in [`value.rs`](../../src/egui_json_tree/value.rs.html) for the following JSON types:
 //! - `serde_json::Value`
 //! - `simd_json::owned::Value`
+//! - `simd_json::borrowed::Value`
+//! - `sonic_rs::Value`
+//! - `yaml_rust2::Yaml`
+//! - `toml::Value`
+//! - `ciborium::Value`
+//! - `rmpv::Value`
+//!
+//! Any [`serde::Serialize`](https://docs.rs/serde/latest/serde/trait.Serialize.html) type can also be visualised via the
+//! `SerdeValue` adapter in the `serde_value` module, when the `serde` feature is enabled.
+//! JSON5 documents parsed by `json5format` can be visualised via the `Json5Value` type in the `json5_value` module,
+//! when the `json5` feature is enabled.
+//!
+//! Some of these types can represent values that have no direct equivalent in JSON, which are mapped as follows:
+//! - YAML map keys that are not strings are represented by [`JsonPointerSegment::NonStringKey`],
+//!   displaying scalar keys as-is and complex keys (sequences and mappings) as compact JSON.
+//! - YAML tags, e.g. `!Ref foo`, are not kept by `yaml_rust2`'s loader, so tagged values are shown as their untagged values.
+//!   Unresolved YAML aliases are shown by their anchor Id as [`BaseValueType::Other`]`("alias")`.
+//! - TOML datetimes are represented as strings, using their TOML text representation.
+//! - CBOR and MessagePack map keys that are not strings, e.g. integers or byte strings,
+//!   are represented by [`JsonPointerSegment::NonStringKey`] in the same way as YAML map keys.
+//! - CBOR tagged values, e.g. `1(1363896240)`, are represented as an object with a single entry, keyed by `tag(1)`.
+//! - CBOR byte strings, and MessagePack binary and extension values, are represented as [`BaseValueType::Bytes`].
+
+use std::{
+    borrow::Cow,
+    fmt::{self, Display, Write as _},
+    hash::{Hash, Hasher},
+    ops::Range,
+};
 
-use std::fmt::Display;
+use ref_cast::RefCast;
 
-use crate::pointer::JsonPointerSegment;
+use crate::{pointer::JsonPointerSegment, serialize::JsonSerializer};
 /// Representation of JSON values for presentation purposes.
 pub enum JsonTreeValue<'a, T: ?Sized> {
     /// Representation for a non-recursive JSON value:
@@ -27,11 +56,92 @@ pub enum JsonTreeValue<'a, T: ?Sized> {
 
 /// The type of a non-recursive JSON value.
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[non_exhaustive]
 pub enum BaseValueType {
     Null,
     Bool,
     Number,
     String,
+    /// A byte string.
+    ///
+    /// The display value should write the bytes as hexadecimal, or as base64 when formatted with the alternate flag (`{:#}`),
+    /// so that they can be shown according to [`JsonTreeStyle::bytes_format`](crate::JsonTreeStyle::bytes_format).
+    /// [`DisplayBytes`] implements this for any byte string.
+    Bytes,
+    /// A custom kind of non-recursive value that is not part of JSON, e.g. a date, UUID or decimal,
+    /// identified by a name such as `"uuid"`.
+    ///
+    /// The color and quoting of each kind can be configured via [`JsonTreeVisuals::other_values`](crate::JsonTreeVisuals::other_values).
+    Other(&'static str),
+}
+
+/// Displays a byte string, e.g. a `Vec<u8>`, in the diagnostic notation of [RFC 8949](https://www.rfc-editor.org/rfc/rfc8949.html#section-8),
+/// as hexadecimal by default (e.g. `h'0102ff'`), or as base64 when formatted with the alternate flag (e.g. `b64'AQL/'`).
+///
+/// Use this as the display value of a [`BaseValueType::Bytes`] value.
+///
+/// ```rust
+/// # use egui_json_tree::value::DisplayBytes;
+/// let bytes = DisplayBytes::new(&[0x01, 0x02, 0xff]);
+/// assert_eq!(format!("{bytes}"), "h'0102ff'");
+/// assert_eq!(format!("{bytes:#}"), "b64'AQL/'");
+/// ```
+#[derive(RefCast)]
+#[repr(transparent)]
+pub struct DisplayBytes<B: AsRef<[u8]>>(B);
+
+impl<B: AsRef<[u8]>> DisplayBytes<B> {
+    pub fn new(bytes: &B) -> &Self {
+        Self::ref_cast(bytes)
+    }
+}
+
+impl<B: AsRef<[u8]>> PartialEq for DisplayBytes<B> {
+    fn eq(&self, other: &Self) -> bool {
+        self.0.as_ref() == other.0.as_ref()
+    }
+}
+
+impl<B: AsRef<[u8]>> Hash for DisplayBytes<B> {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.0.as_ref().hash(state);
+    }
+}
+
+impl<B: AsRef<[u8]>> Display for DisplayBytes<B> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if f.alternate() {
+            f.write_str("b64'")?;
+            write_base64(f, self.0.as_ref())?;
+        } else {
+            f.write_str("h'")?;
+            for byte in self.0.as_ref() {
+                write!(f, "{byte:02x}")?;
+            }
+        }
+        f.write_str("'")
+    }
+}
+
+/// Writes the bytes as standard base64 with padding, as defined in [RFC 4648](https://www.rfc-editor.org/rfc/rfc4648#section-4).
+fn write_base64(f: &mut fmt::Formatter<'_>, bytes: &[u8]) -> fmt::Result {
+    const ALPHABET: &[u8; 64] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+
+    for chunk in bytes.chunks(3) {
+        let n = chunk
+            .iter()
+            .enumerate()
+            .fold(0u32, |n, (idx, byte)| n | (*byte as u32) << (16 - 8 * idx));
+        for idx in 0..4 {
+            if idx <= chunk.len() {
+                let sextet = (n >> (18 - 6 * idx)) & 0x3f;
+                f.write_char(ALPHABET[sextet as usize] as char)?;
+            } else {
+                f.write_char('=')?;
+            }
+        }
+    }
+    Ok(())
 }
 
 /// The type of a recursive JSON value.
@@ -47,6 +157,96 @@ pub trait ToJsonTreeValue {
     fn to_json_tree_value(&self) -> JsonTreeValue<'_, Self>;
     /// Returns whether this JSON value is expandable, i.e. whether it is an object or an array.
     fn is_expandable(&self) -> bool;
+    /// Serializes this JSON value to a JSON string, either pretty-printed or compact.
+    ///
+    /// This is used by the built-in context menu (see [`JsonTreeStyle::context_menu`](crate::JsonTreeStyle::context_menu)) to copy values.
+    /// The default implementation uses a [`JsonSerializer`], which works for any [`ToJsonTreeValue`].
+    /// Override this if your JSON type provides its own serializer.
+    fn to_json_string(&self, pretty: bool) -> String {
+        if pretty {
+            JsonSerializer::pretty().to_string(self)
+        } else {
+            JsonSerializer::new().to_string(self)
+        }
+    }
+    /// Returns the comments attached to this JSON value, e.g. when parsed from JSONC or JSON5.
+    ///
+    /// Comments are rendered in [`JsonTreeVisuals::comment_color`](crate::JsonTreeVisuals::comment_color) alongside the array index or object key of the value.
+    /// The default implementation returns no comments.
+    fn comments(&self) -> JsonTreeComments<'_> {
+        JsonTreeComments::default()
+    }
+    /// Returns the byte range of this value within the source text it was parsed from, if known.
+    ///
+    /// This is exposed by [`RenderContext::span`](crate::render::RenderContext::span), e.g. to jump to the value in a text editor.
+    /// The default implementation returns `None`.
+    fn span(&self) -> Option<Range<usize>> {
+        None
+    }
+    /// Returns the byte range of the key of the object entry at the given index of the [`JsonTreeValue::Expandable`] entries
+    /// of this value, within the source text it was parsed from, if known.
+    ///
+    /// This is exposed by [`RenderPropertyContext::span`](crate::render::RenderPropertyContext::span).
+    /// The default implementation returns `None`.
+    fn key_span(&self, _idx: usize) -> Option<Range<usize>> {
+        None
+    }
+    /// Visits this value and all of its descendants in depth-first order, calling `visitor` with the path to each value relative to this value.
+    ///
+    /// This is used to search the tree. The default implementation walks the tree via [`ToJsonTreeValue::to_json_tree_value`].
+    /// Override this if your JSON type can be walked without converting every value, e.g. by streaming over its source text.
+    fn visit(&self, visitor: &mut dyn FnMut(&[JsonPointerSegment<'_>], JsonTreeVisit<'_>)) {
+        visit_impl(self, &mut vec![], visitor);
+    }
+}
+
+/// A value visited by [`ToJsonTreeValue::visit`].
+#[derive(Clone, Copy)]
+pub enum JsonTreeVisit<'a> {
+    /// A non-recursive JSON value, with its display value and type, as in [`JsonTreeValue::Base`].
+    Base(&'a dyn Display, BaseValueType),
+    /// A recursive JSON value, whose entries are visited next.
+    Expandable(ExpandableType),
+}
+
+fn visit_impl<'a, T: ToJsonTreeValue + ?Sized>(
+    value: &'a T,
+    path_segments: &mut Vec<JsonPointerSegment<'a>>,
+    visitor: &mut dyn FnMut(&[JsonPointerSegment<'_>], JsonTreeVisit<'_>),
+) {
+    match value.to_json_tree_value() {
+        JsonTreeValue::Base(_, display_value, value_type) => {
+            visitor(
+                path_segments,
+                JsonTreeVisit::Base(display_value, value_type),
+            );
+        }
+        JsonTreeValue::Expandable(entries, expandable_type) => {
+            visitor(path_segments, JsonTreeVisit::Expandable(expandable_type));
+            for (property, val) in entries {
+                path_segments.push(property);
+                visit_impl(val, path_segments, visitor);
+                path_segments.pop();
+            }
+        }
+    }
+}
+
+/// Comments attached to a JSON value, e.g. when parsed from JSONC or JSON5.
+///
+/// Each comment is the full text of the comment as it should be shown, including its delimiters, e.g. `// comment` or `/* comment */`.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Hash)]
+pub struct JsonTreeComments<'a> {
+    /// Comments on the lines before the value, rendered above its array index or object key.
+    pub leading: Vec<Cow<'a, str>>,
+    /// A comment after the value on the same line, rendered after the value.
+    pub trailing: Option<Cow<'a, str>>,
+}
+
+impl JsonTreeComments<'_> {
+    pub fn is_empty(&self) -> bool {
+        self.leading.is_empty() && self.trailing.is_none()
+    }
 }
 
 #[cfg(feature = "serde_json")]
@@ -122,3 +322,512 @@ impl ToJsonTreeValue for simd_json::owned::Value {
         )
     }
 }
+
+#[cfg(feature = "simd_json")]
+impl ToJsonTreeValue for simd_json::borrowed::Value<'_> {
+    fn to_json_tree_value(&self) -> JsonTreeValue<'_, Self> {
+        match self {
+            simd_json::BorrowedValue::Static(s) => match s {
+                simd_json::StaticNode::I64(n) => {
+                    JsonTreeValue::Base(self, n, BaseValueType::Number)
+                }
+                simd_json::StaticNode::U64(n) => {
+                    JsonTreeValue::Base(self, n, BaseValueType::Number)
+                }
+                simd_json::StaticNode::F64(n) => {
+                    JsonTreeValue::Base(self, n, BaseValueType::Number)
+                }
+                simd_json::StaticNode::Bool(b) => JsonTreeValue::Base(self, b, BaseValueType::Bool),
+                simd_json::StaticNode::Null => JsonTreeValue::Base(self, self, BaseValueType::Null),
+            },
+            simd_json::BorrowedValue::String(s) => {
+                JsonTreeValue::Base(self, s, BaseValueType::String)
+            }
+            simd_json::BorrowedValue::Array(arr) => JsonTreeValue::Expandable(
+                arr.iter()
+                    .enumerate()
+                    .map(|(idx, elem)| (JsonPointerSegment::Index(idx), elem))
+                    .collect(),
+                ExpandableType::Array,
+            ),
+            simd_json::BorrowedValue::Object(obj) => JsonTreeValue::Expandable(
+                obj.iter()
+                    .map(|(key, val)| (JsonPointerSegment::Key(key), val))
+                    .collect(),
+                ExpandableType::Object,
+            ),
+        }
+    }
+
+    fn is_expandable(&self) -> bool {
+        matches!(
+            self,
+            simd_json::borrowed::Value::Array(_) | simd_json::borrowed::Value::Object(_)
+        )
+    }
+}
+
+#[cfg(feature = "sonic_rs")]
+impl ToJsonTreeValue for sonic_rs::Value {
+    fn to_json_tree_value(&self) -> JsonTreeValue<'_, Self> {
+        use sonic_rs::{JsonContainerTrait, JsonType, JsonValueTrait};
+
+        match self.get_type() {
+            JsonType::Null => JsonTreeValue::Base(self, self, BaseValueType::Null),
+            JsonType::Boolean => JsonTreeValue::Base(self, self, BaseValueType::Bool),
+            JsonType::Number => JsonTreeValue::Base(self, self, BaseValueType::Number),
+            JsonType::String => {
+                JsonTreeValue::Base(self, SonicRsStr::new(self), BaseValueType::String)
+            }
+            JsonType::Array => JsonTreeValue::Expandable(
+                self.as_array()
+                    .into_iter()
+                    .flat_map(|arr| arr.iter())
+                    .enumerate()
+                    .map(|(idx, elem)| (JsonPointerSegment::Index(idx), elem))
+                    .collect(),
+                ExpandableType::Array,
+            ),
+            JsonType::Object => JsonTreeValue::Expandable(
+                self.as_object()
+                    .into_iter()
+                    .flat_map(|obj| obj.iter())
+                    .map(|(key, val)| (JsonPointerSegment::Key(key), val))
+                    .collect(),
+                ExpandableType::Object,
+            ),
+        }
+    }
+
+    fn is_expandable(&self) -> bool {
+        use sonic_rs::JsonValueTrait;
+
+        self.is_array() || self.is_object()
+    }
+}
+
+/// Displays a `sonic_rs::Value` string without quotes or escapes.
+///
+/// `sonic_rs::Value` only exposes its strings as `&str`, which cannot be used as a `&dyn Display`,
+/// and its own `Display` implementation writes strings as quoted JSON.
+#[cfg(feature = "sonic_rs")]
+#[derive(RefCast)]
+#[repr(transparent)]
+struct SonicRsStr(sonic_rs::Value);
+
+#[cfg(feature = "sonic_rs")]
+impl SonicRsStr {
+    fn new(value: &sonic_rs::Value) -> &Self {
+        Self::ref_cast(value)
+    }
+}
+
+#[cfg(feature = "sonic_rs")]
+impl Display for SonicRsStr {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        use sonic_rs::JsonValueTrait;
+
+        f.write_str(self.0.as_str().unwrap_or_default())
+    }
+}
+
+#[cfg(feature = "yaml_rust2")]
+impl ToJsonTreeValue for yaml_rust2::Yaml {
+    fn to_json_tree_value(&self) -> JsonTreeValue<'_, Self> {
+        match self {
+            yaml_rust2::Yaml::Null | yaml_rust2::Yaml::BadValue => {
+                JsonTreeValue::Base(self, &"null", BaseValueType::Null)
+            }
+            yaml_rust2::Yaml::Boolean(b) => JsonTreeValue::Base(self, b, BaseValueType::Bool),
+            yaml_rust2::Yaml::Integer(n) => JsonTreeValue::Base(self, n, BaseValueType::Number),
+            yaml_rust2::Yaml::Real(n) => JsonTreeValue::Base(self, n, BaseValueType::Number),
+            yaml_rust2::Yaml::String(s) => JsonTreeValue::Base(self, s, BaseValueType::String),
+            yaml_rust2::Yaml::Alias(id) => {
+                JsonTreeValue::Base(self, id, BaseValueType::Other("alias"))
+            }
+            yaml_rust2::Yaml::Array(arr) => JsonTreeValue::Expandable(
+                arr.iter()
+                    .enumerate()
+                    .map(|(idx, elem)| (JsonPointerSegment::Index(idx), elem))
+                    .collect(),
+                ExpandableType::Array,
+            ),
+            yaml_rust2::Yaml::Hash(hash) => JsonTreeValue::Expandable(
+                hash.iter()
+                    .map(|(key, val)| (yaml_key_segment(key), val))
+                    .collect(),
+                ExpandableType::Object,
+            ),
+        }
+    }
+
+    fn is_expandable(&self) -> bool {
+        matches!(self, yaml_rust2::Yaml::Array(_) | yaml_rust2::Yaml::Hash(_))
+    }
+}
+
+#[cfg(feature = "yaml_rust2")]
+fn yaml_key_segment(key: &yaml_rust2::Yaml) -> JsonPointerSegment<'_> {
+    match key {
+        yaml_rust2::Yaml::String(s) => JsonPointerSegment::Key(s),
+        yaml_rust2::Yaml::Null => JsonPointerSegment::NonStringKey(&"null"),
+        yaml_rust2::Yaml::Boolean(b) => JsonPointerSegment::NonStringKey(b),
+        yaml_rust2::Yaml::Integer(n) => JsonPointerSegment::NonStringKey(n),
+        yaml_rust2::Yaml::Real(n) => JsonPointerSegment::NonStringKey(n),
+        _ => JsonPointerSegment::NonStringKey(CompactJsonKey::new(key)),
+    }
+}
+
+/// Displays a complex map key, e.g. an array or object, as compact JSON.
+#[cfg(any(feature = "yaml_rust2", feature = "ciborium", feature = "rmpv"))]
+#[derive(RefCast)]
+#[repr(transparent)]
+struct CompactJsonKey<T>(T);
+
+#[cfg(any(feature = "yaml_rust2", feature = "ciborium", feature = "rmpv"))]
+impl<T> CompactJsonKey<T> {
+    fn new(value: &T) -> &Self {
+        Self::ref_cast(value)
+    }
+}
+
+/// Complex keys are compared and hashed by their compact JSON text, as CBOR and MessagePack values do not implement `Hash`.
+#[cfg(any(feature = "yaml_rust2", feature = "ciborium", feature = "rmpv"))]
+impl<T: ToJsonTreeValue> PartialEq for CompactJsonKey<T> {
+    fn eq(&self, other: &Self) -> bool {
+        self.to_string() == other.to_string()
+    }
+}
+
+#[cfg(any(feature = "yaml_rust2", feature = "ciborium", feature = "rmpv"))]
+impl<T: ToJsonTreeValue> Hash for CompactJsonKey<T> {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.to_string().hash(state);
+    }
+}
+
+#[cfg(any(feature = "yaml_rust2", feature = "ciborium", feature = "rmpv"))]
+impl<T: ToJsonTreeValue> Display for CompactJsonKey<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        JsonSerializer::new().write(&self.0, f)
+    }
+}
+
+#[cfg(feature = "toml")]
+impl ToJsonTreeValue for toml::Value {
+    fn to_json_tree_value(&self) -> JsonTreeValue<'_, Self> {
+        match self {
+            toml::Value::Boolean(b) => JsonTreeValue::Base(self, b, BaseValueType::Bool),
+            toml::Value::Integer(n) => JsonTreeValue::Base(self, n, BaseValueType::Number),
+            toml::Value::Float(n) => JsonTreeValue::Base(self, n, BaseValueType::Number),
+            toml::Value::String(s) => JsonTreeValue::Base(self, s, BaseValueType::String),
+            // JSON has no datetime type, so datetimes are shown as strings.
+            toml::Value::Datetime(dt) => JsonTreeValue::Base(self, dt, BaseValueType::String),
+            toml::Value::Array(arr) => JsonTreeValue::Expandable(
+                arr.iter()
+                    .enumerate()
+                    .map(|(idx, elem)| (JsonPointerSegment::Index(idx), elem))
+                    .collect(),
+                ExpandableType::Array,
+            ),
+            toml::Value::Table(table) => JsonTreeValue::Expandable(
+                table
+                    .iter()
+                    .map(|(key, val)| (JsonPointerSegment::Key(key), val))
+                    .collect(),
+                ExpandableType::Object,
+            ),
+        }
+    }
+
+    fn is_expandable(&self) -> bool {
+        matches!(self, toml::Value::Array(_) | toml::Value::Table(_))
+    }
+}
+
+#[cfg(feature = "ciborium")]
+impl ToJsonTreeValue for ciborium::Value {
+    fn to_json_tree_value(&self) -> JsonTreeValue<'_, Self> {
+        match self {
+            ciborium::Value::Null => JsonTreeValue::Base(self, &"null", BaseValueType::Null),
+            ciborium::Value::Bool(b) => JsonTreeValue::Base(self, b, BaseValueType::Bool),
+            ciborium::Value::Integer(n) => {
+                JsonTreeValue::Base(self, CborInteger::new(n), BaseValueType::Number)
+            }
+            ciborium::Value::Float(n) => JsonTreeValue::Base(self, n, BaseValueType::Number),
+            ciborium::Value::Text(s) => JsonTreeValue::Base(self, s, BaseValueType::String),
+            ciborium::Value::Bytes(b) => {
+                JsonTreeValue::Base(self, DisplayBytes::new(b), BaseValueType::Bytes)
+            }
+            ciborium::Value::Array(arr) => JsonTreeValue::Expandable(
+                arr.iter()
+                    .enumerate()
+                    .map(|(idx, elem)| (JsonPointerSegment::Index(idx), elem))
+                    .collect(),
+                ExpandableType::Array,
+            ),
+            ciborium::Value::Map(map) => JsonTreeValue::Expandable(
+                map.iter()
+                    .map(|(key, val)| (cbor_key_segment(key), val))
+                    .collect(),
+                ExpandableType::Object,
+            ),
+            ciborium::Value::Tag(tag, value) => JsonTreeValue::Expandable(
+                vec![(
+                    JsonPointerSegment::NonStringKey(CborTag::new(tag)),
+                    &**value,
+                )],
+                ExpandableType::Object,
+            ),
+            // `ciborium::Value` is non-exhaustive, so future kinds of values are shown as a custom kind.
+            _ => JsonTreeValue::Base(self, &"unknown", BaseValueType::Other("unknown")),
+        }
+    }
+
+    fn is_expandable(&self) -> bool {
+        matches!(
+            self,
+            ciborium::Value::Array(_) | ciborium::Value::Map(_) | ciborium::Value::Tag(..)
+        )
+    }
+}
+
+#[cfg(feature = "ciborium")]
+fn cbor_key_segment(key: &ciborium::Value) -> JsonPointerSegment<'_> {
+    match key {
+        ciborium::Value::Text(s) => JsonPointerSegment::Key(s),
+        ciborium::Value::Null => JsonPointerSegment::NonStringKey(&"null"),
+        ciborium::Value::Bool(b) => JsonPointerSegment::NonStringKey(b),
+        ciborium::Value::Integer(n) => JsonPointerSegment::NonStringKey(CborInteger::new(n)),
+        ciborium::Value::Float(n) => JsonPointerSegment::NonStringKey(FloatKey::new(n)),
+        ciborium::Value::Bytes(b) => JsonPointerSegment::NonStringKey(DisplayBytes::new(b)),
+        _ => JsonPointerSegment::NonStringKey(CompactJsonKey::new(key)),
+    }
+}
+
+/// A floating point map key, which is compared and hashed by its bits, as floats implement neither `Eq` nor `Hash`.
+#[cfg(any(feature = "ciborium", feature = "rmpv"))]
+#[derive(RefCast)]
+#[repr(transparent)]
+struct FloatKey<F>(F);
+
+#[cfg(any(feature = "ciborium", feature = "rmpv"))]
+impl<F> FloatKey<F> {
+    fn new(value: &F) -> &Self {
+        Self::ref_cast(value)
+    }
+}
+
+#[cfg(any(feature = "ciborium", feature = "rmpv"))]
+impl<F: Copy + Into<f64>> PartialEq for FloatKey<F> {
+    fn eq(&self, other: &Self) -> bool {
+        self.0.into().to_bits() == other.0.into().to_bits()
+    }
+}
+
+#[cfg(any(feature = "ciborium", feature = "rmpv"))]
+impl<F: Copy + Into<f64>> Hash for FloatKey<F> {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.0.into().to_bits().hash(state);
+    }
+}
+
+#[cfg(any(feature = "ciborium", feature = "rmpv"))]
+impl<F: Display> Display for FloatKey<F> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.0.fmt(f)
+    }
+}
+
+/// Displays a `ciborium::value::Integer`, which does not implement `Display` itself.
+#[cfg(feature = "ciborium")]
+#[derive(RefCast, PartialEq, Hash)]
+#[repr(transparent)]
+struct CborInteger(ciborium::value::Integer);
+
+#[cfg(feature = "ciborium")]
+impl CborInteger {
+    fn new(value: &ciborium::value::Integer) -> &Self {
+        Self::ref_cast(value)
+    }
+}
+
+#[cfg(feature = "ciborium")]
+impl Display for CborInteger {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", i128::from(self.0))
+    }
+}
+
+/// Displays the number of a CBOR tag as `tag(<number>)`, distinguishing it from integer map keys.
+#[cfg(feature = "ciborium")]
+#[derive(RefCast, PartialEq, Hash)]
+#[repr(transparent)]
+struct CborTag(u64);
+
+#[cfg(feature = "ciborium")]
+impl CborTag {
+    fn new(tag: &u64) -> &Self {
+        Self::ref_cast(tag)
+    }
+}
+
+#[cfg(feature = "ciborium")]
+impl Display for CborTag {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "tag({})", self.0)
+    }
+}
+
+#[cfg(feature = "rmpv")]
+impl ToJsonTreeValue for rmpv::Value {
+    fn to_json_tree_value(&self) -> JsonTreeValue<'_, Self> {
+        match self {
+            rmpv::Value::Nil => JsonTreeValue::Base(self, &"null", BaseValueType::Null),
+            rmpv::Value::Boolean(b) => JsonTreeValue::Base(self, b, BaseValueType::Bool),
+            rmpv::Value::Integer(n) => JsonTreeValue::Base(self, n, BaseValueType::Number),
+            rmpv::Value::F32(n) => JsonTreeValue::Base(self, n, BaseValueType::Number),
+            rmpv::Value::F64(n) => JsonTreeValue::Base(self, n, BaseValueType::Number),
+            rmpv::Value::String(s) => {
+                JsonTreeValue::Base(self, RmpvStr::new(s), BaseValueType::String)
+            }
+            rmpv::Value::Binary(b) => {
+                JsonTreeValue::Base(self, DisplayBytes::new(b), BaseValueType::Bytes)
+            }
+            rmpv::Value::Ext(..) => {
+                JsonTreeValue::Base(self, RmpvExt::new(self), BaseValueType::Bytes)
+            }
+            rmpv::Value::Array(arr) => JsonTreeValue::Expandable(
+                arr.iter()
+                    .enumerate()
+                    .map(|(idx, elem)| (JsonPointerSegment::Index(idx), elem))
+                    .collect(),
+                ExpandableType::Array,
+            ),
+            rmpv::Value::Map(map) => JsonTreeValue::Expandable(
+                map.iter()
+                    .map(|(key, val)| (rmpv_key_segment(key), val))
+                    .collect(),
+                ExpandableType::Object,
+            ),
+        }
+    }
+
+    fn is_expandable(&self) -> bool {
+        matches!(self, rmpv::Value::Array(_) | rmpv::Value::Map(_))
+    }
+}
+
+#[cfg(feature = "rmpv")]
+fn rmpv_key_segment(key: &rmpv::Value) -> JsonPointerSegment<'_> {
+    match key {
+        rmpv::Value::String(s) => match s.as_str() {
+            Some(s) => JsonPointerSegment::Key(s),
+            None => JsonPointerSegment::NonStringKey(RmpvStr::new(s)),
+        },
+        rmpv::Value::Nil => JsonPointerSegment::NonStringKey(&"null"),
+        rmpv::Value::Boolean(b) => JsonPointerSegment::NonStringKey(b),
+        rmpv::Value::Integer(n) => JsonPointerSegment::NonStringKey(RmpvInteger::new(n)),
+        rmpv::Value::F32(n) => JsonPointerSegment::NonStringKey(FloatKey::new(n)),
+        rmpv::Value::F64(n) => JsonPointerSegment::NonStringKey(FloatKey::new(n)),
+        rmpv::Value::Binary(b) => JsonPointerSegment::NonStringKey(DisplayBytes::new(b)),
+        rmpv::Value::Ext(..) => JsonPointerSegment::NonStringKey(RmpvExt::new(key)),
+        rmpv::Value::Array(_) | rmpv::Value::Map(_) => {
+            JsonPointerSegment::NonStringKey(CompactJsonKey::new(key))
+        }
+    }
+}
+
+/// A MessagePack integer map key, as `rmpv::Integer` does not implement `Hash`.
+#[cfg(feature = "rmpv")]
+#[derive(RefCast, PartialEq)]
+#[repr(transparent)]
+struct RmpvInteger(rmpv::Integer);
+
+#[cfg(feature = "rmpv")]
+impl RmpvInteger {
+    fn new(value: &rmpv::Integer) -> &Self {
+        Self::ref_cast(value)
+    }
+}
+
+#[cfg(feature = "rmpv")]
+impl Hash for RmpvInteger {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.0.as_i64().hash(state);
+        self.0.as_u64().hash(state);
+    }
+}
+
+#[cfg(feature = "rmpv")]
+impl Display for RmpvInteger {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.0.fmt(f)
+    }
+}
+
+/// Displays a `rmpv::Utf8String` without quotes or escapes, replacing any invalid UTF-8 sequences.
+///
+/// The `Display` implementation of `rmpv::Utf8String` writes strings quoted and escaped.
+#[cfg(feature = "rmpv")]
+#[derive(RefCast, PartialEq)]
+#[repr(transparent)]
+struct RmpvStr(rmpv::Utf8String);
+
+#[cfg(feature = "rmpv")]
+impl Hash for RmpvStr {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.0.as_bytes().hash(state);
+    }
+}
+
+#[cfg(feature = "rmpv")]
+impl RmpvStr {
+    fn new(value: &rmpv::Utf8String) -> &Self {
+        Self::ref_cast(value)
+    }
+}
+
+#[cfg(feature = "rmpv")]
+impl Display for RmpvStr {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&String::from_utf8_lossy(self.0.as_bytes()))
+    }
+}
+
+/// Displays a MessagePack extension value as `ext(<type>, <bytes>)`, with the bytes displayed as per [`DisplayBytes`].
+#[cfg(feature = "rmpv")]
+#[derive(RefCast, PartialEq)]
+#[repr(transparent)]
+struct RmpvExt(rmpv::Value);
+
+#[cfg(feature = "rmpv")]
+impl Hash for RmpvExt {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        if let rmpv::Value::Ext(ty, data) = &self.0 {
+            ty.hash(state);
+            data.hash(state);
+        }
+    }
+}
+
+#[cfg(feature = "rmpv")]
+impl RmpvExt {
+    fn new(value: &rmpv::Value) -> &Self {
+        Self::ref_cast(value)
+    }
+}
+
+#[cfg(feature = "rmpv")]
+impl Display for RmpvExt {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match &self.0 {
+            rmpv::Value::Ext(ty, data) => {
+                write!(f, "ext({ty}, ")?;
+                Display::fmt(DisplayBytes::new(data), f)?;
+                f.write_str(")")
+            }
+            _ => Ok(()),
+        }
+    }
+}
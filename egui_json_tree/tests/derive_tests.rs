@@ -0,0 +1,108 @@
+#![cfg(feature = "derive")]
+
+use std::{collections::BTreeMap, fmt, net::Ipv4Addr};
+
+use egui_json_tree::{
+    dyn_value::ToDynJsonTreeValue,
+    serialize::JsonSerializer,
+    value::{JsonTreeValue, ToJsonTreeValue},
+};
+
+fn to_json<T: ToDynJsonTreeValue + 'static>(value: &T) -> String {
+    let value: &dyn ToDynJsonTreeValue = value;
+    JsonSerializer::new().to_string(value)
+}
+
+#[derive(ToDynJsonTreeValue)]
+struct Unit;
+
+#[derive(ToDynJsonTreeValue)]
+struct Meters(u32);
+
+#[derive(ToDynJsonTreeValue)]
+struct Point(i32, #[json_tree(skip)] (), i32);
+
+struct Version(u8, u8);
+
+impl fmt::Display for Version {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "v{}.{}", self.0, self.1)
+    }
+}
+
+#[derive(ToDynJsonTreeValue)]
+enum Shape {
+    Empty,
+    #[json_tree(rename = "circle")]
+    Circle(f64),
+    Line(Point, #[json_tree(display)] Version),
+    Rect {
+        width: u8,
+        #[json_tree(skip)]
+        _cached_area: u16,
+        height: u8,
+    },
+}
+
+#[derive(ToDynJsonTreeValue)]
+struct Scene<T> {
+    #[json_tree(rename = "sceneName")]
+    name: String,
+    #[json_tree(skip)]
+    _secret: String,
+    #[json_tree(display)]
+    host: Ipv4Addr,
+    unit: Unit,
+    distance: Meters,
+    shapes: Vec<Shape>,
+    labels: BTreeMap<u8, Option<T>>,
+}
+
+#[test]
+fn derive_structs_and_enums() {
+    let scene = Scene {
+        name: "scene".to_string(),
+        _secret: "hunter2".to_string(),
+        host: Ipv4Addr::LOCALHOST,
+        unit: Unit,
+        distance: Meters(5),
+        shapes: vec![
+            Shape::Empty,
+            Shape::Circle(1.5),
+            Shape::Line(Point(0, (), 1), Version(1, 2)),
+            Shape::Rect {
+                width: 2,
+                _cached_area: 6,
+                height: 3,
+            },
+        ],
+        labels: BTreeMap::from([(1, Some("one")), (2, None)]),
+    };
+
+    assert_eq!(
+        to_json(&scene),
+        concat!(
+            r#"{"sceneName":"scene","host":"127.0.0.1","unit":null,"distance":5,"#,
+            r#""shapes":["Empty",{"circle":1.5},{"Line":[[0,1],"v1.2"]},{"Rect":{"width":2,"height":3}}],"#,
+            r#""labels":{"1":"one","2":null}}"#
+        )
+    );
+}
+
+#[test]
+fn derive_is_expandable_matches_tree_value() {
+    let values: [&dyn ToDynJsonTreeValue; 5] = [
+        &Unit,
+        &Meters(5),
+        &Point(0, (), 0),
+        &Shape::Empty,
+        &Shape::Circle(1.0),
+    ];
+
+    for value in values {
+        assert_eq!(
+            ToJsonTreeValue::is_expandable(value),
+            matches!(value.to_json_tree_value(), JsonTreeValue::Expandable(..))
+        );
+    }
+}
@@ -9,7 +9,14 @@ use serde_json::{Value, json};
 #[cfg(all(feature = "simd_json", not(feature = "serde_json")))]
 use simd_json::{json, owned::Value};
 
-// Keep all keys ordered within objects so rendering order is the same for both serde_json and simd_json.
+#[cfg(all(
+    feature = "sonic_rs",
+    not(feature = "serde_json"),
+    not(feature = "simd_json")
+))]
+use sonic_rs::{Value, json};
+
+// Keep all keys ordered within objects so rendering order is the same for serde_json, simd_json and sonic_rs.
 static OBJECT: LazyLock<Value> = LazyLock::new(|| {
     json!({
       "bar": {
@@ -156,3 +163,69 @@ fn render_object_with_egui_light_theme_should_style_tree_with_light_theme() {
     harness.fit_contents();
     harness.snapshot("light_theme");
 }
+
+/// The same document as `OBJECT`, parsed into a `simd_json::BorrowedValue` which borrows its strings from the input buffer.
+/// Renders identically to `OBJECT`, so the same snapshots are used.
+#[cfg(feature = "simd_json")]
+static BORROWED_OBJECT: LazyLock<simd_json::BorrowedValue<'static>> = LazyLock::new(|| {
+    let buffer = simd_json::to_vec(&*OBJECT).unwrap().leak();
+    simd_json::to_borrowed_value(buffer).unwrap()
+});
+
+#[cfg(feature = "simd_json")]
+#[test]
+fn render_simd_json_borrowed_object_with_default_expand_all() {
+    let mut harness = Harness::new_ui(|ui| {
+        JsonTree::new("id", &*BORROWED_OBJECT)
+            .default_expand(DefaultExpand::All)
+            .show(ui);
+    });
+    harness.fit_contents();
+    harness.snapshot("render_object_with_default_expand_all");
+}
+
+#[cfg(feature = "simd_json")]
+#[test]
+fn render_simd_json_borrowed_object_with_default_expand_none() {
+    let mut harness = Harness::new_ui(|ui| {
+        JsonTree::new("id", &*BORROWED_OBJECT)
+            .default_expand(DefaultExpand::None)
+            .show(ui);
+    });
+    harness.fit_contents();
+    harness.snapshot("render_object_with_default_expand_none");
+}
+
+#[cfg(feature = "simd_json")]
+#[test]
+fn render_simd_json_borrowed_object_search_results() {
+    // Harness::fit_contents seems to cause the tree to wrap, so set a fixed size here.
+    let mut harness = Harness::builder().with_size([400., 400.]).build_ui_state(
+        |ui, default_expand| {
+            JsonTree::new("id", &*BORROWED_OBJECT)
+                .default_expand(*default_expand)
+                .show(ui);
+        },
+        DefaultExpand::SearchResults(""),
+    );
+
+    let mut results = SnapshotResults::new();
+
+    for (idx, search_default_expand) in [
+        DefaultExpand::SearchResults(""),
+        DefaultExpand::SearchResults("g"),
+        DefaultExpand::SearchResults("gr"),
+        DefaultExpand::SearchResults("gre"),
+        DefaultExpand::SearchResults("gree"),
+    ]
+    .into_iter()
+    .enumerate()
+    {
+        *harness.state_mut() = search_default_expand;
+        harness.run();
+
+        let filename = format!("default_expand_search_results/{idx}_{search_default_expand:?}")
+            .replace("\"", "");
+        results.add(harness.try_snapshot(filename));
+    }
+}
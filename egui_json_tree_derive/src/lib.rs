@@ -0,0 +1,420 @@
+//! Derive macro for [`egui_json_tree`](https://docs.rs/egui_json_tree)'s `ToDynJsonTreeValue` trait.
+//!
+//! Enable the `derive` feature of `egui_json_tree` and use the macro re-exported from its `dyn_value` module,
+//! rather than depending on this crate directly. See the `dyn_value` module docs for how types are mapped and the supported attributes.
+use proc_macro::TokenStream;
+use proc_macro2::TokenStream as TokenStream2;
+use quote::{format_ident, quote, quote_spanned};
+use syn::{
+    Attribute, Data, DeriveInput, Field, Fields, GenericParam, Ident, LitStr, Variant,
+    ext::IdentExt, parse_macro_input, parse_quote, spanned::Spanned,
+};
+
+#[proc_macro_derive(ToDynJsonTreeValue, attributes(json_tree))]
+pub fn derive_to_dyn_json_tree_value(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    expand(input)
+        .unwrap_or_else(syn::Error::into_compile_error)
+        .into()
+}
+
+/// The options of a `#[json_tree(...)]` attribute.
+#[derive(Default)]
+struct Attrs {
+    rename: Option<LitStr>,
+    skip: bool,
+    display: bool,
+}
+
+impl Attrs {
+    fn parse(attrs: &[Attribute]) -> syn::Result<Self> {
+        let mut result = Attrs::default();
+        for attr in attrs
+            .iter()
+            .filter(|attr| attr.path().is_ident("json_tree"))
+        {
+            attr.parse_nested_meta(|meta| {
+                if meta.path.is_ident("rename") {
+                    result.rename = Some(meta.value()?.parse()?);
+                } else if meta.path.is_ident("skip") {
+                    result.skip = true;
+                } else if meta.path.is_ident("display") {
+                    result.display = true;
+                } else {
+                    return Err(meta.error(
+                        "unsupported `json_tree` attribute, expected `rename`, `skip` or `display`",
+                    ));
+                }
+                Ok(())
+            })?;
+        }
+        Ok(result)
+    }
+
+    fn parse_container(attrs: &[Attribute]) -> syn::Result<()> {
+        match attrs.iter().find(|attr| attr.path().is_ident("json_tree")) {
+            Some(attr) => Err(syn::Error::new(
+                attr.span(),
+                "`json_tree` attributes are only supported on fields and enum variants",
+            )),
+            None => Ok(()),
+        }
+    }
+
+    fn parse_variant(variant: &Variant) -> syn::Result<Self> {
+        let attrs = Self::parse(&variant.attrs)?;
+        if attrs.skip || attrs.display {
+            return Err(syn::Error::new(
+                variant.span(),
+                "only `rename` is supported on enum variants",
+            ));
+        }
+        Ok(attrs)
+    }
+
+    fn parse_field(field: &Field) -> syn::Result<Self> {
+        let attrs = Self::parse(&field.attrs)?;
+        if attrs.rename.is_some() && field.ident.is_none() {
+            return Err(syn::Error::new(
+                field.span(),
+                "`rename` is only supported on named fields",
+            ));
+        }
+        Ok(attrs)
+    }
+}
+
+/// A field that is shown in the tree.
+struct ShownField<'a> {
+    field: &'a Field,
+    attrs: Attrs,
+    /// The index of the field within its struct or variant, including skipped fields.
+    index: usize,
+}
+
+impl ShownField<'_> {
+    /// The name binding the field when matching an enum variant.
+    fn binding(&self) -> Ident {
+        format_ident!("__field{}", self.index)
+    }
+}
+
+fn shown_fields(fields: &Fields) -> syn::Result<Vec<ShownField<'_>>> {
+    let mut shown = vec![];
+    for (idx, field) in fields.iter().enumerate() {
+        let attrs = Attrs::parse_field(field)?;
+        if !attrs.skip {
+            shown.push(ShownField {
+                field,
+                attrs,
+                index: idx,
+            });
+        }
+    }
+    Ok(shown)
+}
+
+fn is_newtype(fields: &Fields) -> syn::Result<bool> {
+    match fields {
+        Fields::Unnamed(unnamed) if unnamed.unnamed.len() == 1 => {
+            let field = &unnamed.unnamed[0];
+            if Attrs::parse_field(field)?.skip {
+                return Err(syn::Error::new(
+                    field.span(),
+                    "the only field of a newtype cannot be skipped",
+                ));
+            }
+            Ok(true)
+        }
+        _ => Ok(false),
+    }
+}
+
+fn expand(input: DeriveInput) -> syn::Result<TokenStream2> {
+    Attrs::parse_container(&input.attrs)?;
+
+    if let Some(lifetime) = input.generics.lifetimes().next() {
+        return Err(syn::Error::new(
+            lifetime.span(),
+            "`ToDynJsonTreeValue` cannot be derived for types with lifetime parameters",
+        ));
+    }
+
+    let krate = quote!(::egui_json_tree);
+    let name = &input.ident;
+
+    let mut generics = input.generics.clone();
+    let has_type_params = generics
+        .params
+        .iter()
+        .any(|param| matches!(param, GenericParam::Type(_)));
+    if has_type_params {
+        for param in generics.type_params_mut() {
+            param.bounds.push(parse_quote!('static));
+        }
+        let where_clause = generics.make_where_clause();
+        for field in all_fields(&input.data) {
+            let attrs = Attrs::parse_field(field)?;
+            let ty = &field.ty;
+            if attrs.skip {
+                continue;
+            } else if attrs.display {
+                where_clause
+                    .predicates
+                    .push(parse_quote!(#ty: ::std::fmt::Display + 'static));
+            } else {
+                where_clause
+                    .predicates
+                    .push(parse_quote!(#ty: #krate::dyn_value::ToDynJsonTreeValue + 'static));
+            }
+        }
+    }
+    let (impl_generics, ty_generics, where_clause) = generics.split_for_impl();
+
+    let (to_value, is_expandable, variant_fields) = match &input.data {
+        Data::Struct(data) => {
+            let (to_value, is_expandable) = expand_struct(&data.fields)?;
+            (to_value, is_expandable, None)
+        }
+        Data::Enum(data) => expand_enum(data.variants.iter())?,
+        Data::Union(data) => {
+            return Err(syn::Error::new(
+                data.union_token.span(),
+                "`ToDynJsonTreeValue` cannot be derived for unions",
+            ));
+        }
+    };
+
+    let variant_fields_impl = variant_fields.map(|body| {
+        quote! {
+            #[automatically_derived]
+            impl #impl_generics #krate::dyn_value::ToDynJsonTreeVariantFields for #name #ty_generics #where_clause {
+                fn to_dyn_json_tree_variant_fields(
+                    &self,
+                ) -> #krate::value::JsonTreeValue<'_, dyn #krate::dyn_value::ToDynJsonTreeValue> {
+                    #body
+                }
+            }
+        }
+    });
+
+    Ok(quote! {
+        #[automatically_derived]
+        impl #impl_generics #krate::dyn_value::ToDynJsonTreeValue for #name #ty_generics #where_clause {
+            fn to_dyn_json_tree_value(
+                &self,
+            ) -> #krate::value::JsonTreeValue<'_, dyn #krate::dyn_value::ToDynJsonTreeValue> {
+                #to_value
+            }
+
+            fn is_expandable(&self) -> bool {
+                #is_expandable
+            }
+        }
+
+        #variant_fields_impl
+    })
+}
+
+fn all_fields(data: &Data) -> Vec<&Field> {
+    match data {
+        Data::Struct(data) => data.fields.iter().collect(),
+        Data::Enum(data) => data
+            .variants
+            .iter()
+            .flat_map(|variant| variant.fields.iter())
+            .collect(),
+        Data::Union(_) => vec![],
+    }
+}
+
+/// Converts a reference to a field to a `&dyn ToDynJsonTreeValue`, wrapping it in a `DisplayLeaf` for `display` fields.
+fn to_child(field: &ShownField, access: TokenStream2) -> TokenStream2 {
+    let krate = quote!(::egui_json_tree);
+    let span = field.field.ty.span();
+    if field.attrs.display {
+        quote_spanned!(span=> #krate::dyn_value::DisplayLeaf::new(#access) as &dyn #krate::dyn_value::ToDynJsonTreeValue)
+    } else {
+        quote_spanned!(span=> #access as &dyn #krate::dyn_value::ToDynJsonTreeValue)
+    }
+}
+
+/// Builds an array or object of the given fields, using `access` to reference each field.
+fn expand_fields(
+    fields: &Fields,
+    shown: &[ShownField],
+    access: impl Fn(&ShownField) -> TokenStream2,
+) -> TokenStream2 {
+    let krate = quote!(::egui_json_tree);
+    match fields {
+        Fields::Named(_) => {
+            let entries = shown.iter().map(|field| {
+                let key = field.attrs.rename.clone().unwrap_or_else(|| {
+                    let ident = field.field.ident.as_ref().expect("named field");
+                    LitStr::new(&ident.unraw().to_string(), ident.span())
+                });
+                let child = to_child(field, access(field));
+                quote!((#krate::pointer::JsonPointerSegment::Key(#key), #child))
+            });
+            quote! {
+                #krate::value::JsonTreeValue::Expandable(
+                    ::std::vec![#(#entries),*],
+                    #krate::value::ExpandableType::Object,
+                )
+            }
+        }
+        Fields::Unnamed(_) | Fields::Unit => {
+            let elems = shown.iter().enumerate().map(|(idx, field)| {
+                let child = to_child(field, access(field));
+                quote!((#krate::pointer::JsonPointerSegment::Index(#idx), #child))
+            });
+            quote! {
+                #krate::value::JsonTreeValue::Expandable(
+                    ::std::vec![#(#elems),*],
+                    #krate::value::ExpandableType::Array,
+                )
+            }
+        }
+    }
+}
+
+fn expand_struct(fields: &Fields) -> syn::Result<(TokenStream2, TokenStream2)> {
+    let krate = quote!(::egui_json_tree);
+
+    if let Fields::Unit = fields {
+        return Ok((
+            quote! {
+                #krate::value::JsonTreeValue::Base(self, &"null", #krate::value::BaseValueType::Null)
+            },
+            quote!(false),
+        ));
+    }
+
+    let shown = shown_fields(fields)?;
+
+    if is_newtype(fields)? {
+        let inner = to_child(&shown[0], quote!(&self.0));
+        return Ok((
+            quote!(#krate::dyn_value::ToDynJsonTreeValue::to_dyn_json_tree_value(#inner)),
+            quote!(#krate::dyn_value::ToDynJsonTreeValue::is_expandable(#inner)),
+        ));
+    }
+
+    let to_value = expand_fields(fields, &shown, |field| {
+        let member = match &field.field.ident {
+            Some(ident) => quote!(#ident),
+            None => {
+                let idx = syn::Index::from(field.index);
+                quote!(#idx)
+            }
+        };
+        quote!(&self.#member)
+    });
+    Ok((to_value, quote!(true)))
+}
+
+/// Returns the bodies of `to_dyn_json_tree_value` and `is_expandable`, and of `to_dyn_json_tree_variant_fields` if the enum has tuple or struct variants.
+fn expand_enum<'a>(
+    variants: impl Iterator<Item = &'a Variant>,
+) -> syn::Result<(TokenStream2, TokenStream2, Option<TokenStream2>)> {
+    let krate = quote!(::egui_json_tree);
+
+    let mut value_arms = vec![];
+    let mut expandable_arms = vec![];
+    let mut fields_arms = vec![];
+    let mut has_other_variants = false;
+
+    for variant in variants {
+        let attrs = Attrs::parse_variant(variant)?;
+        let ident = &variant.ident;
+        let key = attrs
+            .rename
+            .unwrap_or_else(|| LitStr::new(&ident.unraw().to_string(), ident.span()));
+
+        if let Fields::Unit = variant.fields {
+            value_arms.push(quote! {
+                Self::#ident => #krate::value::JsonTreeValue::Base(self, &#key, #krate::value::BaseValueType::String)
+            });
+            expandable_arms.push(quote!(Self::#ident => false));
+            has_other_variants = true;
+            continue;
+        }
+
+        let shown = shown_fields(&variant.fields)?;
+        let pattern = variant_pattern(variant, &shown);
+        expandable_arms.push(quote!(#pattern => true));
+
+        let inner = if is_newtype(&variant.fields)? {
+            has_other_variants = true;
+            to_child(&shown[0], quote!(__field0))
+        } else {
+            let fields = expand_fields(&variant.fields, &shown, |field| {
+                let binding = field.binding();
+                quote!(#binding)
+            });
+            fields_arms.push(quote!(#pattern => #fields));
+            quote! {
+                #krate::dyn_value::VariantFields::new(self) as &dyn #krate::dyn_value::ToDynJsonTreeValue
+            }
+        };
+        value_arms.push(quote! {
+            #pattern => #krate::value::JsonTreeValue::Expandable(
+                ::std::vec![(#krate::pointer::JsonPointerSegment::Key(#key), #inner)],
+                #krate::value::ExpandableType::Object,
+            )
+        });
+    }
+
+    if value_arms.is_empty() {
+        return Ok((quote!(match *self {}), quote!(match *self {}), None));
+    }
+
+    let variant_fields = (!fields_arms.is_empty()).then(|| {
+        let fallback = has_other_variants.then(|| {
+            quote!(_ => ::std::unreachable!("only tuple and struct variants have variant fields"))
+        });
+        quote! {
+            match self {
+                #(#fields_arms,)*
+                #fallback
+            }
+        }
+    });
+
+    Ok((
+        quote!(match self { #(#value_arms,)* }),
+        quote!(match self { #(#expandable_arms,)* }),
+        variant_fields,
+    ))
+}
+
+/// The pattern matching a tuple or struct variant, binding its shown fields and ignoring skipped ones.
+fn variant_pattern(variant: &Variant, shown: &[ShownField]) -> TokenStream2 {
+    let ident = &variant.ident;
+    let binding_for = |idx: usize| {
+        shown
+            .iter()
+            .find(|field| field.index == idx)
+            .map(ShownField::binding)
+    };
+    match &variant.fields {
+        Fields::Named(named) => {
+            let fields = named.named.iter().enumerate().map(|(idx, field)| {
+                let name = field.ident.as_ref().expect("named field");
+                match binding_for(idx) {
+                    Some(binding) => quote!(#name: #binding),
+                    None => quote!(#name: _),
+                }
+            });
+            quote!(Self::#ident { #(#fields),* })
+        }
+        Fields::Unnamed(unnamed) => {
+            let fields = (0..unnamed.unnamed.len()).map(|idx| match binding_for(idx) {
+                Some(binding) => quote!(#binding),
+                None => quote!(_),
+            });
+            quote!(Self::#ident(#(#fields),*))
+        }
+        Fields::Unit => quote!(Self::#ident),
+    }
+}